@@ -23,10 +23,22 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub dry_run: bool,
 
-    /// Overwrite existing files
+    /// Overwrite existing output files (never affects configuration)
     #[arg(long, global = true)]
     pub overwrite: bool,
 
+    /// Only print errors; hides progress bars and status messages
+    #[arg(long, global = true)]
+    pub quiet: bool,
+
+    /// Print results as JSON instead of styled text (implies --quiet)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Disable colors and progress bars (automatic when stdout isn't a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
     /// Output directory
     #[arg(short, long, global = true)]
     pub output_dir: Option<PathBuf>,
@@ -34,8 +46,30 @@ pub struct Cli {
     /// Custom config file
     #[arg(long, global = true)]
     pub config: Option<PathBuf>,
+
+    /// Named settings profile from the config file (flags given here still win)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Command to run before each file; a non-zero exit skips the file ({input}, {output}, {input_size})
+    #[arg(long, global = true)]
+    pub pre_hook: Option<String>,
+
+    /// Command to run after each successful compression ({input}, {output}, {input_size}, {output_size})
+    #[arg(long, global = true)]
+    pub post_hook: Option<String>,
+
+    /// Progress bar and status symbol style (overrides config progress_style)
+    #[arg(long, global = true, value_enum)]
+    pub progress_style: Option<ProgressTheme>,
+
+    /// Custom indicatif template for progress bars (overrides config progress_template)
+    #[arg(long, global = true)]
+    pub progress_template: Option<String>,
 }
 
+// Parsed once per run, so the size gap between subcommands costs nothing
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Compress video files
@@ -46,14 +80,18 @@ pub enum Commands {
         /// Output file (optional, will auto-generate if not provided)
         output: Option<PathBuf>,
 
-        /// Compression preset
-        #[arg(short, long, default_value = "medium")]
-        preset: VideoPreset,
+        /// Compression preset (defaults to config default_video_preset, then medium)
+        #[arg(short, long)]
+        preset: Option<VideoPreset>,
 
         /// Video codec
         #[arg(long)]
         codec: Option<VideoCodec>,
 
+        /// Remux without re-encoding (copies both video and audio streams)
+        #[arg(long, conflicts_with_all = ["codec", "audio_codec"])]
+        copy: bool,
+
         /// Constant Rate Factor (0-51, lower = better quality)
         #[arg(long)]
         crf: Option<u8>,
@@ -62,10 +100,18 @@ pub enum Commands {
         #[arg(long)]
         bitrate: Option<String>,
 
+        /// Aim for this output size (e.g., 25MB); computes the bitrate from the duration and forces two-pass
+        #[arg(long, value_name = "SIZE", conflicts_with_all = ["bitrate", "crf", "copy"])]
+        target_size: Option<bytesize::ByteSize>,
+
         /// Target resolution (e.g., "1920x1080", "720p")
         #[arg(long)]
         resolution: Option<String>,
 
+        /// Crop before scaling, as W:H:X:Y in source pixels (e.g., 1280:720:0:140)
+        #[arg(long, value_name = "W:H:X:Y")]
+        crop: Option<String>,
+
         /// Target framerate
         #[arg(long)]
         fps: Option<f32>,
@@ -93,6 +139,107 @@ pub enum Commands {
         /// Two-pass encoding for better quality
         #[arg(long)]
         two_pass: bool,
+
+        /// Produce byte-identical output for identical input and settings
+        #[arg(long)]
+        deterministic: bool,
+
+        /// Output container (for auto-generated output names)
+        #[arg(long)]
+        container: Option<VideoContainer>,
+
+        /// Convert variable frame rate input to constant frame rate
+        #[arg(long)]
+        cfr: bool,
+
+        /// Copy metadata and chapters from a reference file
+        #[arg(long)]
+        metadata_from: Option<PathBuf>,
+
+        /// Rotate the picture clockwise by re-encoding (90, 180 or 270)
+        #[arg(long, value_enum, conflicts_with = "rotate_metadata")]
+        rotate: Option<Rotation>,
+
+        /// Set display rotation metadata (clockwise degrees) without rotating pixels
+        #[arg(long)]
+        rotate_metadata: Option<Rotation>,
+
+        /// Codecs to retry with if the encoder is unavailable (e.g. "h265,h264")
+        #[arg(long, value_delimiter = ',')]
+        fallback_codec: Vec<VideoCodec>,
+
+        /// Force FFmpeg's input demuxer (e.g. "h264", "matroska") for extensionless or raw inputs
+        #[arg(long)]
+        input_format: Option<String>,
+
+        /// Load the base preset from a YAML/TOML preset file for this run (flags still override)
+        #[arg(long, value_name = "FILE", conflicts_with = "preset")]
+        preset_from: Option<PathBuf>,
+
+        /// Scene-change sensitivity for keyframe placement (0 disables, encoder default 40; libx264/libx265 only)
+        #[arg(long, value_name = "THRESHOLD", value_parser = clap::value_parser!(u8).range(0..=100))]
+        scene_cut: Option<u8>,
+
+        /// Fade in from black (and silence) over this many seconds
+        #[arg(long, value_name = "SECONDS")]
+        fade_in: Option<f64>,
+
+        /// Fade out to black (and silence) over this many seconds at the end
+        #[arg(long, value_name = "SECONDS")]
+        fade_out: Option<f64>,
+
+        /// Playback speed factor, e.g. 2.0 for twice as fast or 0.5 for half speed (audio tempo follows)
+        /// --start and --end are measured on the sped-up output, not the source
+        #[arg(long, value_name = "FACTOR")]
+        speed: Option<f64>,
+
+        /// Deinterlace old broadcast/DV footage with the yadif filter
+        #[arg(long)]
+        deinterlace: bool,
+
+        /// Encoder tuning for the content (libx264/libx265 only; film and stillimage are H.264 only)
+        #[arg(long, value_enum)]
+        tune: Option<Tune>,
+
+        /// Frames between keyframes (GOP size), e.g. 48 for 2-second HLS/DASH segments at 24 fps
+        #[arg(long, value_name = "FRAMES", value_parser = clap::value_parser!(u32).range(1..))]
+        keyframe_interval: Option<u32>,
+
+        /// Output pixel format (e.g. yuv420p for QuickTime and older hardware)
+        #[arg(long, value_name = "FORMAT")]
+        pix_fmt: Option<String>,
+
+        /// FFmpeg threads (0 lets FFmpeg decide; defaults to config ffmpeg_threads)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Correct the interlacing flags of the output (tff, bff or progressive)
+        #[arg(long, value_enum)]
+        field_order: Option<FieldOrder>,
+
+        /// Refuse inputs longer than this (seconds, MM:SS or HH:MM:SS)
+        #[arg(long, value_name = "TIME")]
+        max_duration: Option<String>,
+
+        /// Overlay a watermark image (e.g. a PNG logo) on the video
+        #[arg(long, value_name = "IMAGE")]
+        watermark: Option<PathBuf>,
+
+        /// Draw a text watermark on the video
+        #[arg(long, value_name = "TEXT")]
+        watermark_text: Option<String>,
+
+        /// Corner or center to place the watermark in
+        #[arg(long, value_enum, default_value = "bottom-right")]
+        watermark_position: WatermarkPosition,
+
+        /// Watermark opacity from 0.0 (invisible) to 1.0 (opaque)
+        #[arg(long, default_value = "1.0")]
+        watermark_opacity: f32,
+
+        /// Hardware encoder backend (uses e.g. h264_nvenc instead of libx264)
+        #[arg(long, value_enum, default_value = "none")]
+        hwaccel: HwAccel,
     },
 
     /// Compress image files
@@ -103,18 +250,30 @@ pub enum Commands {
         /// Output file (optional, will auto-generate if not provided)
         output: Option<PathBuf>,
 
-        /// Image quality (1-100)
-        #[arg(short, long, default_value = "85")]
-        quality: u8,
+        /// Image quality (1-100, defaults to preset or per-format config value)
+        #[arg(short, long)]
+        quality: Option<u8>,
 
         /// Output format
         #[arg(short, long)]
         format: Option<ImageFormat>,
 
-        /// Resize to specific dimensions (e.g., "800x600")
+        /// Crop to a region before resizing, as x,y,width,height (e.g., "100,50,800,600")
+        #[arg(long, value_name = "X,Y,W,H")]
+        crop: Option<String>,
+
+        /// Resize to specific dimensions or a percentage of the original (e.g., "800x600", "50%")
         #[arg(long)]
         resize: Option<String>,
 
+        /// How --resize treats the aspect ratio
+        #[arg(long, value_enum, default_value = "exact")]
+        resize_mode: ResizeMode,
+
+        /// Resampling filter for --resize, --max-width and --max-height
+        #[arg(long, value_enum, default_value = "lanczos3")]
+        filter: ResizeFilter,
+
         /// Maximum width (maintains aspect ratio)
         #[arg(long)]
         max_width: Option<u32>,
@@ -123,7 +282,7 @@ pub enum Commands {
         #[arg(long)]
         max_height: Option<u32>,
 
-        /// Enable optimization
+        /// Enable optimization (PNG: strongest compression, slower)
         #[arg(long)]
         optimize: bool,
 
@@ -135,9 +294,53 @@ pub enum Commands {
         #[arg(long)]
         lossless: bool,
 
-        /// Image preset (web, high, lossless)
+        /// Image preset (web, high, lossless; defaults to config default_image_preset)
         #[arg(short, long)]
         preset: Option<String>,
+
+        /// Encode at several qualities for comparison (e.g. "60,75,90")
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["quality", "output"])]
+        quality_range: Vec<u8>,
+
+        /// Write an HTML page comparing the --quality-range outputs
+        #[arg(long, requires = "quality_range")]
+        comparison_html: bool,
+
+        /// JPEG chroma subsampling (default 420; 444 keeps colored text sharp)
+        #[arg(long)]
+        chroma: Option<ChromaSubsampling>,
+
+        /// Force the input decoder (e.g. "png") for files without a usable extension
+        #[arg(long)]
+        input_format: Option<String>,
+
+        /// Remove EXIF, XMP, ICC profile, embedded thumbnails and comments
+        #[arg(long)]
+        strip: bool,
+
+        /// Never enlarge with --resize; keep the original size if it is already smaller
+        #[arg(long)]
+        downscale_only: bool,
+
+        /// Load the base preset from a YAML/TOML preset file for this run (flags still override)
+        #[arg(long, value_name = "FILE", conflicts_with = "preset")]
+        preset_from: Option<PathBuf>,
+
+        /// Largest allowed output size; searches for the highest quality that fits (JPEG, AVIF, lossy WebP)
+        #[arg(long, value_name = "SIZE", conflicts_with = "quality_range")]
+        max_bytes: Option<bytesize::ByteSize>,
+
+        /// Remove EXIF (including GPS), XMP, IPTC and comments but keep the color profile
+        #[arg(long)]
+        strip_metadata: bool,
+
+        /// Aim for this output size; picks the quality that lands closest under it
+        #[arg(long, value_name = "SIZE", conflicts_with_all = ["max_bytes", "quality_range"])]
+        target_size: Option<bytesize::ByteSize>,
+
+        /// Don't rotate or flip images to match their EXIF orientation tag
+        #[arg(long = "no-auto-orient", action = clap::ArgAction::SetFalse)]
+        auto_orient: bool,
     },
 
     /// Batch process files in a directory
@@ -149,6 +352,10 @@ pub enum Commands {
         #[arg(short, long, default_value = "*")]
         pattern: String,
 
+        /// Skip files whose name matches this glob (repeatable, e.g. --exclude "*_compressed.mp4")
+        #[arg(long)]
+        exclude: Vec<String>,
+
         /// Process videos
         #[arg(long)]
         videos: bool,
@@ -161,17 +368,264 @@ pub enum Commands {
         #[arg(short, long)]
         recursive: bool,
 
-        /// Video preset for batch processing
-        #[arg(long, default_value = "medium")]
-        video_preset: VideoPreset,
+        /// Video preset for batch processing (defaults to config default_video_preset, then medium)
+        #[arg(long)]
+        video_preset: Option<VideoPreset>,
+
+        /// Image quality for batch processing (defaults to per-format config value)
+        #[arg(long)]
+        image_quality: Option<u8>,
+
+        /// Image preset for batch processing (web, high, lossless; defaults to config default_image_preset)
+        #[arg(long)]
+        image_preset: Option<String>,
+
+        /// Delete each original after it compresses to a smaller file
+        #[arg(long)]
+        delete_originals: bool,
+
+        /// Write per-file results to a report file; .json or .csv picks the format
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Skip files whose output already exists
+        #[arg(long)]
+        skip_existing: bool,
+
+        /// Estimated memory ceiling for in-flight image decodes (e.g., 2GiB); images wait for room before starting
+        #[arg(long)]
+        max_memory: Option<bytesize::ByteSize>,
+
+        /// Only process files whose output is missing or older than the input
+        #[arg(long)]
+        only_stale: bool,
+
+        /// Order files are processed in
+        #[arg(long, value_enum, default_value = "name")]
+        sort: BatchSort,
+
+        /// Codecs to retry video encodes with if the encoder is unavailable
+        #[arg(long, value_delimiter = ',')]
+        fallback_codec: Vec<VideoCodec>,
+
+        /// Maximum parallel jobs (defaults to config parallel_jobs)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+    },
+
+    /// Watch a directory and compress files as they are added
+    Watch {
+        /// Directory to watch
+        directory: PathBuf,
+
+        /// File pattern (e.g., "*.mp4", "*.jpg")
+        #[arg(short, long, default_value = "*")]
+        pattern: String,
+
+        /// Compress videos
+        #[arg(long)]
+        videos: bool,
+
+        /// Compress images
+        #[arg(long)]
+        images: bool,
+
+        /// Watch subdirectories too
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Video preset (defaults to config default_video_preset, then medium)
+        #[arg(long)]
+        video_preset: Option<VideoPreset>,
+
+        /// Image quality (defaults to per-format config value)
+        #[arg(long)]
+        image_quality: Option<u8>,
+
+        /// Codecs to retry video encodes with if the encoder is unavailable
+        #[arg(long, value_delimiter = ',')]
+        fallback_codec: Vec<VideoCodec>,
+    },
+
+    /// Predict compressed size without encoding
+    Estimate {
+        /// Input video or image file
+        input: PathBuf,
+
+        /// Video compression preset (defaults to config default_video_preset, then medium)
+        #[arg(short, long)]
+        preset: Option<VideoPreset>,
+
+        /// Video codec
+        #[arg(long)]
+        codec: Option<VideoCodec>,
+
+        /// Constant Rate Factor (0-51, lower = better quality)
+        #[arg(long)]
+        crf: Option<u8>,
+
+        /// Image quality (1-100, defaults to per-format config value)
+        #[arg(short, long)]
+        quality: Option<u8>,
+
+        /// Image output format
+        #[arg(short, long)]
+        format: Option<ImageFormat>,
+    },
 
-        /// Image quality for batch processing
-        #[arg(long, default_value = "85")]
-        image_quality: u8,
+    /// Show a media file's resolution, duration, codecs and bitrate
+    Probe {
+        /// Input video, audio or image file
+        input: PathBuf,
+    },
+
+    /// Generate a contact sheet (thumbnail grid) from a video
+    ContactSheet {
+        /// Input video file
+        input: PathBuf,
+
+        /// Output image file (optional, will auto-generate if not provided)
+        output: Option<PathBuf>,
+
+        /// Number of tile columns
+        #[arg(long, default_value = "4")]
+        columns: u32,
+
+        /// Number of tile rows
+        #[arg(long, default_value = "4")]
+        rows: u32,
+
+        /// Width of each tile in pixels
+        #[arg(long, default_value = "320")]
+        tile_width: u32,
 
-        /// Maximum parallel jobs
-        #[arg(short, long, default_value = "4")]
-        jobs: usize,
+        /// Overlay the timestamp on each tile
+        #[arg(long)]
+        timestamps: bool,
+
+        /// Font file for timestamp labels (auto-detected if not provided)
+        #[arg(long, requires = "timestamps")]
+        font: Option<PathBuf>,
+    },
+
+    /// Arrange several images into a single grid image
+    Collage {
+        /// Input images (at least two), placed left to right, top to bottom
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        /// Output image file (defaults to `<first input>_collage.<format>`)
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Number of tile columns
+        #[arg(long, default_value = "3")]
+        columns: u32,
+
+        /// Size of each tile; images are scaled to fit and centered (e.g., 400x300)
+        #[arg(long, default_value = "400x400")]
+        tile_size: String,
+
+        /// Gap between tiles and around the edges, in pixels
+        #[arg(long, default_value = "10")]
+        padding: u32,
+
+        /// Background color as hex RGB or RGBA (e.g., #ffffff, #00000080)
+        #[arg(long, default_value = "#ffffff")]
+        background: String,
+
+        /// Output image format
+        #[arg(short, long, value_enum, default_value = "jpeg")]
+        format: ImageFormat,
+
+        /// Image quality (1-100, defaults to the configured quality for the format)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: Option<u8>,
+    },
+
+    /// Extract one frame every N seconds from a video into numbered images
+    Frames {
+        /// Input video file
+        input: PathBuf,
+
+        /// Seconds between extracted frames
+        #[arg(short, long, default_value = "1")]
+        interval: f64,
+
+        /// Image format of the extracted frames
+        #[arg(short, long, value_enum, default_value = "jpeg")]
+        format: ImageFormat,
+
+        /// Image quality (1-100, defaults to the configured quality for the format)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: Option<u8>,
+    },
+
+    /// Extract a single frame from a video as an image
+    Thumbnail {
+        /// Input video file
+        input: PathBuf,
+
+        /// Output image file (optional, will auto-generate if not provided)
+        output: Option<PathBuf>,
+
+        /// Timestamp of the frame (e.g., 00:00:10, 1:30 or 90.5)
+        #[arg(long, default_value = "0")]
+        at: String,
+
+        /// Image format of the thumbnail
+        #[arg(short, long, value_enum, default_value = "jpeg")]
+        format: ImageFormat,
+
+        /// Image quality (1-100, defaults to the configured quality for the format)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: Option<u8>,
+    },
+
+    /// Extract the audio track of a video into its own file
+    ExtractAudio {
+        /// Input video file
+        input: PathBuf,
+
+        /// Output audio file (optional, will auto-generate if not provided)
+        output: Option<PathBuf>,
+
+        /// Audio codec (defaults to AAC in an .m4a file; copy keeps the original stream)
+        #[arg(long)]
+        audio_codec: Option<AudioCodec>,
+
+        /// Audio bitrate (e.g., "128K", "256K"; defaults to 192K)
+        #[arg(long)]
+        audio_bitrate: Option<String>,
+    },
+
+    /// Turn an audio file into a video with a waveform or spectrum visualization
+    Visualize {
+        /// Input audio file
+        input: PathBuf,
+
+        /// Output video file (optional, will auto-generate if not provided)
+        output: Option<PathBuf>,
+
+        /// Visualization style
+        #[arg(long, value_enum, default_value = "waves")]
+        visual: AudioVisual,
+
+        /// Video resolution (e.g., 1920x1080, 720p)
+        #[arg(short, long, default_value = "1280x720")]
+        resolution: String,
+
+        /// Compression preset (defaults to config default_video_preset, then medium)
+        #[arg(short, long)]
+        preset: Option<VideoPreset>,
+
+        /// Video codec
+        #[arg(short, long)]
+        codec: Option<VideoCodec>,
+
+        /// Constant Rate Factor (0-51, lower = better quality)
+        #[arg(long)]
+        crf: Option<u8>,
     },
 
     /// Manage compression presets
@@ -180,9 +634,38 @@ pub enum Commands {
         action: PresetAction,
     },
 
+    /// Compare an original file with its compressed output
+    Compare {
+        /// Original file
+        input: PathBuf,
+
+        /// Compressed file
+        output: PathBuf,
+    },
+
+    /// Write a commented default config file to start customizing from
+    Init {
+        /// Where to write the config (defaults to the standard config location)
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check a config file for invalid preset values
+    Validate {
+        /// Config file to check (defaults to --config, then the standard config location)
+        path: Option<PathBuf>,
+    },
+
     /// Show system information and dependencies
     Info,
 
+    /// Show version and build details for bug reports
+    Version,
+
     /// Generate shell completion scripts
     Completions {
         /// Shell to generate completions for
@@ -194,6 +677,7 @@ pub enum Commands {
 #[derive(Subcommand)]
 pub enum PresetAction {
     /// List all available presets
+    #[command(visible_alias = "ls")]
     List,
 
     /// Show details of a specific preset
@@ -209,12 +693,20 @@ pub enum PresetAction {
 
         /// Preset configuration file
         config: PathBuf,
+
+        /// Replace an existing preset without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Delete a custom preset
     Delete {
         /// Preset name
         name: String,
+
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
     },
 }
 
@@ -234,7 +726,7 @@ pub enum VideoPreset {
     Custom,
 }
 
-#[derive(ValueEnum, Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(ValueEnum, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum VideoCodec {
     /// H.264 (widely compatible)
     H264,
@@ -244,9 +736,188 @@ pub enum VideoCodec {
     Vp9,
     /// AV1 (next-gen codec)
     Av1,
+    /// Copy original stream without re-encoding
+    Copy,
 }
 
-#[derive(ValueEnum, Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(ValueEnum, Clone, Debug)]
+pub enum VideoContainer {
+    /// MP4 container
+    Mp4,
+    /// Matroska container
+    Mkv,
+    /// WebM container
+    Webm,
+    /// QuickTime container
+    Mov,
+    /// AVI container
+    Avi,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Rotation {
+    /// 90 degrees clockwise
+    #[value(name = "90")]
+    Cw90,
+    /// 180 degrees
+    #[value(name = "180")]
+    Cw180,
+    /// 270 degrees clockwise
+    #[value(name = "270")]
+    Cw270,
+}
+
+impl Rotation {
+    /// Returns the clockwise rotation in degrees
+    pub fn degrees(self) -> i32 {
+        match self {
+            Rotation::Cw90 => 90,
+            Rotation::Cw180 => 180,
+            Rotation::Cw270 => 270,
+        }
+    }
+
+    /// Returns the FFmpeg filters that rotate the pixels clockwise
+    pub fn transpose_filter(self) -> &'static str {
+        match self {
+            Rotation::Cw90 => "transpose=1",
+            Rotation::Cw180 => "hflip,vflip",
+            Rotation::Cw270 => "transpose=2",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum AudioVisual {
+    /// Oscilloscope-style waveform (showwaves)
+    Waves,
+    /// Scrolling frequency spectrum (showspectrum)
+    Spectrum,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum WatermarkPosition {
+    /// Top-left corner
+    TopLeft,
+    /// Top-right corner
+    TopRight,
+    /// Bottom-left corner
+    BottomLeft,
+    /// Bottom-right corner
+    BottomRight,
+    /// Centered
+    Center,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressTheme {
+    /// Unicode unless the locale isn't UTF-8
+    #[default]
+    Auto,
+    /// Unicode spinners and symbols
+    Unicode,
+    /// ASCII-only output for minimal terminals and CI logs
+    Ascii,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum FieldOrder {
+    /// Interlaced, top field first
+    Tff,
+    /// Interlaced, bottom field first
+    Bff,
+    /// Progressive (not interlaced)
+    Progressive,
+}
+
+impl FieldOrder {
+    /// Returns the mode name used by FFmpeg's setfield filter
+    pub fn setfield_mode(self) -> &'static str {
+        match self {
+            FieldOrder::Tff => "tff",
+            FieldOrder::Bff => "bff",
+            FieldOrder::Progressive => "prog",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Tune {
+    /// Live-action film
+    Film,
+    /// Cartoons and anime
+    Animation,
+    /// Keep film grain
+    Grain,
+    /// Slideshow-like content
+    Stillimage,
+    /// Easier to decode on slow devices
+    Fastdecode,
+    /// Low-latency streaming and screencasts
+    Zerolatency,
+    /// Optimize for PSNR scores
+    Psnr,
+    /// Optimize for SSIM scores
+    Ssim,
+}
+
+impl Tune {
+    /// Checks whether the codec's encoder accepts this tune
+    /// libx265 has no film or stillimage tune; other codecs have no -tune at all
+    pub fn supports(self, codec: &VideoCodec) -> bool {
+        match codec {
+            VideoCodec::H264 => true,
+            VideoCodec::H265 => !matches!(self, Tune::Film | Tune::Stillimage),
+            VideoCodec::Vp9 | VideoCodec::Av1 | VideoCodec::Copy => false,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum HwAccel {
+    /// Software encoding
+    #[default]
+    None,
+    /// NVIDIA NVENC
+    Nvenc,
+    /// Intel Quick Sync Video
+    Qsv,
+    /// VA-API (Intel/AMD on Linux)
+    Vaapi,
+}
+
+impl HwAccel {
+    /// Returns the FFmpeg hardware encoder for a codec, or None if this backend has none
+    pub fn encoder(self, codec: &VideoCodec) -> Option<&'static str> {
+        match (self, codec) {
+            (HwAccel::Nvenc, VideoCodec::H264) => Some("h264_nvenc"),
+            (HwAccel::Nvenc, VideoCodec::H265) => Some("hevc_nvenc"),
+            (HwAccel::Nvenc, VideoCodec::Av1) => Some("av1_nvenc"),
+            (HwAccel::Qsv, VideoCodec::H264) => Some("h264_qsv"),
+            (HwAccel::Qsv, VideoCodec::H265) => Some("hevc_qsv"),
+            (HwAccel::Qsv, VideoCodec::Vp9) => Some("vp9_qsv"),
+            (HwAccel::Qsv, VideoCodec::Av1) => Some("av1_qsv"),
+            (HwAccel::Vaapi, VideoCodec::H264) => Some("h264_vaapi"),
+            (HwAccel::Vaapi, VideoCodec::H265) => Some("hevc_vaapi"),
+            (HwAccel::Vaapi, VideoCodec::Vp9) => Some("vp9_vaapi"),
+            (HwAccel::Vaapi, VideoCodec::Av1) => Some("av1_vaapi"),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for FFmpeg's -hwaccel decoding option
+    pub fn decoder(self) -> Option<&'static str> {
+        match self {
+            HwAccel::None => None,
+            HwAccel::Nvenc => Some("cuda"),
+            HwAccel::Qsv => Some("qsv"),
+            HwAccel::Vaapi => Some("vaapi"),
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum AudioCodec {
     /// AAC (widely compatible)
     Aac,
@@ -270,6 +941,80 @@ pub enum ImageFormat {
     Avif,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum BatchSort {
+    /// By path
+    #[default]
+    Name,
+    /// Largest first, so long encodes start early
+    Size,
+    /// Oldest modification time first
+    Modified,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResizeMode {
+    /// Stretch to exactly the given size
+    #[default]
+    Exact,
+    /// Scale to fit within the size, keeping the aspect ratio
+    Fit,
+    /// Scale to cover the size, keeping the aspect ratio, then crop the center
+    Fill,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResizeFilter {
+    /// Nearest neighbor (fastest, keeps pixel art crisp)
+    Nearest,
+    /// Bilinear
+    Triangle,
+    /// Bicubic, sharp downscaling
+    CatmullRom,
+    /// Gaussian, soft results
+    Gaussian,
+    /// Lanczos with window 3 (best quality, slowest)
+    #[default]
+    Lanczos3,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ChromaSubsampling {
+    /// 4:2:0, quarter-resolution color (smallest, best for photos)
+    #[default]
+    #[value(name = "420")]
+    Yuv420,
+    /// 4:2:2, half-resolution color horizontally
+    #[value(name = "422")]
+    Yuv422,
+    /// 4:4:4, full-resolution color (screenshots, colored text)
+    #[value(name = "444")]
+    Yuv444,
+}
+
+impl From<ResizeFilter> for image::imageops::FilterType {
+    fn from(filter: ResizeFilter) -> Self {
+        match filter {
+            ResizeFilter::Nearest => Self::Nearest,
+            ResizeFilter::Triangle => Self::Triangle,
+            ResizeFilter::CatmullRom => Self::CatmullRom,
+            ResizeFilter::Gaussian => Self::Gaussian,
+            ResizeFilter::Lanczos3 => Self::Lanczos3,
+        }
+    }
+}
+
+impl std::fmt::Display for HwAccel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HwAccel::None => write!(f, "none"),
+            HwAccel::Nvenc => write!(f, "nvenc"),
+            HwAccel::Qsv => write!(f, "qsv"),
+            HwAccel::Vaapi => write!(f, "vaapi"),
+        }
+    }
+}
+
 impl std::fmt::Display for VideoPreset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -290,6 +1035,19 @@ impl std::fmt::Display for VideoCodec {
             VideoCodec::H265 => write!(f, "libx265"),
             VideoCodec::Vp9 => write!(f, "libvpx-vp9"),
             VideoCodec::Av1 => write!(f, "libaom-av1"),
+            VideoCodec::Copy => write!(f, "copy"),
+        }
+    }
+}
+
+impl std::fmt::Display for VideoContainer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoContainer::Mp4 => write!(f, "mp4"),
+            VideoContainer::Mkv => write!(f, "mkv"),
+            VideoContainer::Webm => write!(f, "webm"),
+            VideoContainer::Mov => write!(f, "mov"),
+            VideoContainer::Avi => write!(f, "avi"),
         }
     }
 }
@@ -315,3 +1073,38 @@ impl std::fmt::Display for ImageFormat {
         }
     }
 }
+
+impl std::fmt::Display for FieldOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldOrder::Tff => write!(f, "tff"),
+            FieldOrder::Bff => write!(f, "bff"),
+            FieldOrder::Progressive => write!(f, "progressive"),
+        }
+    }
+}
+
+impl std::fmt::Display for Tune {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tune::Film => write!(f, "film"),
+            Tune::Animation => write!(f, "animation"),
+            Tune::Grain => write!(f, "grain"),
+            Tune::Stillimage => write!(f, "stillimage"),
+            Tune::Fastdecode => write!(f, "fastdecode"),
+            Tune::Zerolatency => write!(f, "zerolatency"),
+            Tune::Psnr => write!(f, "psnr"),
+            Tune::Ssim => write!(f, "ssim"),
+        }
+    }
+}
+
+impl std::fmt::Display for ChromaSubsampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChromaSubsampling::Yuv420 => write!(f, "4:2:0"),
+            ChromaSubsampling::Yuv422 => write!(f, "4:2:2"),
+            ChromaSubsampling::Yuv444 => write!(f, "4:4:4"),
+        }
+    }
+}
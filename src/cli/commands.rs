@@ -4,22 +4,26 @@
 //! including video compression, image compression, batch processing, etc.
 
 use crate::compression::{
-    BatchOptions, BatchProcessor, ImageCompressionOptions, ImageCompressor,
-    VideoCompressionOptions, VideoCompressor,
+    AudioExtractor, AudioVisualizer, BatchOptions, BatchProcessor, CollageOptions,
+    ContactSheetOptions, CropRegion, DirectoryWatcher, EstimateOptions, ExtractAudioOptions,
+    FrameExtractionOptions, ImageCompressionOptions, ImageCompressor, SizeEstimator,
+    ThumbnailGenerator, ThumbnailOptions, VideoCompressionOptions, VideoCompressor,
+    VisualizeOptions, verify_video_encoders,
 };
-use crate::core::{CompressError, Config, Result};
-use crate::ui::progress::{print_error, print_success};
+use crate::core::{BatchReport, CompressError, CompressionReport, Config, ReportFormat, Result};
+use crate::ui::progress::{print_error, print_info, print_success, print_warning};
 use crate::utils;
 use clap::CommandFactory;
 use clap_complete::{Shell, generate};
+use serde::Serialize;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Parameters for video compression command
 pub struct VideoCommandParams {
     pub input: PathBuf,
     pub output: Option<PathBuf>,
-    pub preset: crate::cli::args::VideoPreset,
+    pub preset: Option<crate::cli::args::VideoPreset>,
     pub codec: Option<crate::cli::args::VideoCodec>,
     pub crf: Option<u8>,
     pub bitrate: Option<String>,
@@ -31,15 +35,46 @@ pub struct VideoCommandParams {
     pub start: Option<String>,
     pub end: Option<String>,
     pub two_pass: bool,
+    pub deterministic: bool,
+    pub container: Option<crate::cli::args::VideoContainer>,
+    pub cfr: bool,
+    pub metadata_from: Option<PathBuf>,
+    pub rotate_metadata: Option<crate::cli::args::Rotation>,
+    pub fallback_codec: Vec<crate::cli::args::VideoCodec>,
+    pub input_format: Option<String>,
+    pub preset_from: Option<PathBuf>,
+    pub scene_cut: Option<u8>,
+    pub fade_in: Option<f64>,
+    pub fade_out: Option<f64>,
+    pub speed: Option<f64>,
+    pub deinterlace: bool,
+    pub tune: Option<crate::cli::args::Tune>,
+    pub keyframe_interval: Option<u32>,
+    pub pix_fmt: Option<String>,
+    pub threads: Option<usize>,
+    pub field_order: Option<crate::cli::args::FieldOrder>,
+    pub max_duration: Option<String>,
+    pub watermark: Option<PathBuf>,
+    pub watermark_text: Option<String>,
+    pub watermark_position: crate::cli::args::WatermarkPosition,
+    pub watermark_opacity: f32,
+    pub hwaccel: crate::cli::args::HwAccel,
+    pub copy: bool,
+    pub target_size: Option<bytesize::ByteSize>,
+    pub crop: Option<String>,
+    pub rotate: Option<crate::cli::args::Rotation>,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
+    pub json: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
 }
 
 /// Parameters for image compression command
 pub struct ImageCommandParams {
     pub input: PathBuf,
     pub output: Option<PathBuf>,
-    pub quality: u8,
+    pub quality: Option<u8>,
     pub format: Option<crate::cli::args::ImageFormat>,
     pub resize: Option<String>,
     pub max_width: Option<u32>,
@@ -48,8 +83,25 @@ pub struct ImageCommandParams {
     pub progressive: bool,
     pub lossless: bool,
     pub preset: Option<String>,
+    pub quality_range: Vec<u8>,
+    pub comparison_html: bool,
+    pub chroma: Option<crate::cli::args::ChromaSubsampling>,
+    pub input_format: Option<String>,
+    pub strip: bool,
+    pub downscale_only: bool,
+    pub preset_from: Option<PathBuf>,
+    pub max_bytes: Option<bytesize::ByteSize>,
+    pub strip_metadata: bool,
+    pub target_size: Option<bytesize::ByteSize>,
+    pub auto_orient: bool,
+    pub resize_mode: crate::cli::args::ResizeMode,
+    pub filter: crate::cli::args::ResizeFilter,
+    pub crop: Option<String>,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
+    pub json: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
 }
 
 /// Parameters for batch processing command
@@ -59,9 +111,118 @@ pub struct BatchCommandParams {
     pub videos: bool,
     pub images: bool,
     pub recursive: bool,
-    pub video_preset: crate::cli::args::VideoPreset,
-    pub image_quality: u8,
-    pub jobs: usize,
+    pub video_preset: Option<crate::cli::args::VideoPreset>,
+    pub image_quality: Option<u8>,
+    pub image_preset: Option<String>,
+    pub delete_originals: bool,
+    pub report: Option<PathBuf>,
+    pub skip_existing: bool,
+    pub exclude: Vec<String>,
+    pub max_memory: Option<bytesize::ByteSize>,
+    pub only_stale: bool,
+    pub sort: crate::cli::args::BatchSort,
+    pub fallback_codec: Vec<crate::cli::args::VideoCodec>,
+    pub jobs: Option<usize>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+    pub json: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
+}
+
+/// Parameters for watch command
+pub struct WatchCommandParams {
+    pub directory: PathBuf,
+    pub pattern: String,
+    pub videos: bool,
+    pub images: bool,
+    pub recursive: bool,
+    pub video_preset: Option<crate::cli::args::VideoPreset>,
+    pub image_quality: Option<u8>,
+    pub fallback_codec: Vec<crate::cli::args::VideoCodec>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
+}
+
+/// Parameters for size estimate command
+pub struct EstimateCommandParams {
+    pub input: PathBuf,
+    pub preset: Option<crate::cli::args::VideoPreset>,
+    pub codec: Option<crate::cli::args::VideoCodec>,
+    pub crf: Option<u8>,
+    pub quality: Option<u8>,
+    pub format: Option<crate::cli::args::ImageFormat>,
+}
+
+/// Parameters for contact sheet command
+pub struct ContactSheetCommandParams {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub columns: u32,
+    pub rows: u32,
+    pub tile_width: u32,
+    pub timestamps: bool,
+    pub font: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+/// Parameters for collage command
+pub struct CollageCommandParams {
+    pub inputs: Vec<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub columns: u32,
+    pub tile_size: String,
+    pub padding: u32,
+    pub background: String,
+    pub format: crate::cli::args::ImageFormat,
+    pub quality: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+/// Parameters for frames command
+pub struct FramesCommandParams {
+    pub input: PathBuf,
+    pub interval: f64,
+    pub format: crate::cli::args::ImageFormat,
+    pub quality: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+/// Parameters for thumbnail command
+pub struct ThumbnailCommandParams {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub at: String,
+    pub format: crate::cli::args::ImageFormat,
+    pub quality: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+/// Parameters for extract-audio command
+pub struct ExtractAudioCommandParams {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub audio_codec: Option<crate::cli::args::AudioCodec>,
+    pub audio_bitrate: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+/// Parameters for visualize command
+pub struct VisualizeCommandParams {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub visual: crate::cli::args::AudioVisual,
+    pub resolution: String,
+    pub preset: Option<crate::cli::args::VideoPreset>,
+    pub codec: Option<crate::cli::args::VideoCodec>,
+    pub crf: Option<u8>,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
 }
@@ -77,9 +238,9 @@ pub async fn handle_video_command(
     check_ffmpeg_dependency()?;
 
     let options = VideoCompressionOptions {
-        input: params.input,
+        input: params.input.clone(),
         output: params.output,
-        preset: params.preset,
+        preset: config.resolve_video_preset(params.preset)?,
         codec: params.codec,
         crf: params.crf,
         bitrate: params.bitrate,
@@ -91,14 +252,48 @@ pub async fn handle_video_command(
         start: params.start,
         end: params.end,
         two_pass: params.two_pass,
+        deterministic: params.deterministic,
+        container: params.container,
+        cfr: params.cfr,
+        metadata_from: params.metadata_from,
+        rotate_metadata: params.rotate_metadata,
+        fallback_codec: params.fallback_codec,
+        input_format: params.input_format,
+        preset_from: params.preset_from,
+        scene_cut: params.scene_cut,
+        fade_in: params.fade_in,
+        fade_out: params.fade_out,
+        speed: params.speed,
+        deinterlace: params.deinterlace,
+        tune: params.tune,
+        keyframe_interval: params.keyframe_interval,
+        pix_fmt: params.pix_fmt,
+        threads: params.threads,
+        field_order: params.field_order,
+        max_duration: params.max_duration,
+        watermark: params.watermark,
+        watermark_text: params.watermark_text,
+        watermark_position: params.watermark_position,
+        watermark_opacity: params.watermark_opacity,
+        hwaccel: params.hwaccel,
+        copy: params.copy,
+        target_size: params.target_size,
+        crop: params.crop,
+        rotate: params.rotate,
         output_dir: params.output_dir,
         overwrite: params.overwrite,
+        pre_hook: params.pre_hook,
+        post_hook: params.post_hook,
     };
 
     let compressor = VideoCompressor::new(config, dry_run, verbose);
+    compressor.check_encoder_available(&options)?;
     let output_path = compressor.compress(options).await?;
 
-    if !dry_run {
+    if params.json {
+        let report = CompressionReport::new(&params.input, &output_path, dry_run)?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !dry_run {
         print_success(&format!("Video saved to: {}", output_path.display()));
     }
 
@@ -113,7 +308,7 @@ pub async fn handle_image_command(
     verbose: bool,
 ) -> Result<()> {
     let options = ImageCompressionOptions {
-        input: params.input,
+        input: params.input.clone(),
         output: params.output,
         quality: params.quality,
         format: params.format,
@@ -124,14 +319,39 @@ pub async fn handle_image_command(
         progressive: params.progressive,
         lossless: params.lossless,
         preset: params.preset,
+        chroma: params.chroma,
+        input_format: params.input_format,
+        strip: params.strip,
+        downscale_only: params.downscale_only,
+        preset_from: params.preset_from,
+        max_bytes: params.max_bytes,
+        strip_metadata: params.strip_metadata,
+        target_size: params.target_size,
+        auto_orient: params.auto_orient,
+        resize_mode: params.resize_mode,
+        filter: params.filter,
+        crop: params.crop.as_deref().map(CropRegion::parse).transpose()?,
         output_dir: params.output_dir,
         overwrite: params.overwrite,
+        pre_hook: params.pre_hook,
+        post_hook: params.post_hook,
     };
 
     let compressor = ImageCompressor::new(config, dry_run, verbose);
+
+    if !params.quality_range.is_empty() {
+        compressor
+            .quality_sweep(options, &params.quality_range, params.comparison_html)
+            .await?;
+        return Ok(());
+    }
+
     let output_path = compressor.compress(options).await?;
 
-    if !dry_run {
+    if params.json {
+        let report = CompressionReport::new(&params.input, &output_path, dry_run)?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !dry_run {
         print_success(&format!("Image saved to: {}", output_path.display()));
     }
 
@@ -152,9 +372,33 @@ pub async fn handle_batch_command(
         ));
     }
 
+    let video_preset = config.resolve_video_preset(params.video_preset)?;
+
+    if let Some(name) = &params.image_preset
+        && config.get_image_preset(name).is_none()
+    {
+        return Err(CompressError::config(format!(
+            "Image preset '{}' not found",
+            name
+        )));
+    }
+
+    // Catch a bad report extension before spending time on the batch
+    let report_format = params
+        .report
+        .as_deref()
+        .map(ReportFormat::from_path)
+        .transpose()?;
+
     // Check dependencies based on what we're processing
     if params.videos {
         check_ffmpeg_dependency()?;
+        verify_video_encoders(
+            &config,
+            &video_preset,
+            &params.fallback_codec,
+            utils::check_ffmpeg_encoder,
+        )?;
     }
 
     let options = BatchOptions {
@@ -163,26 +407,502 @@ pub async fn handle_batch_command(
         videos: params.videos,
         images: params.images,
         recursive: params.recursive,
-        video_preset: params.video_preset,
+        video_preset,
         image_quality: params.image_quality,
-        jobs: params.jobs,
+        image_preset: params.image_preset,
+        delete_originals: params.delete_originals,
+        skip_existing: params.skip_existing,
+        exclude: params.exclude,
+        max_memory: params.max_memory,
+        only_stale: params.only_stale,
+        sort: params.sort,
+        fallback_codec: params.fallback_codec,
+        jobs: config.resolve_parallel_jobs(params.jobs),
         output_dir: params.output_dir,
         overwrite: params.overwrite,
+        pre_hook: params.pre_hook,
+        post_hook: params.post_hook,
     };
 
     let processor = BatchProcessor::new(config, dry_run, verbose);
     let results = processor.process_directory(options).await?;
 
-    if !dry_run && results.total_files() > 0 {
+    let processed = results.total_files();
+    let report = BatchReport {
+        files: [results.videos, results.images].concat(),
+        failed: [results.failed_videos, results.failed_images].concat(),
+        skipped_fresh: results.skipped_fresh,
+        skipped_existing: results.skipped_existing,
+    };
+
+    if let (Some(path), Some(format)) = (&params.report, report_format) {
+        report.write_to(path, format, dry_run)?;
+        print_info(&format!("Report written to {}", path.display()));
+    }
+
+    if params.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if !dry_run && processed > 0 {
         print_success(&format!(
             "Batch processing complete: {} files processed",
-            results.total_files()
+            processed
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handles watch command
+pub async fn handle_watch_command(
+    params: WatchCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    if !params.videos && !params.images {
+        return Err(CompressError::config(
+            "Must specify --videos and/or --images for watch mode",
         ));
     }
+    if !params.directory.is_dir() {
+        return Err(CompressError::invalid_input(&params.directory));
+    }
+
+    let video_preset = config.resolve_video_preset(params.video_preset)?;
+
+    if params.videos {
+        check_ffmpeg_dependency()?;
+        verify_video_encoders(
+            &config,
+            &video_preset,
+            &params.fallback_codec,
+            utils::check_ffmpeg_encoder,
+        )?;
+    }
+
+    // Files arrive one at a time, so batch-only settings keep their defaults
+    let options = BatchOptions {
+        directory: params.directory,
+        pattern: params.pattern,
+        exclude: Vec::new(),
+        videos: params.videos,
+        images: params.images,
+        recursive: params.recursive,
+        video_preset,
+        image_quality: params.image_quality,
+        max_memory: None,
+        image_preset: None,
+        skip_existing: false,
+        delete_originals: false,
+        only_stale: false,
+        sort: crate::cli::args::BatchSort::Name,
+        fallback_codec: params.fallback_codec,
+        jobs: 1,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+        pre_hook: params.pre_hook,
+        post_hook: params.post_hook,
+    };
+
+    DirectoryWatcher::new(config, dry_run, verbose)
+        .watch(options)
+        .await
+}
+
+/// Handles init command
+/// Refuses to replace an existing config unless forced
+pub fn handle_init_command(path: Option<PathBuf>, force: bool) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => Config::get_default_config_path()?,
+    };
+
+    if path.exists() && !force {
+        return Err(CompressError::config(format!(
+            "{} already exists; use --force to replace it",
+            path.display()
+        )));
+    }
+
+    Config::default().save_commented(&path)?;
+    print_success(&format!("Wrote default config to {}", path.display()));
 
     Ok(())
 }
 
+/// Handles validate command
+/// Every problem is printed before failing, so one run catches all typos
+pub fn handle_validate_command(path: Option<PathBuf>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => Config::get_default_config_path()?,
+    };
+
+    let config = Config::load_from_file(&path)
+        .map_err(|e| CompressError::config(format!("{} can't be loaded: {}", path.display(), e)))?;
+
+    let problems = config.validate();
+    if problems.is_empty() {
+        print_success(&format!("{} is valid", path.display()));
+        return Ok(());
+    }
+
+    for problem in &problems {
+        print_error(problem);
+    }
+    Err(CompressError::config(format!(
+        "{} has {} problem(s)",
+        path.display(),
+        problems.len()
+    )))
+}
+
+/// Handles size estimate command
+pub fn handle_estimate_command(params: EstimateCommandParams, config: Config) -> Result<()> {
+    if utils::is_video_file(&params.input) {
+        check_ffmpeg_dependency()?;
+    }
+
+    let options = EstimateOptions {
+        input: params.input,
+        preset: config.resolve_video_preset(params.preset)?,
+        codec: params.codec,
+        crf: params.crf,
+        quality: params.quality,
+        format: params.format,
+    };
+
+    let estimator = SizeEstimator::new(config);
+    let estimate = estimator.estimate(&options)?;
+    estimator.print_estimate(&options, &estimate);
+
+    Ok(())
+}
+
+/// Handles contact sheet generation command
+pub async fn handle_contact_sheet_command(
+    params: ContactSheetCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    check_ffmpeg_dependency()?;
+
+    let options = ContactSheetOptions {
+        input: params.input,
+        output: params.output,
+        columns: params.columns,
+        rows: params.rows,
+        tile_width: params.tile_width,
+        timestamps: params.timestamps,
+        font: params.font,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let generator = ThumbnailGenerator::new(config, dry_run, verbose);
+    let output_path = generator.contact_sheet(options).await?;
+
+    if !dry_run {
+        print_success(&format!(
+            "Contact sheet saved to: {}",
+            output_path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Handles image collage command
+pub async fn handle_collage_command(
+    params: CollageCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    let options = CollageOptions {
+        inputs: params.inputs,
+        output: params.output,
+        columns: params.columns,
+        tile_size: params.tile_size,
+        padding: params.padding,
+        background: params.background,
+        format: params.format,
+        quality: params.quality,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let compressor = ImageCompressor::new(config, dry_run, verbose);
+    let output_path = compressor.collage(options).await?;
+
+    if !dry_run {
+        print_success(&format!("Collage saved to: {}", output_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handles audio visualization command
+pub async fn handle_visualize_command(
+    params: VisualizeCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    check_ffmpeg_dependency()?;
+
+    let options = VisualizeOptions {
+        input: params.input,
+        output: params.output,
+        visual: params.visual,
+        resolution: params.resolution,
+        preset: config.resolve_video_preset(params.preset)?,
+        codec: params.codec,
+        crf: params.crf,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let visualizer = AudioVisualizer::new(config, dry_run, verbose);
+    let output_path = visualizer.visualize(options).await?;
+
+    if !dry_run {
+        print_success(&format!("Video saved to: {}", output_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handles frame extraction command
+pub async fn handle_frames_command(
+    params: FramesCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    check_ffmpeg_dependency()?;
+
+    let options = FrameExtractionOptions {
+        input: params.input,
+        interval: params.interval,
+        format: params.format,
+        quality: params.quality,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let generator = ThumbnailGenerator::new(config, dry_run, verbose);
+    let output_dir = generator.extract_frames(options).await?;
+
+    if !dry_run {
+        print_success(&format!("Frames saved to: {}", output_dir.display()));
+    }
+
+    Ok(())
+}
+
+/// Handles single-frame thumbnail extraction command
+pub async fn handle_thumbnail_command(
+    params: ThumbnailCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    check_ffmpeg_dependency()?;
+
+    let options = ThumbnailOptions {
+        input: params.input,
+        output: params.output,
+        at: params.at,
+        format: params.format,
+        quality: params.quality,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let generator = ThumbnailGenerator::new(config, dry_run, verbose);
+    let output_path = generator.thumbnail(options).await?;
+
+    if !dry_run {
+        print_success(&format!("Thumbnail saved to: {}", output_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handles audio extraction command
+pub async fn handle_extract_audio_command(
+    params: ExtractAudioCommandParams,
+    config: Config,
+    dry_run: bool,
+    verbose: bool,
+) -> Result<()> {
+    check_ffmpeg_dependency()?;
+
+    let options = ExtractAudioOptions {
+        input: params.input,
+        output: params.output,
+        audio_codec: params.audio_codec,
+        audio_bitrate: params.audio_bitrate,
+        output_dir: params.output_dir,
+        overwrite: params.overwrite,
+    };
+
+    let extractor = AudioExtractor::new(config, dry_run, verbose);
+    let output_path = extractor.extract(options).await?;
+
+    if !dry_run {
+        print_success(&format!("Audio saved to: {}", output_path.display()));
+    }
+
+    Ok(())
+}
+
+/// Handles compare command
+/// Prints sizes, the reduction and, for images, the dimensions of both files
+pub fn handle_compare_command(input: &Path, output: &Path, json: bool) -> Result<()> {
+    utils::validate_input_file(input)?;
+    if !output.is_file() {
+        return Err(CompressError::invalid_parameter(
+            "output",
+            format!(
+                "{} doesn't exist; compress {} first or check the path",
+                output.display(),
+                input.display()
+            ),
+        ));
+    }
+
+    let report = CompressionReport::new(input, output, false)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", comparison_table(&report));
+    }
+
+    Ok(())
+}
+
+/// Lays out a before/after comparison as aligned rows
+fn comparison_table(report: &CompressionReport) -> String {
+    let compressed_size = report.compressed_size.unwrap_or_default();
+    let ratio = report.ratio.unwrap_or_default();
+    let dimensions = |path: &Path| {
+        image::image_dimensions(path)
+            .map(|(width, height)| format!("{}x{}", width, height))
+            .ok()
+    };
+
+    let mut rows = vec![
+        (
+            "File",
+            report.input.display().to_string(),
+            report.output.display().to_string(),
+        ),
+        (
+            "Size",
+            bytesize::ByteSize::b(report.original_size).to_string(),
+            bytesize::ByteSize::b(compressed_size).to_string(),
+        ),
+    ];
+    if let (Some(before), Some(after)) = (dimensions(&report.input), dimensions(&report.output)) {
+        rows.push(("Dimensions", before, after));
+    }
+
+    let width = rows
+        .iter()
+        .map(|(_, before, _)| before.len())
+        .max()
+        .unwrap_or(0);
+    let mut table = format!("{:<12}{:<width$}  {}", "", "Original", "Compressed");
+    for (label, before, after) in rows {
+        table.push_str(&format!(
+            "\n{:<12}{:<width$}  {}",
+            format!("{}:", label),
+            before,
+            after
+        ));
+    }
+
+    let verdict = if ratio < 0.0 {
+        " (output is larger)"
+    } else {
+        ""
+    };
+    table.push_str(&format!("\n{:<12}{:.1}%{}", "Reduction:", ratio, verdict));
+    table
+}
+
+/// Handles probe command
+/// Prints the FFprobe view of a file as a table, or as JSON with --json
+pub fn handle_probe_command(input: &Path, json: bool) -> Result<()> {
+    let ffprobe = utils::ffprobe_binary();
+    if !utils::check_command_available(&ffprobe) {
+        return Err(CompressError::missing_dependency(
+            ffprobe.display().to_string(),
+        ));
+    }
+    utils::validate_input_file(input)?;
+
+    let info = utils::probe_media(input)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let unknown = || "unknown".to_string();
+    println!("File:       {}", input.display());
+    println!(
+        "Format:     {}",
+        info.format_name.clone().unwrap_or_else(unknown)
+    );
+    println!(
+        "Duration:   {}",
+        info.duration.map(format_timestamp).unwrap_or_else(unknown)
+    );
+    println!(
+        "Resolution: {}",
+        match (info.width, info.height) {
+            (Some(width), Some(height)) => format!("{}x{}", width, height),
+            _ => unknown(),
+        }
+    );
+    if let Some(fps) = info.frame_rate {
+        println!("Frame rate: {:.3} fps", fps);
+    }
+    println!(
+        "Video:      {}",
+        info.video_codec
+            .clone()
+            .unwrap_or_else(|| "none".to_string())
+    );
+    println!(
+        "Audio:      {}",
+        info.audio_codec
+            .clone()
+            .unwrap_or_else(|| "none".to_string())
+    );
+    println!(
+        "Bitrate:    {}",
+        info.bit_rate
+            .map(|bitrate| format!("{} kb/s", bitrate / 1000))
+            .unwrap_or_else(unknown)
+    );
+    println!("Streams:    {}", info.stream_count);
+
+    Ok(())
+}
+
+/// Formats seconds as H:MM:SS.ss
+fn format_timestamp(seconds: f64) -> String {
+    let hours = (seconds / 3600.0).floor();
+    let minutes = ((seconds - hours * 3600.0) / 60.0).floor();
+    let secs = seconds - hours * 3600.0 - minutes * 60.0;
+    format!("{}:{:02}:{:05.2}", hours, minutes, secs)
+}
+
 /// Handles system info command
 pub async fn handle_info_command() -> Result<()> {
     use crate::ui::progress::{print_header, print_separator};
@@ -191,7 +911,7 @@ pub async fn handle_info_command() -> Result<()> {
 
     // Application info
     println!("CompressCLI version: {}", env!("CARGO_PKG_VERSION"));
-    println!("Built with Rust: {}", env!("CARGO_PKG_RUST_VERSION"));
+    println!("Built with: {}", env!("COMPRESSCLI_RUSTC_VERSION"));
 
     print_separator();
 
@@ -199,6 +919,10 @@ pub async fn handle_info_command() -> Result<()> {
     match utils::check_ffmpeg() {
         Ok(version) => {
             print_success(&format!("FFmpeg: {}", version));
+            match utils::parse_ffmpeg_version(&version) {
+                Some((major, minor)) => println!("  Version: {}.{}", major, minor),
+                None => println!("  Version: unknown (development build)"),
+            }
         }
         Err(_) => {
             print_error("FFmpeg: Not found or not accessible");
@@ -207,12 +931,27 @@ pub async fn handle_info_command() -> Result<()> {
     }
 
     // Check ffprobe
-    if utils::check_command_available("ffprobe") {
+    if utils::check_command_available(utils::ffprobe_binary()) {
         print_success("FFprobe: Available");
     } else {
         print_error("FFprobe: Not found (usually comes with FFmpeg)");
     }
 
+    // Encoders for the codecs we support
+    if let Some(encoders) = utils::ffmpeg_encoders() {
+        print_separator();
+        for (codec, encoder, available) in utils::codec_availability(encoders) {
+            if available {
+                print_success(&format!("{}: {}", codec, encoder));
+            } else {
+                print_warning(&format!(
+                    "{}: {} not available in this FFmpeg build",
+                    codec, encoder
+                ));
+            }
+        }
+    }
+
     print_separator();
 
     // System info
@@ -238,6 +977,55 @@ pub async fn handle_info_command() -> Result<()> {
     Ok(())
 }
 
+/// Build and dependency details printed by the version command
+#[derive(Serialize)]
+struct VersionInfo {
+    version: &'static str,
+    git_commit: &'static str,
+    rustc: &'static str,
+    target: &'static str,
+    features: Vec<&'static str>,
+    ffmpeg: Option<String>,
+}
+
+/// Handles version command
+/// Prints a compact block of build details, suitable for pasting into bug reports
+pub fn handle_version_command(json: bool) -> Result<()> {
+    let info = VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: env!("COMPRESSCLI_GIT_COMMIT"),
+        rustc: env!("COMPRESSCLI_RUSTC_VERSION"),
+        target: env!("COMPRESSCLI_TARGET"),
+        features: env!("COMPRESSCLI_FEATURES")
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .collect(),
+        ffmpeg: utils::check_ffmpeg().ok(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
+
+    let features = if info.features.is_empty() {
+        "none".to_string()
+    } else {
+        info.features.join(", ")
+    };
+
+    println!("compresscli {} ({})", info.version, info.git_commit);
+    println!("rustc:    {}", info.rustc);
+    println!("target:   {}", info.target);
+    println!("features: {}", features);
+    println!(
+        "ffmpeg:   {}",
+        info.ffmpeg.as_deref().unwrap_or("not found")
+    );
+
+    Ok(())
+}
+
 /// Generates shell completion scripts
 pub fn handle_completions_command(shell: Shell) -> Result<()> {
     let mut cmd = crate::cli::args::Cli::command();
@@ -249,8 +1037,32 @@ pub fn handle_completions_command(shell: Shell) -> Result<()> {
 /// Checks if FFmpeg is available in the system PATH
 /// Returns error if FFmpeg is not found, as it's required for video processing
 fn check_ffmpeg_dependency() -> Result<()> {
-    if !utils::check_command_available("ffmpeg") {
-        return Err(CompressError::missing_dependency("ffmpeg"));
+    let ffmpeg = utils::ffmpeg_binary();
+    if !utils::check_command_available(&ffmpeg) {
+        return Err(CompressError::missing_dependency(
+            ffmpeg.display().to_string(),
+        ));
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_table_reports_ratio() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("clip.mp4");
+        let output = dir.path().join("clip_compressed.mp4");
+        std::fs::write(&input, vec![0u8; 4000]).unwrap();
+        std::fs::write(&output, vec![0u8; 1000]).unwrap();
+
+        let report = CompressionReport::new(&input, &output, false).unwrap();
+        let table = comparison_table(&report);
+        assert!(table.contains("Reduction:  75.0%"), "{}", table);
+        assert!(!table.contains("Dimensions"));
+
+        assert!(handle_compare_command(&input, &dir.path().join("missing.mp4"), false).is_err());
+    }
+}
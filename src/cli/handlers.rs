@@ -4,16 +4,67 @@
 //! including preset management and configuration loading.
 
 use crate::cli::args::{Cli, Commands, PresetAction};
-use crate::cli::commands::{self, BatchCommandParams, ImageCommandParams, VideoCommandParams};
-use crate::core::{CompressError, Config, ImagePresetConfig, Result, VideoPresetConfig};
-use crate::ui::progress::{print_header, print_success};
+use crate::cli::commands::{
+    self, BatchCommandParams, CollageCommandParams, ContactSheetCommandParams,
+    EstimateCommandParams, ExtractAudioCommandParams, FramesCommandParams, ImageCommandParams,
+    ThumbnailCommandParams, VideoCommandParams, VisualizeCommandParams, WatchCommandParams,
+};
+use crate::core::{
+    CompressError, Config, ImagePresetConfig, PresetKind, PresetOrigin, Profile, Result,
+    VideoPresetConfig,
+};
+use crate::ui::progress::{configure_terminal_output, confirm, print_header, print_success};
+use serde::Serialize;
 
 /// Main CLI execution function
 /// Loads configuration and dispatches to appropriate command handlers
-pub async fn run_cli(cli: Cli) -> Result<()> {
+pub async fn run_cli(mut cli: Cli) -> Result<()> {
+    configure_terminal_output(cli.no_color);
+
+    // These inspect or write the config file itself, so they run before it's loaded:
+    // init must not find the default file already created, and validate has to
+    // report a broken file rather than fail on it
+    match &cli.command {
+        Commands::Init { path, force } => {
+            return commands::handle_init_command(path.clone(), *force);
+        }
+        Commands::Validate { path } => {
+            return commands::handle_validate_command(path.clone().or(cli.config.clone()));
+        }
+        _ => {}
+    }
+
     // Load configuration from file or create default
     let config = load_config(&cli)?;
 
+    // A profile fills in whatever the command line left unset
+    if let Some(name) = &cli.profile {
+        let profile = config
+            .get_profile(name)
+            .cloned()
+            .ok_or_else(|| CompressError::config(format!("Profile '{}' not found", name)))?;
+        apply_profile(&mut cli, &profile);
+    }
+
+    // Command-line output settings take precedence over the config defaults
+    cli.output_dir = config.resolve_output_dir(cli.output_dir.take());
+    cli.overwrite = config.resolve_overwrite(cli.overwrite);
+
+    // Command-line style settings take precedence over the config file
+    crate::utils::configure_output_style(
+        cli.progress_style
+            .unwrap_or(config.default_settings.progress_style),
+        cli.progress_template
+            .clone()
+            .or_else(|| config.default_settings.progress_template.clone()),
+    )?;
+    crate::utils::configure_ffmpeg_paths(
+        config.default_settings.ffmpeg_path.clone(),
+        config.default_settings.ffprobe_path.clone(),
+    );
+    // JSON output must be the only thing on stdout
+    crate::utils::set_quiet_output(cli.quiet || cli.json);
+
     match cli.command {
         Commands::Video {
             input,
@@ -30,6 +81,34 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
             start,
             end,
             two_pass,
+            deterministic,
+            container,
+            cfr,
+            metadata_from,
+            rotate_metadata,
+            fallback_codec,
+            input_format,
+            preset_from,
+            scene_cut,
+            fade_in,
+            fade_out,
+            speed,
+            deinterlace,
+            tune,
+            keyframe_interval,
+            pix_fmt,
+            threads,
+            field_order,
+            max_duration,
+            watermark,
+            watermark_text,
+            watermark_position,
+            watermark_opacity,
+            hwaccel,
+            copy,
+            target_size,
+            crop,
+            rotate,
         } => {
             let params = VideoCommandParams {
                 input,
@@ -46,8 +125,39 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
                 start,
                 end,
                 two_pass,
+                deterministic,
+                container,
+                cfr,
+                metadata_from,
+                rotate_metadata,
+                fallback_codec,
+                input_format,
+                preset_from,
+                scene_cut,
+                fade_in,
+                fade_out,
+                speed,
+                deinterlace,
+                tune,
+                keyframe_interval,
+                pix_fmt,
+                threads,
+                field_order,
+                max_duration,
+                watermark,
+                watermark_text,
+                watermark_position,
+                watermark_opacity,
+                hwaccel,
+                copy,
+                target_size,
+                crop,
+                rotate,
                 output_dir: cli.output_dir,
                 overwrite: cli.overwrite,
+                json: cli.json,
+                pre_hook: cli.pre_hook,
+                post_hook: cli.post_hook,
             };
             commands::handle_video_command(params, config, cli.dry_run, cli.verbose).await?;
         }
@@ -64,6 +174,20 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
             progressive,
             lossless,
             preset,
+            quality_range,
+            comparison_html,
+            chroma,
+            input_format,
+            strip,
+            downscale_only,
+            preset_from,
+            max_bytes,
+            strip_metadata,
+            target_size,
+            auto_orient,
+            resize_mode,
+            filter,
+            crop,
         } => {
             let params = ImageCommandParams {
                 input,
@@ -77,8 +201,25 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
                 progressive,
                 lossless,
                 preset,
+                quality_range,
+                comparison_html,
+                chroma,
+                input_format,
+                strip,
+                downscale_only,
+                preset_from,
+                max_bytes,
+                strip_metadata,
+                target_size,
+                auto_orient,
+                resize_mode,
+                filter,
+                crop,
                 output_dir: cli.output_dir,
                 overwrite: cli.overwrite,
+                json: cli.json,
+                pre_hook: cli.pre_hook,
+                post_hook: cli.post_hook,
             };
             commands::handle_image_command(params, config, cli.dry_run, cli.verbose).await?;
         }
@@ -91,6 +232,15 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
             recursive,
             video_preset,
             image_quality,
+            image_preset,
+            delete_originals,
+            report,
+            skip_existing,
+            exclude,
+            max_memory,
+            only_stale,
+            sort,
+            fallback_codec,
             jobs,
         } => {
             let params = BatchCommandParams {
@@ -101,21 +251,221 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
                 recursive,
                 video_preset,
                 image_quality,
+                image_preset,
+                delete_originals,
+                report,
+                skip_existing,
+                exclude,
+                max_memory,
+                only_stale,
+                sort,
+                fallback_codec,
                 jobs,
                 output_dir: cli.output_dir,
                 overwrite: cli.overwrite,
+                json: cli.json,
+                pre_hook: cli.pre_hook,
+                post_hook: cli.post_hook,
             };
             commands::handle_batch_command(params, config, cli.dry_run, cli.verbose).await?;
         }
 
+        Commands::Watch {
+            directory,
+            pattern,
+            videos,
+            images,
+            recursive,
+            video_preset,
+            image_quality,
+            fallback_codec,
+        } => {
+            let params = WatchCommandParams {
+                directory,
+                pattern,
+                videos,
+                images,
+                recursive,
+                video_preset,
+                image_quality,
+                fallback_codec,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+                pre_hook: cli.pre_hook,
+                post_hook: cli.post_hook,
+            };
+            commands::handle_watch_command(params, config, cli.dry_run, cli.verbose).await?;
+        }
+
+        Commands::Estimate {
+            input,
+            preset,
+            codec,
+            crf,
+            quality,
+            format,
+        } => {
+            let params = EstimateCommandParams {
+                input,
+                preset,
+                codec,
+                crf,
+                quality,
+                format,
+            };
+            commands::handle_estimate_command(params, config)?;
+        }
+
+        Commands::ContactSheet {
+            input,
+            output,
+            columns,
+            rows,
+            tile_width,
+            timestamps,
+            font,
+        } => {
+            let params = ContactSheetCommandParams {
+                input,
+                output,
+                columns,
+                rows,
+                tile_width,
+                timestamps,
+                font,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_contact_sheet_command(params, config, cli.dry_run, cli.verbose)
+                .await?;
+        }
+
+        Commands::Collage {
+            inputs,
+            output,
+            columns,
+            tile_size,
+            padding,
+            background,
+            format,
+            quality,
+        } => {
+            let params = CollageCommandParams {
+                inputs,
+                output,
+                columns,
+                tile_size,
+                padding,
+                background,
+                format,
+                quality,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_collage_command(params, config, cli.dry_run, cli.verbose).await?;
+        }
+
+        Commands::Frames {
+            input,
+            interval,
+            format,
+            quality,
+        } => {
+            let params = FramesCommandParams {
+                input,
+                interval,
+                format,
+                quality,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_frames_command(params, config, cli.dry_run, cli.verbose).await?;
+        }
+
+        Commands::Thumbnail {
+            input,
+            output,
+            at,
+            format,
+            quality,
+        } => {
+            let params = ThumbnailCommandParams {
+                input,
+                output,
+                at,
+                format,
+                quality,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_thumbnail_command(params, config, cli.dry_run, cli.verbose).await?;
+        }
+
+        Commands::ExtractAudio {
+            input,
+            output,
+            audio_codec,
+            audio_bitrate,
+        } => {
+            let params = ExtractAudioCommandParams {
+                input,
+                output,
+                audio_codec,
+                audio_bitrate,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_extract_audio_command(params, config, cli.dry_run, cli.verbose)
+                .await?;
+        }
+
+        Commands::Visualize {
+            input,
+            output,
+            visual,
+            resolution,
+            preset,
+            codec,
+            crf,
+        } => {
+            let params = VisualizeCommandParams {
+                input,
+                output,
+                visual,
+                resolution,
+                preset,
+                codec,
+                crf,
+                output_dir: cli.output_dir,
+                overwrite: cli.overwrite,
+            };
+            commands::handle_visualize_command(params, config, cli.dry_run, cli.verbose).await?;
+        }
+
         Commands::Presets { action } => {
-            handle_presets_command(action, config).await?;
+            handle_presets_command(action, config, cli.json).await?;
+        }
+
+        Commands::Probe { input } => {
+            commands::handle_probe_command(&input, cli.json)?;
+        }
+
+        Commands::Compare { input, output } => {
+            commands::handle_compare_command(&input, &output, cli.json)?;
+        }
+
+        Commands::Init { .. } | Commands::Validate { .. } => {
+            unreachable!("handled before the config is loaded")
         }
 
         Commands::Info => {
             commands::handle_info_command().await?;
         }
 
+        Commands::Version => {
+            commands::handle_version_command(cli.json)?;
+        }
+
         Commands::Completions { shell } => {
             commands::handle_completions_command(shell)?;
         }
@@ -126,29 +476,36 @@ pub async fn run_cli(cli: Cli) -> Result<()> {
 
 /// Handles all preset-related commands (list, show, create, delete)
 /// Manages user-defined and built-in compression presets
-async fn handle_presets_command(action: PresetAction, config: Config) -> Result<()> {
+async fn handle_presets_command(action: PresetAction, config: Config, json: bool) -> Result<()> {
     match action {
         PresetAction::List => {
+            if json {
+                println!("{}", presets_to_json(&config)?);
+                return Ok(());
+            }
+
             print_header("Available Presets");
 
             println!("\\n{}", console::style("Video Presets:").bold());
             for (name, preset) in &config.video_presets {
                 println!(
-                    "  {} - {} (CRF: {:?}, Codec: {})",
+                    "  {} - {} (CRF: {:?}, Codec: {}){}",
                     console::style(name).cyan(),
                     preset.preset,
                     preset.crf,
-                    preset.codec
+                    preset.codec,
+                    origin_marker(config.preset_origin(PresetKind::Video, name))
                 );
             }
 
             println!("\\n{}", console::style("Image Presets:").bold());
             for (name, preset) in &config.image_presets {
                 println!(
-                    "  {} - Quality: {}, Optimize: {}",
+                    "  {} - Quality: {}, Optimize: {}{}",
                     console::style(name).cyan(),
                     preset.quality,
-                    preset.optimize
+                    preset.optimize,
+                    origin_marker(config.preset_origin(PresetKind::Image, name))
                 );
             }
         }
@@ -183,11 +540,17 @@ async fn handle_presets_command(action: PresetAction, config: Config) -> Result<
         PresetAction::Create {
             name,
             config: config_file,
+            yes,
         } => {
             // Load preset from file
             let preset_content = std::fs::read_to_string(&config_file)
                 .map_err(|e| CompressError::config(format!("Failed to read config file: {}", e)))?;
 
+            if config.video_presets.contains_key(&name) || config.image_presets.contains_key(&name)
+            {
+                confirm_config_change(&format!("Replace existing preset '{}'?", name), yes)?;
+            }
+
             // Try to parse as video preset first, then image preset
             if let Ok(video_preset) = serde_yaml::from_str::<VideoPresetConfig>(&preset_content) {
                 let mut config = config;
@@ -208,7 +571,12 @@ async fn handle_presets_command(action: PresetAction, config: Config) -> Result<
             }
         }
 
-        PresetAction::Delete { name } => {
+        PresetAction::Delete { name, yes } => {
+            if config.video_presets.contains_key(&name) || config.image_presets.contains_key(&name)
+            {
+                confirm_config_change(&format!("Delete preset '{}'?", name), yes)?;
+            }
+
             let mut config = config;
             let mut deleted = false;
 
@@ -236,6 +604,85 @@ async fn handle_presets_command(action: PresetAction, config: Config) -> Result<
     Ok(())
 }
 
+/// Requires confirmation before a destructive configuration change
+/// Config mutations are confirmed with --yes or an interactive prompt, never --overwrite
+fn confirm_config_change(prompt: &str, yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+
+    check_confirmation(prompt, confirm(&format!("{} [y/N]", prompt)))
+}
+
+/// Turns the answer to a confirmation prompt into a result
+/// No answer means there was no terminal to ask on
+fn check_confirmation(prompt: &str, answer: Option<bool>) -> Result<()> {
+    match answer {
+        Some(true) => Ok(()),
+        Some(false) => Err(CompressError::config("Aborted by user")),
+        None => Err(CompressError::config(format!(
+            "{} Re-run with --yes to confirm",
+            prompt
+        ))),
+    }
+}
+
+/// A named preset entry for JSON output
+#[derive(Serialize)]
+struct PresetEntry<'a, T: Serialize> {
+    name: &'a str,
+    origin: PresetOrigin,
+    #[serde(flatten)]
+    settings: &'a T,
+}
+
+/// JSON document listing all presets
+#[derive(Serialize)]
+struct PresetList<'a> {
+    video_presets: Vec<PresetEntry<'a, VideoPresetConfig>>,
+    image_presets: Vec<PresetEntry<'a, ImagePresetConfig>>,
+}
+
+/// Serializes all presets, sorted by name, into a JSON document
+fn presets_to_json(config: &Config) -> Result<String> {
+    let mut video_presets: Vec<_> = config
+        .video_presets
+        .iter()
+        .map(|(name, settings)| PresetEntry {
+            name,
+            origin: config.preset_origin(PresetKind::Video, name),
+            settings,
+        })
+        .collect();
+    video_presets.sort_by_key(|entry| entry.name);
+
+    let mut image_presets: Vec<_> = config
+        .image_presets
+        .iter()
+        .map(|(name, settings)| PresetEntry {
+            name,
+            origin: config.preset_origin(PresetKind::Image, name),
+            settings,
+        })
+        .collect();
+    image_presets.sort_by_key(|entry| entry.name);
+
+    let list = PresetList {
+        video_presets,
+        image_presets,
+    };
+    Ok(serde_json::to_string_pretty(&list)?)
+}
+
+/// Returns a marker for user-added or changed presets in the human-readable list
+fn origin_marker(origin: PresetOrigin) -> &'static str {
+    match origin {
+        PresetOrigin::Builtin => "",
+        PresetOrigin::Overridden => " [overridden]",
+        PresetOrigin::User => " [user]",
+    }
+}
+
 /// Loads configuration from file or creates default configuration
 /// Uses custom config path if provided, otherwise uses default location
 fn load_config(cli: &Cli) -> Result<Config> {
@@ -245,3 +692,158 @@ fn load_config(cli: &Cli) -> Result<Config> {
         Config::load_or_create_default()
     }
 }
+
+/// Fills settings the command line left unset from a config profile
+/// Output settings apply to every command; codec, resolution and audio settings to
+/// the commands that take them
+fn apply_profile(cli: &mut Cli, profile: &Profile) {
+    cli.output_dir = cli.output_dir.take().or_else(|| profile.output_dir.clone());
+    cli.overwrite |= profile.overwrite;
+
+    match &mut cli.command {
+        Commands::Video {
+            codec,
+            resolution,
+            audio_codec,
+            audio_bitrate,
+            ..
+        } => {
+            *codec = codec.take().or_else(|| profile.codec.clone());
+            *resolution = resolution.take().or_else(|| profile.resolution.clone());
+            *audio_codec = audio_codec.take().or_else(|| profile.audio_codec.clone());
+            *audio_bitrate = audio_bitrate
+                .take()
+                .or_else(|| profile.audio_bitrate.clone());
+        }
+        Commands::ExtractAudio {
+            audio_codec,
+            audio_bitrate,
+            ..
+        } => {
+            *audio_codec = audio_codec.take().or_else(|| profile.audio_codec.clone());
+            *audio_bitrate = audio_bitrate
+                .take()
+                .or_else(|| profile.audio_bitrate.clone());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::VideoCodec;
+    use clap::Parser;
+    use std::path::PathBuf;
+
+    /// Parses a `presets` command and returns its action and the global --overwrite
+    fn parse_preset_action(args: &[&str]) -> (PresetAction, bool) {
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Presets { action } => (action, cli.overwrite),
+            _ => panic!("expected the presets command"),
+        }
+    }
+
+    #[test]
+    fn test_overwrite_does_not_confirm_preset_changes() {
+        let (action, overwrite) =
+            parse_preset_action(&["compresscli", "--overwrite", "presets", "delete", "fast"]);
+        assert!(overwrite);
+        let PresetAction::Delete { yes, .. } = action else {
+            panic!("expected presets delete");
+        };
+        assert!(!yes);
+        // Without --yes and without a terminal to ask on, the delete is refused
+        let refused = check_confirmation("Delete preset 'fast'?", None).unwrap_err();
+        assert!(refused.to_string().contains("--yes"));
+
+        let (action, overwrite) = parse_preset_action(&[
+            "compresscli",
+            "--overwrite",
+            "presets",
+            "create",
+            "fast",
+            "fast.yaml",
+        ]);
+        assert!(overwrite);
+        let PresetAction::Create { yes, .. } = action else {
+            panic!("expected presets create");
+        };
+        assert!(!yes);
+        assert!(check_confirmation("Replace existing preset 'fast'?", Some(false)).is_err());
+    }
+
+    #[test]
+    fn test_yes_confirms_preset_changes() {
+        let (action, overwrite) =
+            parse_preset_action(&["compresscli", "presets", "delete", "fast", "--yes"]);
+        assert!(!overwrite);
+        let PresetAction::Delete { yes, .. } = action else {
+            panic!("expected presets delete");
+        };
+        assert!(yes);
+        assert!(confirm_config_change("Delete preset 'fast'?", yes).is_ok());
+
+        let (action, _) = parse_preset_action(&[
+            "compresscli",
+            "presets",
+            "create",
+            "fast",
+            "fast.yaml",
+            "-y",
+        ]);
+        let PresetAction::Create { yes, .. } = action else {
+            panic!("expected presets create");
+        };
+        assert!(confirm_config_change("Replace existing preset 'fast'?", yes).is_ok());
+        assert!(check_confirmation("Replace existing preset 'fast'?", Some(true)).is_ok());
+    }
+
+    #[test]
+    fn test_profile_fills_unset_flags() {
+        let profile = Profile {
+            codec: Some(VideoCodec::H265),
+            resolution: Some("1080p".to_string()),
+            output_dir: Some(PathBuf::from("/srv/youtube")),
+            ..Profile::default()
+        };
+
+        let mut cli =
+            Cli::try_parse_from(["compresscli", "--profile", "youtube", "video", "in.mp4"])
+                .unwrap();
+        apply_profile(&mut cli, &profile);
+        let Commands::Video {
+            codec, resolution, ..
+        } = &cli.command
+        else {
+            panic!("expected the video command");
+        };
+        assert!(matches!(codec, Some(VideoCodec::H265)));
+        assert_eq!(resolution.as_deref(), Some("1080p"));
+        assert_eq!(cli.output_dir, Some(PathBuf::from("/srv/youtube")));
+
+        // Flags given on the command line win over the profile
+        let mut cli = Cli::try_parse_from([
+            "compresscli",
+            "--profile",
+            "youtube",
+            "video",
+            "in.mp4",
+            "--codec",
+            "vp9",
+            "--resolution",
+            "720p",
+        ])
+        .unwrap();
+        apply_profile(&mut cli, &profile);
+        let Commands::Video {
+            codec, resolution, ..
+        } = &cli.command
+        else {
+            panic!("expected the video command");
+        };
+        assert!(matches!(codec, Some(VideoCodec::Vp9)));
+        assert_eq!(resolution.as_deref(), Some("720p"));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::cli::args::AudioCodec;
+use crate::core::{AUDIO_BITRATE_MEDIUM, CompressError, Config, Result};
+use crate::ui::progress::{print_success, print_warning};
+use crate::utils::{
+    FFmpegCommandBuilder, FFmpegProgressParser, check_output_overwrite, ensure_parent_dir,
+    generate_output_path, monitor_ffmpeg_progress, probe_duration, probe_media,
+    validate_input_file, validate_safe_path,
+};
+use log::{debug, info};
+use std::path::{Path, PathBuf};
+
+pub struct AudioExtractor {
+    pub config: Config,
+    pub dry_run: bool,
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractAudioOptions {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub audio_codec: Option<AudioCodec>,
+    pub audio_bitrate: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+impl AudioExtractor {
+    /// Creates a new AudioExtractor instance
+    /// Initializes with configuration, dry-run mode, and verbosity settings
+    pub fn new(config: Config, dry_run: bool, verbose: bool) -> Self {
+        Self {
+            config,
+            dry_run,
+            verbose,
+        }
+    }
+
+    /// Writes the audio stream of a video to its own file, dropping the video
+    /// Defaults to AAC in an .m4a file; `copy` keeps the original stream in .mka
+    /// Returns the path to the extracted audio
+    pub async fn extract(&self, options: ExtractAudioOptions) -> Result<PathBuf> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
+
+        let codec = options.audio_codec.clone().unwrap_or(AudioCodec::Aac);
+        if options.audio_bitrate.is_some() && matches!(codec, AudioCodec::Copy) {
+            print_warning("--audio-bitrate is ignored because audio is copied without re-encoding");
+        }
+
+        let output_path = self.generate_output_path(&options, &codec)?;
+        ensure_parent_dir(&output_path)?;
+        check_output_overwrite(&output_path, options.overwrite)?;
+
+        info!(
+            "Extracting audio: {} -> {}",
+            options.input.display(),
+            output_path.display()
+        );
+
+        if self.dry_run {
+            self.print_dry_run_info(&options, &codec, &output_path);
+            return Ok(output_path);
+        }
+
+        if probe_media(&options.input)?.audio_codec.is_none() {
+            return Err(CompressError::invalid_parameter(
+                "input",
+                format!("{} has no audio stream", options.input.display()),
+            ));
+        }
+        let duration = probe_duration(&options.input)?;
+
+        let mut command = build_extract_audio_command(&options, &codec, &output_path)?.build();
+
+        if self.verbose {
+            debug!("Executing FFmpeg command: {:?}", command);
+        }
+
+        let child = command.spawn().map_err(|e| {
+            CompressError::ffmpeg_error(
+                format!("Failed to start FFmpeg: {}", e),
+                Some(format!("{:?}", command)),
+            )
+        })?;
+
+        let progress_parser = FFmpegProgressParser::new(duration);
+        progress_parser.set_message("Extracting audio...");
+
+        monitor_ffmpeg_progress(child, progress_parser).await?;
+
+        print_success(&format!("Audio extracted as {}", codec));
+
+        Ok(output_path)
+    }
+
+    /// Generates output path with proper naming and validation
+    fn generate_output_path(
+        &self,
+        options: &ExtractAudioOptions,
+        codec: &AudioCodec,
+    ) -> Result<PathBuf> {
+        if let Some(output) = &options.output {
+            validate_safe_path(output)?;
+            Ok(output.clone())
+        } else {
+            Ok(generate_output_path(
+                &options.input,
+                options.output_dir.as_deref(),
+                Some("_audio"),
+                Some(audio_extension(codec)),
+            ))
+        }
+    }
+
+    /// Prints dry run information
+    fn print_dry_run_info(
+        &self,
+        options: &ExtractAudioOptions,
+        codec: &AudioCodec,
+        output_path: &Path,
+    ) {
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+        println!("Input:  {}", options.input.display());
+        println!("Output: {}", output_path.display());
+        println!("Audio:  {}", codec);
+        if !matches!(codec, AudioCodec::Copy) {
+            println!(
+                "Bitrate: {}",
+                options
+                    .audio_bitrate
+                    .as_deref()
+                    .unwrap_or(AUDIO_BITRATE_MEDIUM)
+            );
+        }
+    }
+}
+
+/// Builds the FFmpeg command that keeps only the audio stream
+fn build_extract_audio_command(
+    options: &ExtractAudioOptions,
+    codec: &AudioCodec,
+    output_path: &Path,
+) -> Result<FFmpegCommandBuilder> {
+    let mut builder = FFmpegCommandBuilder::new()
+        .input(&options.input)?
+        .no_video()
+        .audio_codec(codec.clone());
+
+    if !matches!(codec, AudioCodec::Copy) {
+        let bitrate = options
+            .audio_bitrate
+            .as_deref()
+            .unwrap_or(AUDIO_BITRATE_MEDIUM);
+        builder = builder.audio_bitrate(bitrate)?;
+    }
+
+    builder.progress().overwrite().output(output_path)
+}
+
+/// File extension for an extracted audio stream
+/// Copied streams can be any codec, so they go into Matroska audio
+fn audio_extension(codec: &AudioCodec) -> &'static str {
+    match codec {
+        AudioCodec::Aac => "m4a",
+        AudioCodec::Mp3 => "mp3",
+        AudioCodec::Opus => "opus",
+        AudioCodec::Copy => "mka",
+    }
+}
+
+// Make AudioExtractor cloneable for async processing
+impl Clone for AudioExtractor {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            dry_run: self.dry_run,
+            verbose: self.verbose,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_options() -> ExtractAudioOptions {
+        ExtractAudioOptions {
+            input: PathBuf::from("/videos/talk.mp4"),
+            output: None,
+            audio_codec: None,
+            audio_bitrate: None,
+            output_dir: None,
+            overwrite: false,
+        }
+    }
+
+    #[test]
+    fn test_extract_audio_command_drops_video() {
+        let options = sample_options();
+        let command = build_extract_audio_command(
+            &options,
+            &AudioCodec::Opus,
+            Path::new("/videos/talk_audio.opus"),
+        )
+        .unwrap()
+        .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-vn\""));
+        assert!(!cmd_str.contains("-c:v"));
+        assert!(cmd_str.contains("\"-c:a\" \"libopus\""));
+        assert!(cmd_str.contains("\"-b:a\" \"192k\""));
+
+        let copy = build_extract_audio_command(
+            &options,
+            &AudioCodec::Copy,
+            Path::new("/videos/talk_audio.mka"),
+        )
+        .unwrap()
+        .build();
+        assert!(!format!("{:?}", copy).contains("-b:a"));
+    }
+
+    #[test]
+    fn test_extract_audio_output_path() {
+        let extractor = AudioExtractor::new(Config::default(), true, false);
+        let output = extractor
+            .generate_output_path(&sample_options(), &AudioCodec::Aac)
+            .unwrap();
+        assert_eq!(output, PathBuf::from("/videos/talk_audio.m4a"));
+    }
+}
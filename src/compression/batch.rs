@@ -1,36 +1,61 @@
-use crate::cli::args::VideoPreset;
+use crate::cli::args::{BatchSort, ResizeFilter, ResizeMode, VideoCodec, VideoPreset};
 use crate::compression::{
     ImageCompressionOptions, ImageCompressor, VideoCompressionOptions, VideoCompressor,
 };
-use crate::core::{CompressError, Config, Result};
-use crate::ui::progress::{print_header, print_info, print_success};
-use crate::utils::{ProgressManager, is_image_file, is_video_file};
+use crate::core::{CompressError, CompressionReport, Config, RETRY_BACKOFF_MS, Result};
+use crate::ui::progress::{print_error, print_header, print_info, print_success, print_warning};
+use crate::utils::{
+    ProgressManager, get_file_size, is_compressed_output, is_image_file, is_output_fresh,
+    is_video_file, new_multi_progress, probe_media, quiet_output,
+};
+use bytesize::ByteSize;
 use glob::Pattern;
-use log::{error, warn};
-use std::path::PathBuf;
+use indicatif::MultiProgress;
+use log::{debug, error, warn};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinSet;
 use walkdir::WalkDir;
 
+/// Fewest files per scan thread; smaller batches are stat'ed on the calling thread
+const PARALLEL_SCAN_MIN_ITEMS: usize = 64;
+
+/// Smallest scan thread pool, whatever the CPU count
+const MIN_SCAN_THREADS: usize = 4;
+
 pub struct BatchProcessor {
     config: Config,
     dry_run: bool,
     verbose: bool,
+    /// Shared by the overall bar and the per-file bars of parallel jobs
+    multi_progress: MultiProgress,
 }
 
 #[derive(Debug, Clone)]
 pub struct BatchOptions {
     pub directory: PathBuf,
     pub pattern: String,
+    pub exclude: Vec<String>,
     pub videos: bool,
     pub images: bool,
     pub recursive: bool,
     pub video_preset: VideoPreset,
-    pub image_quality: u8,
+    pub image_quality: Option<u8>,
+    pub image_preset: Option<String>,
+    pub delete_originals: bool,
+    pub skip_existing: bool,
+    pub max_memory: Option<ByteSize>,
+    pub only_stale: bool,
+    pub sort: BatchSort,
+    pub fallback_codec: Vec<VideoCodec>,
     pub jobs: usize,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
 }
 
 impl BatchProcessor {
@@ -41,6 +66,7 @@ impl BatchProcessor {
             config,
             dry_run,
             verbose,
+            multi_progress: new_multi_progress(),
         }
     }
 
@@ -62,15 +88,57 @@ impl BatchProcessor {
         }
 
         // Separate video and image files
-        let (video_files, image_files) = self.separate_files(&files);
+        let (mut video_files, mut image_files) = self.separate_files(&files);
 
-        let mut results = BatchResults::default();
+        let mut skipped_existing = 0;
+        if options.skip_existing {
+            let videos_before = video_files.len();
+            let images_before = image_files.len();
+            video_files = inputs_without_output(self.planned_video_outputs(video_files, &options)?);
+            image_files = inputs_without_output(self.planned_image_outputs(image_files, &options)?);
+            skipped_existing =
+                videos_before - video_files.len() + images_before - image_files.len();
+
+            if skipped_existing > 0 {
+                print_info(&format!(
+                    "Skipping {} files with existing outputs",
+                    skipped_existing
+                ));
+            }
+        }
+
+        // Leave up-to-date outputs alone, like make
+        let mut skipped_fresh = 0;
+        if options.only_stale {
+            let videos_before = video_files.len();
+            let images_before = image_files.len();
+            video_files = stale_inputs(self.planned_video_outputs(video_files, &options)?);
+            image_files = stale_inputs(self.planned_image_outputs(image_files, &options)?);
+            skipped_fresh = videos_before - video_files.len() + images_before - image_files.len();
+
+            if skipped_fresh > 0 {
+                print_info(&format!("Skipping {} up-to-date files", skipped_fresh));
+            }
+        }
+
+        if self.dry_run {
+            let mut results = self.plan_outputs(video_files, image_files, &options)?;
+            results.skipped_fresh = skipped_fresh;
+            results.skipped_existing = skipped_existing;
+            return Ok(results);
+        }
+
+        let mut results = BatchResults {
+            skipped_fresh,
+            skipped_existing,
+            ..Default::default()
+        };
 
         // Process videos if requested
         if options.videos && !video_files.is_empty() {
             print_info(&format!("Processing {} video files...", video_files.len()));
             let video_results = self.process_videos(video_files, &options).await?;
-            results.videos = video_results.successful;
+            results.videos = size_reports(&video_results.successful)?;
             results.failed_videos = video_results.failed;
         }
 
@@ -78,21 +146,67 @@ impl BatchProcessor {
         if options.images && !image_files.is_empty() {
             print_info(&format!("Processing {} image files...", image_files.len()));
             let image_results = self.process_images(image_files, &options).await?;
-            results.images = image_results.successful;
+            results.images = size_reports(&image_results.successful)?;
             results.failed_images = image_results.failed;
         }
 
+        if options.delete_originals && !self.dry_run {
+            results.deleted_originals = results
+                .videos
+                .iter()
+                .chain(&results.images)
+                .filter(|report| match delete_original(report) {
+                    Ok(deleted) => deleted,
+                    Err(e) => {
+                        print_error(&format!(
+                            "Failed to delete {}: {}",
+                            report.input.display(),
+                            e
+                        ));
+                        false
+                    }
+                })
+                .count();
+        }
+
         self.print_batch_summary(&results);
         Ok(results)
     }
 
+    /// Compresses one file with the batch settings, picking the compressor by file type
+    /// Used by watch mode, where files arrive one at a time
+    pub async fn process_file(&self, file: PathBuf, options: &BatchOptions) -> Result<PathBuf> {
+        let retries = self.config.default_settings.retry_attempts;
+
+        if is_video_file(&file) {
+            let compressor = VideoCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+            with_retries(&file, retries, || {
+                compressor.compress(video_options_for(file.clone(), options))
+            })
+            .await
+        } else {
+            let compressor = ImageCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+            with_retries(&file, retries, || {
+                compressor.compress(image_options_for(file.clone(), options, &self.config))
+            })
+            .await
+        }
+    }
+
     /// Finds all files in the directory that match the specified criteria
     /// Supports recursive traversal and pattern matching
-    /// Filters by file type (video/image) based on options
+    /// Filters by file type (video/image) based on options and orders the matches by
+    /// `--sort`; sizes and mtimes are only read when the sort needs them
     fn find_files(&self, options: &BatchOptions) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
         let pattern = Pattern::new(&options.pattern)
             .map_err(|e| CompressError::invalid_parameter("pattern", e.to_string()))?;
+        let excludes = options
+            .exclude
+            .iter()
+            .map(|exclude| Pattern::new(exclude))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| CompressError::invalid_parameter("exclude", e.to_string()))?;
 
         let walker = if options.recursive {
             WalkDir::new(&options.directory)
@@ -102,25 +216,171 @@ impl BatchProcessor {
 
         for entry in walker {
             let entry = entry?;
-            let path = entry.path();
 
-            if path.is_file()
-                && let Some(filename) = path.file_name()
-                && let Some(filename_str) = filename.to_str()
+            // The directory listing already carries the file type, so only symlinks
+            // need a stat to see whether they point at a file
+            let is_file =
+                entry.file_type().is_file() || (entry.path_is_symlink() && entry.path().is_file());
+
+            if is_file
+                && let Some(filename_str) = entry.file_name().to_str()
                 && pattern.matches(filename_str)
+                && !excludes.iter().any(|exclude| exclude.matches(filename_str))
             {
+                let path = entry.into_path();
+
                 // Check if it's a video or image file based on what we're processing
-                let is_target_file = (options.videos && is_video_file(path))
-                    || (options.images && is_image_file(path));
+                // Earlier outputs in the directory are skipped so suffixes don't nest
+                let is_target_file = ((options.videos && is_video_file(&path))
+                    || (options.images && is_image_file(&path)))
+                    && !is_compressed_output(&path);
 
                 if is_target_file {
-                    files.push(path.to_path_buf());
+                    files.push(path);
                 }
             }
         }
 
-        files.sort();
-        Ok(files)
+        if options.sort == BatchSort::Name {
+            files.sort();
+            return Ok(files);
+        }
+
+        // Stat calls dominate on large trees, so they're spread across threads;
+        // files removed since the walk are dropped
+        let mut scanned: Vec<ScannedFile> = parallel_map(files, |path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some(ScannedFile {
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+                path,
+            })
+        })
+        .into_iter()
+        .flatten()
+        .collect();
+
+        sort_scanned_files(&mut scanned, options.sort);
+        Ok(scanned.into_iter().map(|file| file.path).collect())
+    }
+
+    /// Computes the planned output path for every file and prints the mapping
+    /// Used in dry-run mode so naming and placement can be checked before a real run
+    fn plan_outputs(
+        &self,
+        video_files: Vec<PathBuf>,
+        image_files: Vec<PathBuf>,
+        options: &BatchOptions,
+    ) -> Result<BatchResults> {
+        let video_compressor =
+            VideoCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+        let image_compressor =
+            ImageCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+
+        let mut results = BatchResults::default();
+        let mut plan = Vec::new();
+
+        if options.videos {
+            for file in video_files {
+                let output = video_compressor
+                    .planned_output_path(&video_options_for(file.clone(), options))?;
+                results
+                    .videos
+                    .push(CompressionReport::new(&file, &output, true)?);
+                plan.push((file, output));
+            }
+        }
+
+        if options.images {
+            for file in image_files {
+                let output = image_compressor.planned_output_path(&image_options_for(
+                    file.clone(),
+                    options,
+                    &self.config,
+                ))?;
+                results
+                    .images
+                    .push(CompressionReport::new(&file, &output, true)?);
+                plan.push((file, output));
+            }
+        }
+
+        self.print_dry_run_plan(&plan, options);
+        Ok(results)
+    }
+
+    /// Prints planned input -> output mappings as a two-column table
+    fn print_dry_run_plan(&self, plan: &[(PathBuf, PathBuf)], options: &BatchOptions) {
+        if quiet_output() {
+            return;
+        }
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+
+        let rows: Vec<(String, String)> = plan
+            .iter()
+            .map(|(input, output)| {
+                let input = input.strip_prefix(&options.directory).unwrap_or(input);
+                (input.display().to_string(), output.display().to_string())
+            })
+            .collect();
+        let width = rows.iter().map(|(input, _)| input.len()).max().unwrap_or(0);
+
+        for (input, output) in &rows {
+            println!(
+                "{:<width$}  {}  {}",
+                input,
+                console::style("->").dim(),
+                output
+            );
+        }
+
+        print_info(&format!("{} files planned", rows.len()));
+
+        if let Some(hook) = &options.pre_hook {
+            print_info(&format!("Pre-hook (not run) before each file: {}", hook));
+        }
+    }
+
+    /// Pairs each video with the output path it would be compressed to
+    fn planned_video_outputs(
+        &self,
+        files: Vec<PathBuf>,
+        options: &BatchOptions,
+    ) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let compressor = VideoCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+        files
+            .into_iter()
+            .map(|file| {
+                let output =
+                    compressor.planned_output_path(&video_options_for(file.clone(), options))?;
+                Ok((file, output))
+            })
+            .collect()
+    }
+
+    /// Pairs each image with the output path it would be compressed to
+    fn planned_image_outputs(
+        &self,
+        files: Vec<PathBuf>,
+        options: &BatchOptions,
+    ) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let compressor = ImageCompressor::new(self.config.clone(), self.dry_run, self.verbose);
+        files
+            .into_iter()
+            .map(|file| {
+                let output = compressor.planned_output_path(&image_options_for(
+                    file.clone(),
+                    options,
+                    &self.config,
+                ))?;
+                Ok((file, output))
+            })
+            .collect()
     }
 
     /// Separates files into video and image categories
@@ -147,76 +407,24 @@ impl BatchProcessor {
         options: &BatchOptions,
     ) -> Result<ProcessingResults> {
         let video_compressor =
-            VideoCompressor::new(self.config.clone(), self.dry_run, self.verbose);
-        let progress = ProgressManager::new_file_progress(files.len());
-
-        let mut successful = Vec::new();
-        let mut failed = Vec::new();
-        let mut tasks: JoinSet<Result<(PathBuf, Option<PathBuf>)>> = JoinSet::new();
-        let semaphore = Arc::new(Semaphore::new(options.jobs));
-
-        // Spawn tasks for all files
-        for file in files {
-            let compressor = video_compressor.clone();
-            let batch_options = options.clone();
-            let permit = Arc::clone(&semaphore);
-
-            tasks.spawn(async move {
-                // Acquire permit at the start of the task
-                let _permit = permit.acquire().await.map_err(|e| {
-                    CompressError::process_failed(format!("Failed to acquire semaphore: {}", e))
-                })?;
-
-                let video_options = VideoCompressionOptions {
-                    input: file.clone(),
-                    output: None,
-                    preset: batch_options.video_preset,
-                    codec: None,
-                    crf: None,
-                    bitrate: None,
-                    resolution: None,
-                    fps: None,
-                    audio_codec: None,
-                    audio_bitrate: None,
-                    no_audio: false,
-                    start: None,
-                    end: None,
-                    two_pass: false,
-                    output_dir: batch_options.output_dir,
-                    overwrite: batch_options.overwrite,
-                };
-
-                match compressor.compress(video_options).await {
-                    Ok(output_path) => Ok((file, Some(output_path))),
-                    Err(_e) => Ok((file, None)),
-                }
-            });
-        }
+            VideoCompressor::new(self.config.clone(), self.dry_run, self.verbose)
+                .with_multi_progress(self.multi_progress.clone());
+        let batch_options = options.clone();
 
-        // Collect results as tasks complete
-        while let Some(result) = tasks.join_next().await {
-            match result {
-                Ok(Ok((_input_file, Some(output_path)))) => {
-                    successful.push(output_path);
-                    progress.inc(1);
-                }
-                Ok(Ok((input_file, None))) => {
-                    failed.push(input_file);
-                    progress.inc(1);
-                }
-                Ok(Err(e)) => {
-                    error!("Video compression task failed: {}", e);
-                    progress.inc(1);
-                }
-                Err(e) => {
-                    error!("Task join error: {}", e);
-                    progress.inc(1);
-                }
-            }
-        }
-
-        progress.finish_and_clear();
-        Ok(ProcessingResults { successful, failed })
+        run_jobs(
+            files,
+            options.jobs,
+            self.config.default_settings.retry_attempts,
+            &self.multi_progress,
+            None,
+            "Video",
+            move |file| {
+                let compressor = video_compressor.clone();
+                let video_options = video_options_for(file, &batch_options);
+                async move { compressor.compress(video_options).await }
+            },
+        )
+        .await
     }
 
     /// Processes image files with error handling and resource management
@@ -227,72 +435,24 @@ impl BatchProcessor {
     ) -> Result<ProcessingResults> {
         let image_compressor =
             ImageCompressor::new(self.config.clone(), self.dry_run, self.verbose);
-        let progress = ProgressManager::new_file_progress(files.len());
-
-        let mut successful = Vec::new();
-        let mut failed = Vec::new();
-        let mut tasks: JoinSet<Result<(PathBuf, Option<PathBuf>)>> = JoinSet::new();
-        let semaphore = Arc::new(Semaphore::new(options.jobs));
-
-        // Spawn tasks for all files
-        for file in files {
-            let compressor = image_compressor.clone();
-            let batch_options = options.clone();
-            let permit = Arc::clone(&semaphore);
-
-            tasks.spawn(async move {
-                // Acquire permit at the start of the task
-                let _permit = permit.acquire().await.map_err(|e| {
-                    CompressError::process_failed(format!("Failed to acquire semaphore: {}", e))
-                })?;
-
-                let image_options = ImageCompressionOptions {
-                    input: file.clone(),
-                    output: None,
-                    quality: batch_options.image_quality,
-                    format: None,
-                    resize: None,
-                    max_width: None,
-                    max_height: None,
-                    optimize: true,
-                    progressive: false,
-                    lossless: false,
-                    preset: None,
-                    output_dir: batch_options.output_dir,
-                    overwrite: batch_options.overwrite,
-                };
-
-                match compressor.compress(image_options).await {
-                    Ok(output_path) => Ok((file, Some(output_path))),
-                    Err(_e) => Ok((file, None)),
-                }
-            });
-        }
-
-        // Collect results as tasks complete
-        while let Some(result) = tasks.join_next().await {
-            match result {
-                Ok(Ok((_input_file, Some(output_path)))) => {
-                    successful.push(output_path);
-                    progress.inc(1);
-                }
-                Ok(Ok((input_file, None))) => {
-                    failed.push(input_file);
-                    progress.inc(1);
-                }
-                Ok(Err(e)) => {
-                    error!("Image compression task failed: {}", e);
-                    progress.inc(1);
-                }
-                Err(e) => {
-                    error!("Task join error: {}", e);
-                    progress.inc(1);
-                }
-            }
-        }
+        let memory_budget = options.max_memory.map(MemoryBudget::new).transpose()?;
+        let batch_options = options.clone();
+        let config = self.config.clone();
 
-        progress.finish_and_clear();
-        Ok(ProcessingResults { successful, failed })
+        run_jobs(
+            files,
+            options.jobs,
+            self.config.default_settings.retry_attempts,
+            &self.multi_progress,
+            memory_budget,
+            "Image",
+            move |file| {
+                let compressor = image_compressor.clone();
+                let image_options = image_options_for(file, &batch_options, &config);
+                async move { compressor.compress(image_options).await }
+            },
+        )
+        .await
     }
 
     /// Prints a summary of batch processing results
@@ -313,6 +473,19 @@ impl BatchProcessor {
             warn!("Images failed: {}", results.failed_images.len());
         }
 
+        if results.skipped_fresh > 0 {
+            print_info(&format!("Skipped as up to date: {}", results.skipped_fresh));
+        }
+        if results.skipped_existing > 0 {
+            print_info(&format!(
+                "Skipped with existing output: {}",
+                results.skipped_existing
+            ));
+        }
+        if results.deleted_originals > 0 {
+            print_info(&format!("Originals deleted: {}", results.deleted_originals));
+        }
+
         let total_successful = results.videos.len() + results.images.len();
         let total_failed = results.failed_videos.len() + results.failed_images.len();
 
@@ -328,13 +501,480 @@ impl BatchProcessor {
     }
 }
 
+/// A batch input with the metadata gathered by the scan
+struct ScannedFile {
+    path: PathBuf,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Orders scanned files for `--sort`
+/// Ties fall back to the path, so the order doesn't depend on which thread stat'ed first
+fn sort_scanned_files(files: &mut [ScannedFile], sort: BatchSort) {
+    match sort {
+        BatchSort::Name => files.sort_by(|a, b| a.path.cmp(&b.path)),
+        BatchSort::Size => {
+            files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)))
+        }
+        BatchSort::Modified => files.sort_by(|a, b| {
+            a.modified
+                .cmp(&b.modified)
+                .then_with(|| a.path.cmp(&b.path))
+        }),
+    }
+}
+
+/// Maps items on a pool of scoped threads
+/// Stats wait on the filesystem more than the CPU, so the pool is at least
+/// `MIN_SCAN_THREADS` even on small machines. Workers take the next unclaimed item,
+/// so slow stats don't hold up a whole chunk; results come back in input order
+fn parallel_map<T, R, F>(items: Vec<T>, map: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let threads = num_cpus::get()
+        .max(MIN_SCAN_THREADS)
+        .min(items.len() / PARALLEL_SCAN_MIN_ITEMS);
+    if threads < 2 {
+        return items.into_iter().map(map).collect();
+    }
+
+    let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+    let next = || queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+    let map = &map;
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    while let Some((index, item)) = next() {
+                        done.push((index, map(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Returns the inputs whose output is missing or outdated, comparing mtimes in parallel
+fn stale_inputs(planned: Vec<(PathBuf, PathBuf)>) -> Vec<PathBuf> {
+    parallel_map(planned, |(input, output)| {
+        (!is_output_fresh(&input, &output)).then_some(input)
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Reads the sizes of each compressed input and output pair
+fn size_reports(successful: &[(PathBuf, PathBuf)]) -> Result<Vec<CompressionReport>> {
+    successful
+        .iter()
+        .map(|(input, output)| CompressionReport::new(input, output, false))
+        .collect()
+}
+
+/// Deletes a compressed file's original once its output is verified non-empty and smaller
+/// Returns false, keeping the original, if the output doesn't pass those checks
+fn delete_original(report: &CompressionReport) -> Result<bool> {
+    let same_file = match (report.input.canonicalize(), report.output.canonicalize()) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => report.input == report.output,
+    };
+    if same_file {
+        return Ok(false);
+    }
+
+    let output_size = get_file_size(&report.output)?.as_u64();
+    if output_size == 0 || output_size >= report.original_size {
+        print_warning(&format!(
+            "Keeping {}: the compressed output isn't smaller",
+            report.input.display()
+        ));
+        return Ok(false);
+    }
+
+    std::fs::remove_file(&report.input)?;
+    debug!("Deleted original {}", report.input.display());
+    Ok(true)
+}
+
+/// Returns the inputs whose output doesn't exist yet, checking in parallel
+fn inputs_without_output(planned: Vec<(PathBuf, PathBuf)>) -> Vec<PathBuf> {
+    parallel_map(planned, |(input, output)| {
+        (!output.exists()).then_some(input)
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Verifies once that FFmpeg can encode with the preset's codec or one of its fallbacks
+/// Fails the whole batch up front instead of failing every file the same way
+pub fn verify_video_encoders<F>(
+    config: &Config,
+    preset: &VideoPreset,
+    fallback_codec: &[VideoCodec],
+    is_available: F,
+) -> Result<()>
+where
+    F: Fn(&str) -> bool,
+{
+    let codec = config
+        .get_video_preset(preset)
+        .map(|preset_config| preset_config.codec.clone())
+        .ok_or_else(|| CompressError::config(format!("Unknown preset: {}", preset)))?;
+
+    // Stream copy doesn't need an encoder
+    if matches!(codec, VideoCodec::Copy) {
+        return Ok(());
+    }
+
+    let candidates: Vec<String> = std::iter::once(&codec)
+        .chain(fallback_codec)
+        .map(ToString::to_string)
+        .collect();
+    if candidates.iter().any(|encoder| is_available(encoder)) {
+        return Ok(());
+    }
+
+    Err(CompressError::missing_dependency(format!(
+        "FFmpeg encoder {} (used by preset '{}'); install an FFmpeg build that includes it or pick another --fallback-codec",
+        candidates.join(", "),
+        preset
+    )))
+}
+
+/// Runs `job` for every file with at most `jobs` in flight, retrying transient failures
+/// Files are admitted in order: with a memory budget, each first reserves its estimated
+/// decode memory, then a job slot, and only then is its task spawned
+/// The overall file count is drawn in `multi_progress`, above any per-file bars
+async fn run_jobs<F, Fut>(
+    files: Vec<PathBuf>,
+    jobs: usize,
+    retries: usize,
+    multi_progress: &MultiProgress,
+    memory_budget: Option<MemoryBudget>,
+    kind: &'static str,
+    job: F,
+) -> Result<ProcessingResults>
+where
+    F: Fn(PathBuf) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Result<PathBuf>> + Send + 'static,
+{
+    let progress = ProgressManager::new_file_progress(files.len()).add_to(multi_progress);
+
+    let mut results = ProcessingResults::default();
+    let mut tasks: JoinSet<(PathBuf, Option<PathBuf>)> = JoinSet::new();
+    let semaphore = Arc::new(Semaphore::new(jobs));
+
+    for file in files {
+        // Wait for room in the memory budget before taking a job slot, so a
+        // large image doesn't hold a slot smaller images could use
+        let memory = match &memory_budget {
+            Some(budget) => {
+                // Reading the header (or running ffprobe) blocks, so keep it off the runtime
+                let path = file.clone();
+                let estimate = tokio::task::spawn_blocking(move || estimate_decode_memory(&path))
+                    .await
+                    .ok()
+                    .flatten();
+                Some(budget.acquire(estimate).await?)
+            }
+            None => None,
+        };
+
+        let permit = Arc::clone(&semaphore).acquire_owned().await.map_err(|e| {
+            CompressError::process_failed(format!("Failed to acquire semaphore: {}", e))
+        })?;
+
+        let job = job.clone();
+        tasks.spawn(async move {
+            // Both reservations are released when the file is done
+            let _memory = memory;
+            let _permit = permit;
+
+            match with_retries(&file, retries, || job(file.clone())).await {
+                Ok(output_path) => (file, Some(output_path)),
+                Err(e) => {
+                    print_error(&format!("{}: {}", file.display(), e));
+                    (file, None)
+                }
+            }
+        });
+
+        // Count files that finished while this one waited to be admitted
+        while let Some(result) = tasks.try_join_next() {
+            record_result(result, &mut results, kind);
+            progress.inc(1);
+        }
+    }
+
+    // Collect the rest as tasks complete
+    while let Some(result) = tasks.join_next().await {
+        record_result(result, &mut results, kind);
+        progress.inc(1);
+    }
+
+    progress.finish_and_clear();
+    Ok(results)
+}
+
+/// Sorts a finished batch task into the successful or failed files
+fn record_result(
+    result: std::result::Result<(PathBuf, Option<PathBuf>), tokio::task::JoinError>,
+    results: &mut ProcessingResults,
+    kind: &str,
+) {
+    match result {
+        Ok((input_file, Some(output_path))) => {
+            results.successful.push((input_file, output_path));
+        }
+        Ok((input_file, None)) => {
+            results.failed.push(input_file);
+        }
+        Err(e) => {
+            error!("{} compression task failed: {}", kind, e);
+        }
+    }
+}
+
+/// Runs `attempt` until it succeeds, fails permanently, or `retries` retries are used up
+/// Only transient errors (e.g. a file briefly locked by another process) are retried,
+/// each after a slightly longer pause than the last
+async fn with_retries<T, F, Fut>(file: &Path, retries: usize, mut attempt: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retried = 0;
+    loop {
+        match attempt().await {
+            Err(e) if retried < retries && e.is_transient() => {
+                retried += 1;
+                warn!("{}: {} (retry {}/{})", file.display(), e, retried, retries);
+                tokio::time::sleep(Duration::from_millis(RETRY_BACKOFF_MS * retried as u64)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Output directory for a file in a batch
+/// Recursive runs recreate the file's subdirectory under the output dir, so files
+/// with the same name in different folders don't overwrite each other
+fn output_dir_for(file: &Path, batch_options: &BatchOptions) -> Option<PathBuf> {
+    let output_dir = batch_options.output_dir.as_ref()?;
+    if !batch_options.recursive {
+        return Some(output_dir.clone());
+    }
+
+    let relative = file
+        .parent()
+        .and_then(|parent| parent.strip_prefix(&batch_options.directory).ok());
+    Some(match relative {
+        Some(relative) => output_dir.join(relative),
+        None => output_dir.clone(),
+    })
+}
+
+/// Builds compression options for a video file in a batch
+fn video_options_for(file: PathBuf, batch_options: &BatchOptions) -> VideoCompressionOptions {
+    let output_dir = output_dir_for(&file, batch_options);
+    VideoCompressionOptions {
+        input: file,
+        output: None,
+        preset: batch_options.video_preset.clone(),
+        codec: None,
+        crf: None,
+        bitrate: None,
+        resolution: None,
+        fps: None,
+        audio_codec: None,
+        audio_bitrate: None,
+        no_audio: false,
+        start: None,
+        end: None,
+        two_pass: false,
+        deterministic: false,
+        container: None,
+        cfr: false,
+        metadata_from: None,
+        rotate_metadata: None,
+        fallback_codec: batch_options.fallback_codec.clone(),
+        input_format: None,
+        preset_from: None,
+        scene_cut: None,
+        fade_in: None,
+        fade_out: None,
+        speed: None,
+        deinterlace: false,
+        tune: None,
+        keyframe_interval: None,
+        pix_fmt: None,
+        threads: None,
+        field_order: None,
+        max_duration: None,
+        watermark: None,
+        watermark_text: None,
+        watermark_position: crate::cli::args::WatermarkPosition::BottomRight,
+        watermark_opacity: 1.0,
+        hwaccel: crate::cli::args::HwAccel::None,
+        copy: false,
+        target_size: None,
+        crop: None,
+        rotate: None,
+        output_dir,
+        // Anything left in an --only-stale run has a missing or outdated output
+        overwrite: batch_options.overwrite || batch_options.only_stale,
+        pre_hook: batch_options.pre_hook.clone(),
+        post_hook: batch_options.post_hook.clone(),
+    }
+}
+
+/// Bytes per decoded pixel (8-bit RGBA, the widest common in-memory layout)
+const DECODED_BYTES_PER_PIXEL: u64 = 4;
+
+/// Decoded copies alive at once while an image is processed (decoded + resized/converted)
+const DECODED_WORKING_COPIES: u64 = 2;
+
+/// Limits the estimated memory of concurrently decoded images
+/// Permits are KiB of estimated memory; tokio's semaphore is fair, so a large image
+/// waiting for room isn't starved by smaller ones queued behind it
+#[derive(Clone)]
+struct MemoryBudget {
+    semaphore: Arc<Semaphore>,
+    capacity_kib: u32,
+}
+
+impl MemoryBudget {
+    fn new(max_memory: ByteSize) -> Result<Self> {
+        let capacity_kib = (max_memory.as_u64() / 1024)
+            .min(Semaphore::MAX_PERMITS as u64)
+            .min(u32::MAX as u64) as u32;
+        if capacity_kib == 0 {
+            return Err(CompressError::invalid_parameter(
+                "max_memory",
+                format!("{} (must be at least 1 KiB)", max_memory),
+            ));
+        }
+
+        Ok(Self {
+            semaphore: Arc::new(Semaphore::new(capacity_kib as usize)),
+            capacity_kib,
+        })
+    }
+
+    /// Waits until `bytes` fit in the budget and reserves them until the permit drops
+    /// Estimates above the ceiling (or unknown ones) reserve the whole budget, so
+    /// the image runs alone instead of never starting
+    async fn acquire(&self, bytes: Option<u64>) -> Result<OwnedSemaphorePermit> {
+        let kib = bytes
+            .map(|bytes| bytes.div_ceil(1024).clamp(1, self.capacity_kib as u64) as u32)
+            .unwrap_or(self.capacity_kib);
+
+        Arc::clone(&self.semaphore)
+            .acquire_many_owned(kib)
+            .await
+            .map_err(|e| {
+                CompressError::process_failed(format!("Failed to acquire memory budget: {}", e))
+            })
+    }
+}
+
+/// Estimates the memory needed to decode and process an image from its header dimensions
+/// Returns None if the dimensions can't be read without decoding
+fn estimate_decode_memory(path: &Path) -> Option<u64> {
+    let (width, height) = image::image_dimensions(path).ok().or_else(|| {
+        // HEIC/HEIF aren't readable by the image crate, but FFmpeg can report them
+        let info = probe_media(path).ok()?;
+        Some((info.width?, info.height?))
+    })?;
+
+    let bytes = width as u64 * height as u64 * DECODED_BYTES_PER_PIXEL * DECODED_WORKING_COPIES;
+    debug!(
+        "Estimated {} to process {} ({}x{})",
+        ByteSize::b(bytes),
+        path.display(),
+        width,
+        height
+    );
+    Some(bytes)
+}
+
+/// Builds compression options for an image file in a batch
+/// A named image preset decides optimize/progressive/lossless; without one, files are optimized
+fn image_options_for(
+    file: PathBuf,
+    batch_options: &BatchOptions,
+    config: &Config,
+) -> ImageCompressionOptions {
+    let output_dir = output_dir_for(&file, batch_options);
+    let preset = batch_options
+        .image_preset
+        .as_deref()
+        .and_then(|name| config.get_image_preset(name));
+
+    ImageCompressionOptions {
+        input: file,
+        output: None,
+        quality: batch_options
+            .image_quality
+            .or(preset.map(|preset| preset.quality)),
+        format: None,
+        resize: None,
+        max_width: None,
+        max_height: None,
+        optimize: preset.is_none_or(|preset| preset.optimize),
+        progressive: preset.is_some_and(|preset| preset.progressive),
+        lossless: preset.is_some_and(|preset| preset.lossless),
+        preset: batch_options.image_preset.clone(),
+        chroma: None,
+        input_format: None,
+        strip: false,
+        downscale_only: false,
+        preset_from: None,
+        max_bytes: None,
+        strip_metadata: false,
+        target_size: None,
+        auto_orient: true,
+        resize_mode: ResizeMode::Exact,
+        filter: ResizeFilter::Lanczos3,
+        crop: None,
+        output_dir,
+        overwrite: batch_options.overwrite || batch_options.only_stale,
+        pre_hook: batch_options.pre_hook.clone(),
+        post_hook: batch_options.post_hook.clone(),
+    }
+}
+
 /// Results of processing a batch of files
 #[derive(Debug, Default)]
 pub struct BatchResults {
-    pub videos: Vec<PathBuf>,
-    pub images: Vec<PathBuf>,
+    /// Paths and sizes of each processed video
+    pub videos: Vec<CompressionReport>,
+    /// Paths and sizes of each processed image
+    pub images: Vec<CompressionReport>,
     pub failed_videos: Vec<PathBuf>,
     pub failed_images: Vec<PathBuf>,
+    pub skipped_fresh: usize,
+    pub skipped_existing: usize,
+    pub deleted_originals: usize,
 }
 
 impl BatchResults {
@@ -345,15 +985,202 @@ impl BatchResults {
 }
 
 /// Internal structure for tracking processing results
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct ProcessingResults {
-    successful: Vec<PathBuf>,
+    successful: Vec<(PathBuf, PathBuf)>,
     failed: Vec<PathBuf>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::{HookContext, run_hook};
+    use indicatif::ProgressDrawTarget;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_transient_failure_is_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+
+        // Fails like a busy file on the first call, then succeeds
+        let job = move |file: PathBuf| {
+            let counter = Arc::clone(&counter);
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(CompressError::Io(std::io::Error::new(
+                        std::io::ErrorKind::ResourceBusy,
+                        "file is locked",
+                    )));
+                }
+                Ok(file.with_extension("out"))
+            }
+        };
+
+        let files = vec![PathBuf::from("clip.mp4")];
+        let multi_progress = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let results = run_jobs(files, 1, 1, &multi_progress, None, "Video", job)
+            .await
+            .unwrap();
+        assert_eq!(
+            results.successful,
+            [(PathBuf::from("clip.mp4"), PathBuf::from("clip.out"))]
+        );
+        assert!(results.failed.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failure_is_not_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let job = move |_file: PathBuf| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move { Err(CompressError::invalid_parameter("crf", "99")) }
+        };
+
+        let files = vec![PathBuf::from("clip.mp4")];
+        let multi_progress = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let results = run_jobs(files, 1, 3, &multi_progress, None, "Video", job)
+            .await
+            .unwrap();
+        assert_eq!(results.failed, [PathBuf::from("clip.mp4")]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_hook_is_not_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let job = move |file: PathBuf| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let context = HookContext {
+                    input: &file,
+                    output: None,
+                    input_size: None,
+                    output_size: None,
+                };
+                run_hook("post-hook", "exit 3", &context)?;
+                Ok(file.with_extension("out"))
+            }
+        };
+
+        let files = vec![PathBuf::from("clip.mp4")];
+        let multi_progress = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let results = run_jobs(files, 1, 3, &multi_progress, None, "Video", job)
+            .await
+            .unwrap();
+        assert_eq!(results.failed, [PathBuf::from("clip.mp4")]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_budget_limits_in_flight_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.png");
+        image::RgbImage::new(64, 32).save(&path).unwrap();
+        assert_eq!(estimate_decode_memory(&path), Some(64 * 32 * 4 * 2));
+
+        let budget = MemoryBudget::new(ByteSize::kib(100)).unwrap();
+        let first = budget.acquire(Some(60 * 1024)).await.unwrap();
+        let wait = Duration::from_millis(50);
+
+        // 60 + 60 KiB exceeds the ceiling, so the second image has to wait
+        assert!(
+            timeout(wait, budget.acquire(Some(60 * 1024)))
+                .await
+                .is_err()
+        );
+        assert!(timeout(wait, budget.acquire(Some(40 * 1024))).await.is_ok());
+
+        // An image larger than the ceiling runs once everything else has finished
+        assert!(timeout(wait, budget.acquire(Some(u64::MAX))).await.is_err());
+        drop(first);
+        assert!(timeout(wait, budget.acquire(Some(u64::MAX))).await.is_ok());
+
+        assert!(MemoryBudget::new(ByteSize::b(100)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_budget_admits_files_in_order() {
+        // The first image needs the whole 100 KiB budget, the rest 2 KiB each
+        let dir = tempfile::tempdir().unwrap();
+        image::RgbImage::new(64, 200)
+            .save(dir.path().join("a_large.png"))
+            .unwrap();
+        let mut files = vec![dir.path().join("a_large.png")];
+        for name in ["b.png", "c.png", "d.png"] {
+            image::RgbImage::new(16, 16)
+                .save(dir.path().join(name))
+                .unwrap();
+            files.push(dir.path().join(name));
+        }
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let starts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let job = {
+            let in_flight = Arc::clone(&in_flight);
+            let starts = Arc::clone(&starts);
+            move |file: PathBuf| {
+                let in_flight = Arc::clone(&in_flight);
+                let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                starts.lock().unwrap().push((file.clone(), running));
+                async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok(file)
+                }
+            }
+        };
+
+        let budget = MemoryBudget::new(ByteSize::kib(100)).unwrap();
+        let multi_progress = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let results = run_jobs(
+            files.clone(),
+            4,
+            0,
+            &multi_progress,
+            Some(budget),
+            "Image",
+            job,
+        )
+        .await
+        .unwrap();
+        assert_eq!(results.successful.len(), 4);
+
+        // Files start in order, and the large one runs alone
+        let starts = starts.lock().unwrap().clone();
+        let order: Vec<PathBuf> = starts.iter().map(|(file, _)| file.clone()).collect();
+        assert_eq!(order, files);
+        assert_eq!(starts[0].1, 1);
+        assert_eq!(starts[1].1, 1);
+    }
+
+    #[test]
+    fn test_verify_video_encoders() {
+        let config = Config::default();
+        let codec = config
+            .get_video_preset(&VideoPreset::Medium)
+            .unwrap()
+            .codec
+            .to_string();
+        let no_encoders = |_: &str| false;
+
+        let err = verify_video_encoders(&config, &VideoPreset::Medium, &[], no_encoders)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(&codec));
+
+        // A working fallback is enough to start the batch
+        let only_vp9 = |encoder: &str| encoder == "libvpx-vp9";
+        assert!(
+            verify_video_encoders(&config, &VideoPreset::Medium, &[VideoCodec::Vp9], only_vp9)
+                .is_ok()
+        );
+        assert!(verify_video_encoders(&config, &VideoPreset::Medium, &[], |_| true).is_ok());
+    }
 
     #[test]
     fn test_separate_files() {
@@ -373,11 +1200,259 @@ mod tests {
         assert_eq!(images.len(), 2);
     }
 
+    fn sample_options(directory: &Path) -> BatchOptions {
+        BatchOptions {
+            directory: directory.to_path_buf(),
+            pattern: "*".to_string(),
+            exclude: Vec::new(),
+            videos: true,
+            images: true,
+            recursive: false,
+            video_preset: VideoPreset::Medium,
+            image_quality: None,
+            image_preset: None,
+            delete_originals: false,
+            skip_existing: false,
+            max_memory: None,
+            only_stale: false,
+            sort: BatchSort::Name,
+            fallback_codec: Vec::new(),
+            jobs: 1,
+            output_dir: None,
+            overwrite: false,
+            pre_hook: None,
+            post_hook: None,
+        }
+    }
+
+    #[test]
+    fn test_find_files_skips_excluded() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["clip.mp4", "clip_small.mp4", "notes.txt"] {
+            std::fs::write(dir.path().join(name), b"data").unwrap();
+        }
+
+        let options = BatchOptions {
+            pattern: "*.mp4".to_string(),
+            exclude: vec!["*_small.mp4".to_string()],
+            ..sample_options(dir.path())
+        };
+
+        let processor = BatchProcessor::new(Config::default(), false, false);
+        let files = processor.find_files(&options).unwrap();
+        assert_eq!(files, [dir.path().join("clip.mp4")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_files_follows_file_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        std::fs::write(target.path().join("real.jpg"), b"data").unwrap();
+        std::fs::write(dir.path().join("photo.jpg"), b"data").unwrap();
+        std::os::unix::fs::symlink(target.path().join("real.jpg"), dir.path().join("link.jpg"))
+            .unwrap();
+        std::os::unix::fs::symlink(target.path(), dir.path().join("folder.jpg")).unwrap();
+
+        let processor = BatchProcessor::new(Config::default(), false, false);
+        let files = processor.find_files(&sample_options(dir.path())).unwrap();
+        assert_eq!(
+            files,
+            [dir.path().join("link.jpg"), dir.path().join("photo.jpg")]
+        );
+    }
+
+    #[test]
+    fn test_find_files_sort_is_deterministic() {
+        // Enough files for the threaded scan, with repeated sizes and mtimes
+        let dir = tempfile::tempdir().unwrap();
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut expected = Vec::new();
+        for i in 0..300u64 {
+            let path = dir.path().join(format!("photo{:03}.jpg", i));
+            let size = i * 7 % 50;
+            let modified = epoch + Duration::from_secs(i * 13 % 100);
+            std::fs::write(&path, vec![0u8; size as usize]).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+            expected.push((path, size, modified));
+        }
+
+        let processor = BatchProcessor::new(Config::default(), false, false);
+        let find = |sort| {
+            let options = BatchOptions {
+                sort,
+                ..sample_options(dir.path())
+            };
+            processor.find_files(&options).unwrap()
+        };
+        let paths = |expected: &[(PathBuf, u64, SystemTime)]| -> Vec<PathBuf> {
+            expected.iter().map(|(path, _, _)| path.clone()).collect()
+        };
+
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(find(BatchSort::Name), paths(&expected));
+
+        expected.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        assert_eq!(find(BatchSort::Size), paths(&expected));
+
+        expected.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+        for _ in 0..3 {
+            assert_eq!(find(BatchSort::Modified), paths(&expected));
+        }
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<u32> = (0..5000).collect();
+        let doubled = parallel_map(items, |n| n * 2);
+        assert_eq!(doubled, (0..5000).map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_find_files_skips_compressed_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "clip.mp4",
+            "clip_compressed_medium.mp4",
+            "photo.jpg",
+            "photo_compressed.jpg",
+        ] {
+            std::fs::write(dir.path().join(name), b"data").unwrap();
+        }
+
+        let processor = BatchProcessor::new(Config::default(), false, false);
+        let files = processor.find_files(&sample_options(dir.path())).unwrap();
+        assert_eq!(
+            files,
+            [dir.path().join("clip.mp4"), dir.path().join("photo.jpg")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_existing_skips_files_with_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["done.jpg", "done_compressed.jpg", "new.jpg"] {
+            std::fs::write(dir.path().join(name), b"data").unwrap();
+        }
+
+        let options = BatchOptions {
+            skip_existing: true,
+            ..sample_options(dir.path())
+        };
+
+        let processor = BatchProcessor::new(Config::default(), true, false);
+        let results = processor.process_directory(options).await.unwrap();
+        assert_eq!(results.skipped_existing, 1);
+        assert_eq!(results.images.len(), 1);
+        assert_eq!(results.images[0].input, dir.path().join("new.jpg"));
+        assert_eq!(
+            results.images[0].output,
+            dir.path().join("new_compressed.jpg")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_recursive_output_mirrors_input_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input");
+        let output = dir.path().join("output");
+        for name in ["a.jpg", "sub/a.jpg", "sub/deeper/b.png"] {
+            let path = input.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"data").unwrap();
+        }
+
+        let options = BatchOptions {
+            recursive: true,
+            output_dir: Some(output.clone()),
+            ..sample_options(&input)
+        };
+
+        let processor = BatchProcessor::new(Config::default(), true, false);
+        let results = processor.process_directory(options).await.unwrap();
+        let mut outputs: Vec<PathBuf> = results
+            .images
+            .into_iter()
+            .map(|report| report.output)
+            .collect();
+        outputs.sort();
+        assert_eq!(
+            outputs,
+            [
+                output.join("a_compressed.jpg"),
+                output.join("sub").join("a_compressed.jpg"),
+                output.join("sub").join("deeper").join("b_compressed.png"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delete_original_only_after_smaller_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, size: usize| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, vec![0u8; size]).unwrap();
+            path
+        };
+
+        let input = write("clip.mp4", 2048);
+        let output = write("clip_compressed.mp4", 1024);
+        let report = CompressionReport::new(&input, &output, false).unwrap();
+        assert!(delete_original(&report).unwrap());
+        assert!(!input.exists());
+
+        // An output that came out larger means the compression didn't pay off
+        let input = write("photo.jpg", 1024);
+        let output = write("photo_compressed.jpg", 2048);
+        let report = CompressionReport::new(&input, &output, false).unwrap();
+        assert!(!delete_original(&report).unwrap());
+        assert!(input.exists());
+
+        let report = CompressionReport::new(&input, &input, false).unwrap();
+        assert!(!delete_original(&report).unwrap());
+        assert!(input.exists());
+    }
+
+    #[test]
+    fn test_image_preset_applies_to_batch_images() {
+        let options = BatchOptions {
+            image_preset: Some("lossless".to_string()),
+            ..sample_options(Path::new("photos"))
+        };
+
+        let image_options =
+            image_options_for(PathBuf::from("photos/a.png"), &options, &Config::default());
+        assert!(image_options.lossless);
+        assert_eq!(image_options.quality, Some(100));
+        assert_eq!(image_options.preset.as_deref(), Some("lossless"));
+
+        let default_options = image_options_for(
+            PathBuf::from("photos/a.png"),
+            &sample_options(Path::new("photos")),
+            &Config::default(),
+        );
+        assert!(default_options.optimize);
+        assert!(!default_options.lossless);
+    }
+
     #[test]
     fn test_batch_results() {
+        let report = |input: &str, output: &str| CompressionReport {
+            input: PathBuf::from(input),
+            output: PathBuf::from(output),
+            original_size: 2048,
+            compressed_size: Some(1024),
+            ratio: Some(50.0),
+        };
+
         let mut results = BatchResults::default();
-        results.videos.push(PathBuf::from("output1.mp4"));
-        results.images.push(PathBuf::from("output1.jpg"));
+        results.videos.push(report("input1.mov", "output1.mp4"));
+        results.images.push(report("input1.png", "output1.jpg"));
         results.failed_videos.push(PathBuf::from("failed.mp4"));
 
         assert_eq!(results.total_files(), 2);
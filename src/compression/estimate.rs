@@ -0,0 +1,274 @@
+use crate::cli::args::{ImageFormat, VideoCodec, VideoPreset};
+use crate::core::{CompressError, Config, DEFAULT_IMAGE_QUALITY, Result, VideoPresetConfig};
+use crate::ui::progress::{print_header, print_info};
+use crate::utils::{
+    MediaInfo, calculate_compression_ratio, get_extension_lowercase, get_file_size, is_image_file,
+    is_video_file, parse_bitrate, probe_media, validate_input_file,
+};
+use bytesize::ByteSize;
+use std::path::PathBuf;
+
+/// Approximate error margin of video estimates, in percent
+const VIDEO_ESTIMATE_MARGIN: u32 = 40;
+
+/// Approximate error margin of image estimates, in percent
+const IMAGE_ESTIMATE_MARGIN: u32 = 30;
+
+/// Bits per pixel per frame produced by H.264 at CRF 23 on typical content
+const H264_CRF23_BITS_PER_PIXEL: f64 = 0.08;
+
+pub struct SizeEstimator {
+    pub config: Config,
+}
+
+#[derive(Debug, Clone)]
+pub struct EstimateOptions {
+    pub input: PathBuf,
+    pub preset: VideoPreset,
+    pub codec: Option<VideoCodec>,
+    pub crf: Option<u8>,
+    pub quality: Option<u8>,
+    pub format: Option<ImageFormat>,
+}
+
+/// Predicted output size for a file
+#[derive(Debug, Clone)]
+pub struct SizeEstimate {
+    pub original_size: u64,
+    pub estimated_size: u64,
+    pub margin_percent: u32,
+}
+
+impl SizeEstimator {
+    /// Creates a new SizeEstimator instance
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Estimates the compressed size of a video or image without encoding it
+    /// Uses probed properties and a heuristic model, so results are approximate
+    pub fn estimate(&self, options: &EstimateOptions) -> Result<SizeEstimate> {
+        validate_input_file(&options.input)?;
+
+        if is_video_file(&options.input) {
+            self.estimate_video(options)
+        } else if is_image_file(&options.input) {
+            self.estimate_image(options)
+        } else {
+            Err(CompressError::unsupported_format(
+                options.input.display().to_string(),
+            ))
+        }
+    }
+
+    /// Prints an estimate in a human-readable form
+    pub fn print_estimate(&self, options: &EstimateOptions, estimate: &SizeEstimate) {
+        print_header(&format!("Size Estimate: {}", options.input.display()));
+        println!("Original size:  {}", ByteSize::b(estimate.original_size));
+        println!(
+            "Estimated size: {} (±{}%)",
+            ByteSize::b(estimate.estimated_size),
+            estimate.margin_percent
+        );
+        println!(
+            "Estimated reduction: {:.1}%",
+            calculate_compression_ratio(estimate.original_size, estimate.estimated_size)
+        );
+        print_info("Heuristic estimate only; no encoding was performed");
+    }
+
+    /// Estimates video size from probed bitrate, resolution and frame rate
+    fn estimate_video(&self, options: &EstimateOptions) -> Result<SizeEstimate> {
+        let preset_config = self.video_preset_config(options)?;
+        let info = probe_media(&options.input)?;
+        let original_size = get_file_size(&options.input)?.as_u64();
+
+        // Remuxing keeps the streams as they are
+        if matches!(preset_config.codec, VideoCodec::Copy) {
+            return Ok(SizeEstimate {
+                original_size,
+                estimated_size: original_size,
+                margin_percent: VIDEO_ESTIMATE_MARGIN,
+            });
+        }
+
+        let duration = info.duration.ok_or_else(|| {
+            CompressError::ffmpeg_error("Could not determine video duration", None)
+        })?;
+
+        let video_bitrate = match &preset_config.bitrate {
+            Some(bitrate) => parse_bitrate(bitrate)? as f64,
+            None => estimate_crf_bitrate(&info, &preset_config),
+        };
+        let audio_bitrate = match &preset_config.audio_bitrate {
+            Some(bitrate) => parse_bitrate(bitrate)? as f64,
+            None => 128_000.0,
+        };
+
+        let estimated_size = ((video_bitrate + audio_bitrate) * duration / 8.0) as u64;
+
+        Ok(SizeEstimate {
+            original_size,
+            estimated_size,
+            margin_percent: VIDEO_ESTIMATE_MARGIN,
+        })
+    }
+
+    /// Estimates image size from pixel count, output format and quality
+    fn estimate_image(&self, options: &EstimateOptions) -> Result<SizeEstimate> {
+        let (width, height) = image::image_dimensions(&options.input)?;
+        let original_size = get_file_size(&options.input)?.as_u64();
+
+        let format = options
+            .format
+            .clone()
+            .or_else(|| {
+                get_extension_lowercase(&options.input).and_then(|ext| match ext.as_str() {
+                    "png" => Some(ImageFormat::Png),
+                    "webp" => Some(ImageFormat::Webp),
+                    "avif" => Some(ImageFormat::Avif),
+                    _ => None,
+                })
+            })
+            .unwrap_or(ImageFormat::Jpeg);
+
+        let quality = options
+            .quality
+            .or_else(|| self.config.format_quality_default(&format))
+            .unwrap_or(DEFAULT_IMAGE_QUALITY);
+        let bits_per_pixel = image_bits_per_pixel(&format, quality);
+        let estimated_size = (width as f64 * height as f64 * bits_per_pixel / 8.0) as u64;
+
+        Ok(SizeEstimate {
+            original_size,
+            estimated_size,
+            margin_percent: IMAGE_ESTIMATE_MARGIN,
+        })
+    }
+
+    /// Gets the video preset with command-line overrides applied
+    fn video_preset_config(&self, options: &EstimateOptions) -> Result<VideoPresetConfig> {
+        let mut config = self
+            .config
+            .get_video_preset(&options.preset)
+            .cloned()
+            .ok_or_else(|| CompressError::config(format!("Unknown preset: {}", options.preset)))?;
+
+        if let Some(codec) = &options.codec {
+            config.codec = codec.clone();
+        }
+        if let Some(crf) = options.crf {
+            config.crf = Some(crf);
+            config.bitrate = None;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Models the bitrate a CRF encode will produce
+/// Starts from an H.264 CRF 23 baseline, halves every 6 CRF steps, and scales
+/// by codec efficiency and encoder preset; never exceeds the source bitrate
+fn estimate_crf_bitrate(info: &MediaInfo, preset_config: &VideoPresetConfig) -> f64 {
+    let pixels = info.width.unwrap_or(1280) as f64 * info.height.unwrap_or(720) as f64;
+    let fps = info.frame_rate.unwrap_or(30.0);
+    let crf = preset_config.crf.unwrap_or(23) as f64;
+
+    let codec_factor = match preset_config.codec {
+        VideoCodec::H264 => 1.0,
+        VideoCodec::H265 => 0.6,
+        VideoCodec::Vp9 => 0.65,
+        VideoCodec::Av1 => 0.5,
+        // Stream copy is handled before modelling; treat it like the baseline
+        VideoCodec::Copy => 1.0,
+    };
+    let preset_factor = match preset_config.preset.as_str() {
+        "ultrafast" => 1.4,
+        "fast" => 1.1,
+        "slow" => 0.95,
+        "veryslow" => 0.9,
+        _ => 1.0,
+    };
+
+    let bitrate = pixels
+        * fps
+        * H264_CRF23_BITS_PER_PIXEL
+        * 2f64.powf((23.0 - crf) / 6.0)
+        * codec_factor
+        * preset_factor;
+
+    match info.bit_rate {
+        Some(source) => bitrate.min(source as f64),
+        None => bitrate,
+    }
+}
+
+/// Typical bits per pixel for photographic content in each output format
+fn image_bits_per_pixel(format: &ImageFormat, quality: u8) -> f64 {
+    let q = quality.clamp(1, 100) as f64 / 100.0;
+    let jpeg = 0.4 + 3.2 * q.powi(3);
+
+    match format {
+        ImageFormat::Jpeg => jpeg,
+        ImageFormat::Webp => jpeg * 0.7,
+        ImageFormat::Avif => jpeg * 0.5,
+        ImageFormat::Png => 12.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_info() -> MediaInfo {
+        MediaInfo {
+            duration: Some(60.0),
+            bit_rate: Some(50_000_000),
+            width: Some(1920),
+            height: Some(1080),
+            frame_rate: Some(30.0),
+            real_frame_rate: Some(30.0),
+            video_codec: None,
+            audio_codec: None,
+            field_order: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_crf_bitrate_halves_every_six_steps() {
+        let config = Config::default();
+        let mut preset = config.video_presets["medium"].clone();
+
+        preset.crf = Some(23);
+        let base = estimate_crf_bitrate(&sample_info(), &preset);
+        preset.crf = Some(29);
+        let higher_crf = estimate_crf_bitrate(&sample_info(), &preset);
+
+        assert!((base / higher_crf - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_crf_bitrate_capped_at_source() {
+        let config = Config::default();
+        let preset = config.video_presets["medium"].clone();
+        let info = MediaInfo {
+            bit_rate: Some(1_000),
+            ..sample_info()
+        };
+
+        assert_eq!(estimate_crf_bitrate(&info, &preset), 1_000.0);
+    }
+
+    #[test]
+    fn test_image_bits_per_pixel() {
+        assert!(
+            image_bits_per_pixel(&ImageFormat::Jpeg, 95)
+                > image_bits_per_pixel(&ImageFormat::Jpeg, 50)
+        );
+        assert!(
+            image_bits_per_pixel(&ImageFormat::Webp, 85)
+                < image_bits_per_pixel(&ImageFormat::Jpeg, 85)
+        );
+    }
+}
@@ -1,13 +1,36 @@
-use crate::cli::args::ImageFormat;
-use crate::core::{CompressError, Config, DEFAULT_IMAGE_QUALITY, Result};
-use crate::ui::progress::print_success;
+use crate::cli::args::{ChromaSubsampling, ImageFormat, ResizeFilter, ResizeMode};
+#[cfg(feature = "avif")]
+use crate::core::DEFAULT_AVIF_SPEED;
+use crate::core::{
+    CompressError, Config, DEFAULT_IMAGE_QUALITY, HEIF_EXTENSIONS, ImagePresetConfig,
+    MIN_FFMPEG_HEIF_VERSION, Result,
+};
+use crate::ui::progress::{print_info, print_success, print_warning};
 use crate::utils::{
-    calculate_compression_ratio, check_output_overwrite, ensure_parent_dir, generate_output_path,
-    get_extension_lowercase, get_file_size, validate_input_file, validate_safe_path,
+    FFmpegCommandBuilder, HookContext, backup_original, calculate_compression_ratio,
+    check_ffmpeg_min_version, check_output_overwrite, ensure_parent_dir, expand_hook,
+    generate_output_path, get_extension_lowercase, get_file_size, is_special_file, quiet_output,
+    read_exif_orientation, run_hook, strip_image_metadata, strip_private_metadata,
+    validate_input_file, validate_safe_path,
 };
-use image::{DynamicImage, ImageFormat as ImageLibFormat};
+use bytesize::ByteSize;
+#[cfg(feature = "avif")]
+use image::codecs::avif::AvifEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder};
+use image::error::{EncodingError, ImageFormatHint};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageError, ImageFormat as ImageLibFormat, Rgba};
+use jpeg_encoder::{ColorType as JpegColorType, Encoder, SamplingFactor};
 use log::{debug, info};
 use std::path::{Path, PathBuf};
+use webp::Encoder as WebPEncoder;
+
+/// Largest percentage accepted by `--resize N%`
+const MAX_RESIZE_PERCENT: f64 = 1000.0;
+
+/// Upper bound on encodes when searching for a quality that fits --max-bytes/--target-size
+/// Seven halvings cover the full 1-100 range
+const MAX_BUDGET_SEARCH_ITERATIONS: usize = 8;
 
 pub struct ImageCompressor {
     pub config: Config,
@@ -19,7 +42,7 @@ pub struct ImageCompressor {
 pub struct ImageCompressionOptions {
     pub input: PathBuf,
     pub output: Option<PathBuf>,
-    pub quality: u8,
+    pub quality: Option<u8>,
     pub format: Option<ImageFormat>,
     pub resize: Option<String>,
     pub max_width: Option<u32>,
@@ -28,6 +51,125 @@ pub struct ImageCompressionOptions {
     pub progressive: bool,
     pub lossless: bool,
     pub preset: Option<String>,
+    pub chroma: Option<ChromaSubsampling>,
+    pub input_format: Option<String>,
+    pub strip: bool,
+    pub downscale_only: bool,
+    pub preset_from: Option<PathBuf>,
+    pub max_bytes: Option<ByteSize>,
+    pub strip_metadata: bool,
+    pub target_size: Option<ByteSize>,
+    pub auto_orient: bool,
+    pub resize_mode: ResizeMode,
+    pub filter: ResizeFilter,
+    pub crop: Option<CropRegion>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
+}
+
+impl ImageCompressionOptions {
+    /// Creates options for compressing `input` with the same defaults as the `image` command
+    pub fn new(input: impl Into<PathBuf>) -> Self {
+        Self {
+            input: input.into(),
+            output: None,
+            quality: None,
+            format: None,
+            resize: None,
+            max_width: None,
+            max_height: None,
+            optimize: false,
+            progressive: false,
+            lossless: false,
+            preset: None,
+            chroma: None,
+            input_format: None,
+            strip: false,
+            downscale_only: false,
+            preset_from: None,
+            max_bytes: None,
+            strip_metadata: false,
+            target_size: None,
+            auto_orient: true,
+            resize_mode: ResizeMode::default(),
+            filter: ResizeFilter::default(),
+            crop: None,
+            output_dir: None,
+            overwrite: false,
+            pre_hook: None,
+            post_hook: None,
+        }
+    }
+}
+
+/// A rectangular region of an image, in pixels from the top-left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRegion {
+    /// Parses a region from `x,y,width,height`
+    pub fn parse(crop: &str) -> Result<Self> {
+        let values = crop
+            .split(',')
+            .map(|value| value.trim().parse::<u32>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| CompressError::invalid_parameter("crop", crop))?;
+
+        let [x, y, width, height] = values[..] else {
+            return Err(CompressError::invalid_parameter(
+                "crop",
+                format!("{} (expected x,y,width,height)", crop),
+            ));
+        };
+        if width == 0 || height == 0 {
+            return Err(CompressError::invalid_parameter(
+                "crop",
+                "Width and height must be greater than 0",
+            ));
+        }
+
+        Ok(Self {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Checks that the region lies within an image of the given size
+    fn validate_within(&self, width: u32, height: u32) -> Result<()> {
+        let right = self.x as u64 + self.width as u64;
+        let bottom = self.y as u64 + self.height as u64;
+        if right > width as u64 || bottom > height as u64 {
+            return Err(CompressError::invalid_parameter(
+                "crop",
+                format!(
+                    "{}x{} at {},{} extends outside the {}x{} image",
+                    self.width, self.height, self.x, self.y, width, height
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CollageOptions {
+    pub inputs: Vec<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub columns: u32,
+    pub tile_size: String,
+    pub padding: u32,
+    pub background: String,
+    pub format: ImageFormat,
+    pub quality: Option<u8>,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
 }
@@ -54,13 +196,39 @@ impl ImageCompressor {
         // Apply preset configuration if specified
         self.apply_preset_config(&mut options)?;
 
+        // Reject unknown decoder hints before doing any work
+        parse_input_format(options.input_format.as_deref())?;
+
         // Get original file size
         let original_size = get_file_size(&options.input)?;
 
         // Determine output format and path
         let output_format = self.determine_output_format(&options)?;
+        self.apply_format_quality_default(&mut options, &output_format);
         let output_path = self.generate_output_path(&options, &output_format)?;
 
+        if options.chroma.is_some() && !matches!(output_format, ImageFormat::Jpeg) {
+            print_warning("--chroma only applies to JPEG output and will be ignored");
+        }
+
+        // PNG and lossless WebP ignore quality, so there's nothing to search over
+        let quality_sets_size = match output_format {
+            ImageFormat::Jpeg | ImageFormat::Avif => true,
+            ImageFormat::Webp => !options.lossless,
+            ImageFormat::Png => false,
+        };
+        if let Some(parameter) = size_limit_parameter(&options)
+            && !quality_sets_size
+        {
+            return Err(CompressError::invalid_parameter(
+                parameter,
+                format!(
+                    "not supported for {} output, only JPEG, AVIF and lossy WebP",
+                    output_format
+                ),
+            ));
+        }
+
         // Ensure parent directory exists
         ensure_parent_dir(&output_path)?;
 
@@ -73,21 +241,66 @@ impl ImageCompressor {
             output_path.display()
         );
 
+        // Let the user's pre-hook validate or prepare the input
+        self.run_pre_hook(&options, &output_path, original_size.as_u64())?;
+
         if self.dry_run {
             self.print_dry_run_info(&options, &output_format, &output_path);
             return Ok(output_path);
         }
 
+        // Keep a copy of the source in case the output replaces it
+        if self.config.default_settings.backup_originals {
+            let backup = backup_original(&options.input)?;
+            debug!("Backed up original to {}", backup.display());
+        }
+
         // Load image
         info!("Loading image...");
-        let mut img = image::open(&options.input).map_err(CompressError::Image)?;
+        let mut img = load_image(&options)?;
 
         // Apply transformations
         img = self.apply_transformations(img, &options)?;
 
         // Compress and save
         info!("Compressing and saving...");
-        self.save_image(&img, &output_path, &output_format, &options)?;
+        if let Some(budget) = options.max_bytes {
+            let (quality, data) = self
+                .encode_within_budget(&img, &output_format, &options, budget.as_u64())?
+                .ok_or_else(|| {
+                    CompressError::invalid_parameter(
+                        "max_bytes",
+                        format!("{} can't be met, even quality 1 is larger", budget),
+                    )
+                })?;
+            std::fs::write(&output_path, data)?;
+            print_info(&format!("Quality {} fits the {} budget", quality, budget));
+        } else if let Some(target) = options.target_size {
+            let (quality, data) =
+                self.encode_near_target(&img, &output_format, &options, target.as_u64())?;
+            let size = ByteSize::b(data.len() as u64);
+            std::fs::write(&output_path, data)?;
+            if size > target {
+                print_warning(&format!(
+                    "Quality {} is the lowest, but its {} output is over the {} target",
+                    quality, size, target
+                ));
+            } else {
+                print_info(&format!(
+                    "Quality {} gives {} for the {} target",
+                    quality, size, target
+                ));
+            }
+        } else {
+            self.save_image(&img, &output_path, &output_format, &options)?;
+        }
+
+        // Streamed outputs have no meaningful size to compare
+        if is_special_file(&output_path) {
+            print_success("Image compressed successfully (streamed output)");
+            self.run_post_hook(&options, &output_path, original_size.as_u64(), None)?;
+            return Ok(output_path);
+        }
 
         // Calculate compression ratio
         let compressed_size = get_file_size(&output_path)?;
@@ -99,49 +312,258 @@ impl ImageCompressor {
             original_size, compressed_size, compression_ratio
         ));
 
+        self.run_post_hook(
+            &options,
+            &output_path,
+            original_size.as_u64(),
+            Some(compressed_size.as_u64()),
+        )?;
+
         Ok(output_path)
     }
 
-    /// Applies preset configuration to options
-    fn apply_preset_config(&self, options: &mut ImageCompressionOptions) -> Result<()> {
-        if let Some(preset_name) = &options.preset {
-            if let Some(preset) = self.config.get_image_preset(preset_name) {
-                // Only apply preset values if the option wasn't explicitly set by the user
-                // We need a better way to detect user-set vs default values
-                // For now, we'll apply preset values and let CLI overrides take precedence
-
-                // Apply preset quality only if it's still the default and not explicitly set
-                if options.quality == DEFAULT_IMAGE_QUALITY {
-                    options.quality = preset.quality;
-                }
+    /// Encodes the same image at several quality levels for side-by-side comparison
+    /// The image is decoded and transformed once; each output's size is added to its
+    /// file name, and an HTML page showing all variants is written if requested
+    pub async fn quality_sweep(
+        &self,
+        mut options: ImageCompressionOptions,
+        qualities: &[u8],
+        comparison_html: bool,
+    ) -> Result<Vec<QualitySample>> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
 
-                // Apply other preset options if they weren't explicitly enabled
-                if !options.optimize {
-                    options.optimize = preset.optimize;
-                }
-                if !options.progressive {
-                    options.progressive = preset.progressive;
-                }
-                if !options.lossless {
-                    options.lossless = preset.lossless;
+        if let Some(quality) = qualities.iter().find(|q| !(1..=100).contains(*q)) {
+            return Err(CompressError::invalid_parameter(
+                "quality_range",
+                format!("{} (must be 1-100)", quality),
+            ));
+        }
+
+        self.apply_preset_config(&mut options)?;
+        parse_input_format(options.input_format.as_deref())?;
+        let output_format = self.determine_output_format(&options)?;
+
+        if self.dry_run {
+            println!(
+                "\n{}",
+                console::style("DRY RUN - No files will be modified")
+                    .yellow()
+                    .bold()
+            );
+            println!("Input:   {}", options.input.display());
+            println!("Format:  {}", output_format);
+            for quality in qualities {
+                let path = self.sweep_output_path(&options, &output_format, *quality, None);
+                println!("Quality {:>3}: {}", quality, path.display());
+            }
+            return Ok(Vec::new());
+        }
+
+        info!("Loading image...");
+        let img = load_image(&options)?;
+        let img = self.apply_transformations(img, &options)?;
+
+        let mut samples = Vec::with_capacity(qualities.len());
+        for &quality in qualities {
+            options.quality = Some(quality);
+
+            // The size is only known after encoding, so encode first and rename after
+            let encoded_path = self.sweep_output_path(&options, &output_format, quality, None);
+            ensure_parent_dir(&encoded_path)?;
+            self.save_image(&img, &encoded_path, &output_format, &options)?;
+
+            let size = get_file_size(&encoded_path)?.as_u64();
+            let path = self.sweep_output_path(&options, &output_format, quality, Some(size));
+            if let Err(e) = check_output_overwrite(&path, options.overwrite) {
+                std::fs::remove_file(&encoded_path)?;
+                return Err(e);
+            }
+            std::fs::rename(&encoded_path, &path)?;
+
+            println!(
+                "Quality {:>3}: {:>10}  {}",
+                quality,
+                ByteSize::b(size),
+                path.display()
+            );
+            samples.push(QualitySample {
+                quality,
+                size,
+                path,
+            });
+        }
+
+        if comparison_html && let Some(first) = samples.first() {
+            let html_path = first.path.with_file_name(format!(
+                "{}_quality.html",
+                options
+                    .input
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            ));
+            check_output_overwrite(&html_path, options.overwrite)?;
+            std::fs::write(&html_path, comparison_html_page(&options.input, &samples))?;
+            print_success(&format!("Comparison page: {}", html_path.display()));
+        }
+
+        Ok(samples)
+    }
+
+    /// Builds the output path for one quality level of a sweep
+    /// Produces `<stem>_q<quality>.<ext>`, or `<stem>_q<quality>_<size>KB.<ext>` once the size is known
+    fn sweep_output_path(
+        &self,
+        options: &ImageCompressionOptions,
+        format: &ImageFormat,
+        quality: u8,
+        size: Option<u64>,
+    ) -> PathBuf {
+        let suffix = match size {
+            Some(size) => format!("_q{}_{}KB", quality, size.div_ceil(1024)),
+            None => format!("_q{}", quality),
+        };
+        generate_output_path(
+            &options.input,
+            options.output_dir.as_deref(),
+            Some(&suffix),
+            Some(&format.to_string()),
+        )
+    }
+
+    /// Runs the user's pre-hook before compressing a file
+    /// A non-zero exit aborts this file; in dry-run mode the command is only printed
+    fn run_pre_hook(
+        &self,
+        options: &ImageCompressionOptions,
+        output_path: &Path,
+        input_size: u64,
+    ) -> Result<()> {
+        if let Some(hook) = &options.pre_hook {
+            let context = HookContext {
+                input: &options.input,
+                output: Some(output_path),
+                input_size: Some(input_size),
+                output_size: None,
+            };
+            if self.dry_run {
+                if !quiet_output() {
+                    println!("Pre-hook (not run): {}", expand_hook(hook, &context));
                 }
             } else {
-                return Err(CompressError::config(format!(
-                    "Image preset '{}' not found",
-                    preset_name
-                )));
+                run_hook("Pre-hook", hook, &context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the user's post-hook for a successfully compressed file
+    fn run_post_hook(
+        &self,
+        options: &ImageCompressionOptions,
+        output_path: &Path,
+        input_size: u64,
+        output_size: Option<u64>,
+    ) -> Result<()> {
+        if let Some(hook) = &options.post_hook {
+            let context = HookContext {
+                input: &options.input,
+                output: Some(output_path),
+                input_size: Some(input_size),
+                output_size,
+            };
+            run_hook("Post-hook", hook, &context)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the output path that compressing with these options would produce
+    /// Used for planning batch runs without touching any files
+    pub fn planned_output_path(&self, options: &ImageCompressionOptions) -> Result<PathBuf> {
+        let mut options = options.clone();
+        self.apply_preset_config(&mut options)?;
+        let output_format = self.determine_output_format(&options)?;
+        self.apply_format_quality_default(&mut options, &output_format);
+        self.generate_output_path(&options, &output_format)
+    }
+
+    /// Applies preset configuration to options
+    /// A preset file given with --preset-from takes the place of a named preset
+    fn apply_preset_config(&self, options: &mut ImageCompressionOptions) -> Result<()> {
+        let preset = if let Some(path) = &options.preset_from {
+            Some(Config::load_preset_file::<ImagePresetConfig, _>(path)?)
+        } else {
+            // Fall back to the configured default preset
+            if options.preset.is_none() {
+                options.preset = self.config.default_settings.default_image_preset.clone();
+            }
+
+            match &options.preset {
+                Some(preset_name) => Some(
+                    self.config
+                        .get_image_preset(preset_name)
+                        .cloned()
+                        .ok_or_else(|| {
+                            CompressError::config(format!(
+                                "Image preset '{}' not found",
+                                preset_name
+                            ))
+                        })?,
+                ),
+                None => None,
+            }
+        };
+
+        if let Some(preset) = preset {
+            // Apply preset quality only if it wasn't explicitly set by the user
+            if options.quality.is_none() {
+                options.quality = Some(preset.quality);
+            }
+
+            // Apply other preset options if they weren't explicitly enabled
+            if !options.optimize {
+                options.optimize = preset.optimize;
+            }
+            if !options.progressive {
+                options.progressive = preset.progressive;
+            }
+            if !options.lossless {
+                options.lossless = preset.lossless;
             }
         }
         Ok(())
     }
 
+    /// Fills in quality when neither the user nor a preset set it
+    /// Precedence: explicit --quality > preset > per-format config default > global default
+    fn apply_format_quality_default(
+        &self,
+        options: &mut ImageCompressionOptions,
+        format: &ImageFormat,
+    ) {
+        if options.quality.is_none() {
+            options.quality = Some(
+                self.config
+                    .format_quality_default(format)
+                    .unwrap_or(DEFAULT_IMAGE_QUALITY),
+            );
+        }
+    }
+
     /// Determines output format from options or input file extension
     fn determine_output_format(&self, options: &ImageCompressionOptions) -> Result<ImageFormat> {
         if let Some(format) = &options.format {
             Ok(format.clone())
         } else {
-            // Try to determine from input extension
-            if let Some(extension) = get_extension_lowercase(&options.input) {
+            // Try to determine from the input format hint, then the input extension
+            let extension = options
+                .input_format
+                .as_deref()
+                .map(str::to_lowercase)
+                .or_else(|| get_extension_lowercase(&options.input));
+            if let Some(extension) = extension {
                 match extension.as_str() {
                     "jpg" | "jpeg" => Ok(ImageFormat::Jpeg),
                     "png" => Ok(ImageFormat::Png),
@@ -155,6 +577,104 @@ impl ImageCompressor {
         }
     }
 
+    /// Arranges the input images into a grid on a single canvas
+    /// Each image is scaled to fit its tile and centered in it; tiles are filled
+    /// left to right, top to bottom
+    /// Returns the path to the generated image
+    pub async fn collage(&self, options: CollageOptions) -> Result<PathBuf> {
+        if options.inputs.len() < 2 {
+            return Err(CompressError::invalid_parameter(
+                "inputs",
+                "a collage needs at least two images",
+            ));
+        }
+        for input in &options.inputs {
+            validate_input_file(input)?;
+            validate_safe_path(input)?;
+        }
+        if options.columns == 0 {
+            return Err(CompressError::invalid_parameter(
+                "columns",
+                "must be at least 1",
+            ));
+        }
+
+        let tile_size = self.parse_resize_dimensions(&options.tile_size)?;
+        let background = parse_hex_color(&options.background)?;
+        let (columns, rows) = collage_grid(options.inputs.len(), options.columns);
+        let (width, height) = collage_canvas_size(columns, rows, tile_size, options.padding);
+
+        let output_path = match &options.output {
+            Some(output) => {
+                validate_safe_path(output)?;
+                output.clone()
+            }
+            None => generate_output_path(
+                &options.inputs[0],
+                options.output_dir.as_deref(),
+                Some("_collage"),
+                Some(&options.format.to_string()),
+            ),
+        };
+        ensure_parent_dir(&output_path)?;
+        check_output_overwrite(&output_path, options.overwrite)?;
+
+        info!(
+            "Creating {}x{} collage of {} images -> {}",
+            columns,
+            rows,
+            options.inputs.len(),
+            output_path.display()
+        );
+
+        if self.dry_run {
+            println!(
+                "\n{}",
+                console::style("DRY RUN - No files will be modified")
+                    .yellow()
+                    .bold()
+            );
+            println!("Inputs:  {}", options.inputs.len());
+            println!("Output:  {}", output_path.display());
+            println!(
+                "Grid:    {}x{} tiles of {}",
+                columns, rows, options.tile_size
+            );
+            println!("Canvas:  {}x{}", width, height);
+            println!("Format:  {}", options.format);
+            return Ok(output_path);
+        }
+
+        let mut canvas = image::RgbaImage::from_pixel(width, height, background);
+        for (index, input) in options.inputs.iter().enumerate() {
+            let tile = open_image(input)?
+                .resize(tile_size.0, tile_size.1, FilterType::Lanczos3)
+                .to_rgba8();
+            let (x, y) = collage_tile_origin(index as u32, columns, tile_size, options.padding);
+            // Center the scaled image in its tile
+            let x = x + (tile_size.0 - tile.width()) / 2;
+            let y = y + (tile_size.1 - tile.height()) / 2;
+            imageops::overlay(&mut canvas, &tile, x.into(), y.into());
+            debug!("Placed {} at {}x{}", input.display(), x, y);
+        }
+
+        let quality = options.quality.unwrap_or_else(|| {
+            self.config
+                .format_quality_default(&options.format)
+                .unwrap_or(DEFAULT_IMAGE_QUALITY)
+        });
+        let data =
+            encode_with_quality(&DynamicImage::ImageRgba8(canvas), &options.format, quality)?;
+        std::fs::write(&output_path, data)?;
+
+        print_success(&format!(
+            "Collage created: {}x{} tiles, {}x{} pixels",
+            columns, rows, width, height
+        ));
+
+        Ok(output_path)
+    }
+
     /// Generates output path with proper naming and validation
     fn generate_output_path(
         &self,
@@ -183,11 +703,52 @@ impl ImageCompressor {
         mut img: DynamicImage,
         options: &ImageCompressionOptions,
     ) -> Result<DynamicImage> {
+        // Rotate first so size constraints apply to the dimensions as displayed
+        if options.auto_orient
+            && let Some(orientation) = read_exif_orientation(&options.input)
+        {
+            img.apply_orientation(orientation);
+            debug!("Applied EXIF orientation {:?}", orientation);
+        }
+
+        // Crop before resizing so the region is in source pixels
+        if let Some(crop) = &options.crop {
+            crop.validate_within(img.width(), img.height())?;
+            img = img.crop_imm(crop.x, crop.y, crop.width, crop.height);
+            debug!(
+                "Cropped to {}x{} at {},{}",
+                crop.width, crop.height, crop.x, crop.y
+            );
+        }
+
         // Resize if specified
         if let Some(resize_str) = &options.resize {
-            let (width, height) = self.parse_resize_dimensions(resize_str)?;
-            img = img.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
-            debug!("Resized image to {}x{}", width, height);
+            let (width, height) = self.resize_target(resize_str, img.width(), img.height())?;
+            let (width, height) = match options.resize_mode {
+                ResizeMode::Fit => fit_within(img.width(), img.height(), width, height),
+                ResizeMode::Exact | ResizeMode::Fill => (width, height),
+            };
+            if options.downscale_only && (width > img.width() || height > img.height()) {
+                debug!(
+                    "Keeping {}x{}: resizing to {}x{} would enlarge the image",
+                    img.width(),
+                    img.height(),
+                    width,
+                    height
+                );
+            } else {
+                img = match options.resize_mode {
+                    // Fit dimensions were already scaled to keep the aspect ratio
+                    ResizeMode::Exact | ResizeMode::Fit => {
+                        img.resize_exact(width, height, options.filter.into())
+                    }
+                    ResizeMode::Fill => img.resize_to_fill(width, height, options.filter.into()),
+                };
+                debug!(
+                    "Resized image to {}x{} ({:?})",
+                    width, height, options.resize_mode
+                );
+            }
         }
 
         // Apply max width/height constraints
@@ -213,7 +774,7 @@ impl ImageCompressor {
 
         // Apply resize if dimensions changed
         if new_width != current_width || new_height != current_height {
-            img = img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3);
+            img = img.resize(new_width, new_height, options.filter.into());
             debug!(
                 "Resized image to fit constraints: {}x{}",
                 new_width, new_height
@@ -231,35 +792,137 @@ impl ImageCompressor {
         format: &ImageFormat,
         options: &ImageCompressionOptions,
     ) -> Result<()> {
-        match format {
-            ImageFormat::Jpeg => {
-                // For JPEG, we could use more advanced encoding options
-                // but the image crate has limited JPEG encoder options
-                img.save_with_format(output_path, ImageLibFormat::Jpeg)?;
-            }
-            ImageFormat::Png => {
-                img.save_with_format(output_path, ImageLibFormat::Png)?;
-            }
-            ImageFormat::Webp => {
-                img.save_with_format(output_path, ImageLibFormat::WebP)?;
-            }
-            ImageFormat::Avif => {
-                return Err(CompressError::unsupported_format(
-                    "AVIF encoding not yet supported by the image crate",
-                ));
-            }
-        }
+        let data = self.encode_image(img, format, options)?;
+        std::fs::write(output_path, data)?;
 
         if self.verbose {
             debug!(
                 "Saved image with quality: {}, optimize: {}, progressive: {}, lossless: {}",
-                options.quality, options.optimize, options.progressive, options.lossless
+                options.quality.unwrap_or(DEFAULT_IMAGE_QUALITY),
+                options.optimize,
+                options.progressive,
+                options.lossless
             );
         }
 
         Ok(())
     }
 
+    /// Encodes an image into memory with format-specific options
+    fn encode_image(
+        &self,
+        img: &DynamicImage,
+        format: &ImageFormat,
+        options: &ImageCompressionOptions,
+    ) -> Result<Vec<u8>> {
+        let quality = options.quality.unwrap_or(DEFAULT_IMAGE_QUALITY);
+        let mut data = Vec::new();
+
+        if matches!(format, ImageFormat::Jpeg) {
+            // The image crate's encoder exposes neither subsampling nor progressive mode
+            let chroma = options.chroma.unwrap_or_default();
+            encode_jpeg_with_chroma(img, &mut data, quality, chroma, options.progressive)?;
+        } else if matches!(format, ImageFormat::Png) {
+            encode_png(img, &mut data, options.optimize)?;
+        } else if matches!(format, ImageFormat::Webp) {
+            encode_webp(img, &mut data, quality, options.lossless)?;
+        } else {
+            data = encode_with_quality(img, format, quality)?;
+        }
+
+        // Encoders may carry metadata over; drop it before writing
+        Ok(apply_metadata_stripping(data, options))
+    }
+
+    /// Finds the highest quality whose encoded output fits in `budget` bytes
+    /// Binary-searches between 1 and the requested quality, re-encoding the decoded
+    /// image in memory each step; returns the chosen quality and its encoded bytes,
+    /// or None if nothing tried fits
+    fn encode_within_budget(
+        &self,
+        img: &DynamicImage,
+        format: &ImageFormat,
+        options: &ImageCompressionOptions,
+        budget: u64,
+    ) -> Result<Option<(u8, Vec<u8>)>> {
+        let mut options = options.clone();
+        let mut low = 1u8;
+        let mut high = options.quality.unwrap_or(DEFAULT_IMAGE_QUALITY);
+        let mut best = None;
+
+        for _ in 0..MAX_BUDGET_SEARCH_ITERATIONS {
+            if low > high {
+                break;
+            }
+            let quality = low + (high - low).div_ceil(2);
+            options.quality = Some(quality);
+            let data = self.encode_image(img, format, &options)?;
+            debug!("Quality {} encodes to {} bytes", quality, data.len());
+
+            if data.len() as u64 <= budget {
+                best = Some((quality, data));
+                low = quality + 1;
+            } else if quality == 1 {
+                break;
+            } else {
+                high = quality - 1;
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// Encodes at the quality that lands closest under `target` bytes
+    /// Unlike --max-bytes the whole 1-100 range is searched, and if even quality 1
+    /// is too large its output is returned as the closest result
+    fn encode_near_target(
+        &self,
+        img: &DynamicImage,
+        format: &ImageFormat,
+        options: &ImageCompressionOptions,
+        target: u64,
+    ) -> Result<(u8, Vec<u8>)> {
+        let options = ImageCompressionOptions {
+            quality: Some(100),
+            ..options.clone()
+        };
+        if let Some(result) = self.encode_within_budget(img, format, &options, target)? {
+            return Ok(result);
+        }
+
+        let options = ImageCompressionOptions {
+            quality: Some(1),
+            ..options
+        };
+        Ok((1, self.encode_image(img, format, &options)?))
+    }
+
+    /// Resolves a `--resize` value to pixel dimensions for a source image
+    /// A trailing `%` scales both source dimensions; otherwise it's `WIDTHxHEIGHT`
+    fn resize_target(&self, resize_str: &str, width: u32, height: u32) -> Result<(u32, u32)> {
+        let Some(percent) = resize_str.strip_suffix('%') else {
+            return self.parse_resize_dimensions(resize_str);
+        };
+
+        let percent: f64 = percent
+            .trim()
+            .parse()
+            .map_err(|_| CompressError::invalid_parameter("resize", resize_str))?;
+        if !(percent > 0.0 && percent <= MAX_RESIZE_PERCENT) {
+            return Err(CompressError::invalid_parameter(
+                "resize",
+                format!(
+                    "{} (percentage must be above 0% and at most {}%)",
+                    resize_str, MAX_RESIZE_PERCENT
+                ),
+            ));
+        }
+
+        // Round, but never down to zero pixels
+        let scale = |size: u32| ((size as f64 * percent / 100.0).round() as u32).max(1);
+        Ok((scale(width), scale(height)))
+    }
+
     /// Parses resize dimensions from string format
     fn parse_resize_dimensions(&self, resize_str: &str) -> Result<(u32, u32)> {
         let parts: Vec<&str> = resize_str.split('x').collect();
@@ -291,6 +954,9 @@ impl ImageCompressor {
         format: &ImageFormat,
         output_path: &Path,
     ) {
+        if quiet_output() {
+            return;
+        }
         println!(
             "\n{}",
             console::style("DRY RUN - No files will be modified")
@@ -300,10 +966,33 @@ impl ImageCompressor {
         println!("Input:   {}", options.input.display());
         println!("Output:  {}", output_path.display());
         println!("Format:  {}", format);
-        println!("Quality: {}", options.quality);
+        println!(
+            "Quality: {}",
+            options.quality.unwrap_or(DEFAULT_IMAGE_QUALITY)
+        );
 
+        if let Some(max_bytes) = options.max_bytes {
+            println!("Max size: {}", max_bytes);
+        }
+        if let Some(target_size) = options.target_size {
+            println!("Target size: {}", target_size);
+        }
+        if let Some(chroma) = options.chroma {
+            println!("Chroma:  {}", chroma);
+        }
+        if options.strip {
+            println!("Strip:   EXIF, XMP, ICC profile, thumbnails, comments");
+        } else if options.strip_metadata {
+            println!("Strip:   EXIF, XMP, IPTC, thumbnails, comments (ICC profile kept)");
+        }
+        if let Some(crop) = &options.crop {
+            println!(
+                "Crop:    {}x{} at {},{}",
+                crop.width, crop.height, crop.x, crop.y
+            );
+        }
         if let Some(resize) = &options.resize {
-            println!("Resize:  {}", resize);
+            println!("Resize:  {} ({:?})", resize, options.resize_mode);
         }
         if let Some(max_width) = options.max_width {
             println!("Max width: {}", max_width);
@@ -315,6 +1004,10 @@ impl ImageCompressor {
         println!("Optimize: {}", options.optimize);
         println!("Progressive: {}", options.progressive);
         println!("Lossless: {}", options.lossless);
+
+        if let Some(hook) = &options.post_hook {
+            println!("Post-hook: {}", hook);
+        }
     }
 }
 
@@ -329,9 +1022,331 @@ impl Clone for ImageCompressor {
     }
 }
 
+/// Scales `width`x`height` to the largest size that fits in the box, keeping the aspect ratio
+fn fit_within(width: u32, height: u32, box_width: u32, box_height: u32) -> (u32, u32) {
+    let scale = f64::min(
+        box_width as f64 / width as f64,
+        box_height as f64 / height as f64,
+    );
+    let scaled = |size: u32| ((size as f64 * scale).round() as u32).max(1);
+    (scaled(width), scaled(height))
+}
+
+/// Returns the option that asks for a size-limited encode, if any
+fn size_limit_parameter(options: &ImageCompressionOptions) -> Option<&'static str> {
+    if options.max_bytes.is_some() {
+        Some("max_bytes")
+    } else if options.target_size.is_some() {
+        Some("target_size")
+    } else {
+        None
+    }
+}
+
+/// Parses an `--input-format` hint into a decoder format
+/// Accepts the usual extensions (e.g. "png", "jpg") of formats this build can decode
+fn parse_input_format(hint: Option<&str>) -> Result<Option<ImageLibFormat>> {
+    let Some(hint) = hint else {
+        return Ok(None);
+    };
+
+    match ImageLibFormat::from_extension(hint) {
+        Some(format) if format.reading_enabled() => Ok(Some(format)),
+        _ => Err(CompressError::invalid_parameter(
+            "input_format",
+            format!("{} (not a supported image format)", hint),
+        )),
+    }
+}
+
+/// Drops metadata from encoded bytes as requested by --strip or --strip-metadata
+/// Leaves the bytes untouched when neither flag is set
+fn apply_metadata_stripping(data: Vec<u8>, options: &ImageCompressionOptions) -> Vec<u8> {
+    if options.strip {
+        strip_image_metadata(&data)
+    } else if options.strip_metadata {
+        strip_private_metadata(&data)
+    } else {
+        data
+    }
+}
+
+/// Encodes an image into memory with the default encoder settings for its format
+fn encode_with_quality(img: &DynamicImage, format: &ImageFormat, quality: u8) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+
+    match format {
+        ImageFormat::Jpeg => {
+            encode_jpeg_with_chroma(img, &mut data, quality, ChromaSubsampling::default(), false)?
+        }
+        ImageFormat::Png => encode_png(img, &mut data, false)?,
+        ImageFormat::Webp => encode_webp(img, &mut data, quality, false)?,
+        ImageFormat::Avif => encode_avif(img, &mut data, quality)?,
+    }
+
+    Ok(data)
+}
+
+/// Encodes an image as PNG with adaptive filtering
+/// `optimize` trades encoding time for the strongest deflate compression
+fn encode_png(img: &DynamicImage, output: &mut Vec<u8>, optimize: bool) -> Result<()> {
+    let compression = if optimize {
+        CompressionType::Best
+    } else {
+        CompressionType::Default
+    };
+    img.write_with_encoder(PngEncoder::new_with_quality(
+        output,
+        compression,
+        PngFilterType::Adaptive,
+    ))?;
+    Ok(())
+}
+
+/// Encodes an image as WebP with libwebp
+/// Lossy output uses `quality` for the quantizer; lossless output ignores it
+fn encode_webp(
+    img: &DynamicImage,
+    output: &mut Vec<u8>,
+    quality: u8,
+    lossless: bool,
+) -> Result<()> {
+    let (width, height) = (img.width(), img.height());
+    // Keep the alpha channel only when there is one, so opaque images stay smaller
+    let pixels = if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(img.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    };
+    let encoder = match &pixels {
+        DynamicImage::ImageRgba8(rgba) => WebPEncoder::from_rgba(rgba, width, height),
+        _ => WebPEncoder::from_rgb(pixels.as_bytes(), width, height),
+    };
+
+    let encoded = encoder
+        .encode_simple(lossless, f32::from(quality))
+        .map_err(|e| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageLibFormat::WebP),
+                format!("{:?}", e),
+            ))
+        })?;
+    output.extend_from_slice(&encoded);
+    Ok(())
+}
+
+/// Encodes an image as AVIF with rav1e at the default speed
+#[cfg(feature = "avif")]
+fn encode_avif(img: &DynamicImage, output: &mut Vec<u8>, quality: u8) -> Result<()> {
+    img.write_with_encoder(AvifEncoder::new_with_speed_quality(
+        output,
+        DEFAULT_AVIF_SPEED,
+        quality,
+    ))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "avif"))]
+fn encode_avif(_img: &DynamicImage, _output: &mut Vec<u8>, _quality: u8) -> Result<()> {
+    Err(CompressError::unsupported_format(
+        "AVIF encoding requires compresscli to be built with the `avif` feature",
+    ))
+}
+
+/// Parses a `#RRGGBB` or `#RRGGBBAA` color (the `#` is optional)
+fn parse_hex_color(color: &str) -> Result<Rgba<u8>> {
+    let hex = color.trim_start_matches('#');
+    let invalid = || CompressError::invalid_parameter("background", color);
+
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut channels = [u8::MAX; 4];
+    for (channel, i) in channels.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *channel = u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(Rgba(channels))
+}
+
+/// Returns the (columns, rows) of a collage grid, dropping columns that would stay empty
+fn collage_grid(count: usize, columns: u32) -> (u32, u32) {
+    let count = count as u32;
+    let columns = columns.min(count);
+    (columns, count.div_ceil(columns))
+}
+
+/// Computes the collage canvas size: tiles plus padding between and around them
+fn collage_canvas_size(columns: u32, rows: u32, tile: (u32, u32), padding: u32) -> (u32, u32) {
+    (
+        columns * tile.0 + (columns + 1) * padding,
+        rows * tile.1 + (rows + 1) * padding,
+    )
+}
+
+/// Returns the top-left corner of the tile at `index`
+fn collage_tile_origin(index: u32, columns: u32, tile: (u32, u32), padding: u32) -> (u32, u32) {
+    let (column, row) = (index % columns, index / columns);
+    (
+        padding + column * (tile.0 + padding),
+        padding + row * (tile.1 + padding),
+    )
+}
+
+/// Loads the input image, forcing the decoder when a format hint is given
+fn load_image(options: &ImageCompressionOptions) -> Result<DynamicImage> {
+    match parse_input_format(options.input_format.as_deref())? {
+        Some(format) => {
+            let data = std::fs::read(&options.input)?;
+            Ok(image::load_from_memory_with_format(&data, format)?)
+        }
+        None => open_image(&options.input),
+    }
+}
+
+/// Decodes an image file, detecting the format from its extension and contents
+fn open_image(path: &Path) -> Result<DynamicImage> {
+    if is_heif_file(path) {
+        decode_with_ffmpeg(path)
+    } else {
+        image::open(path).map_err(CompressError::Image)
+    }
+}
+
+/// Checks for HEIC/HEIF extensions, which the image crate can't decode
+fn is_heif_file(path: &Path) -> bool {
+    get_extension_lowercase(path).is_some_and(|ext| HEIF_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Decodes an image the image crate doesn't support (HEIC/HEIF) by having FFmpeg
+/// convert its first frame to PNG in memory
+fn decode_with_ffmpeg(input: &Path) -> Result<DynamicImage> {
+    let unsupported = || {
+        CompressError::unsupported_format(format!(
+            "{} is HEIC/HEIF, which needs FFmpeg 7.1 or newer with HEIF support to decode; \
+             convert it to JPEG or PNG first otherwise",
+            input.display()
+        ))
+    };
+
+    let (major, minor) = MIN_FFMPEG_HEIF_VERSION;
+    if check_ffmpeg_min_version(major, minor).is_err() {
+        return Err(unsupported());
+    }
+
+    let mut command = FFmpegCommandBuilder::new()
+        .custom_args(["-v", "error"])
+        .input(input)?
+        .frames(1)
+        .custom_args(["-f", "image2pipe", "-c:v", "png", "-"])
+        .build();
+    debug!("Decoding with FFmpeg: {:?}", command);
+
+    let output = command.output().map_err(|_| unsupported())?;
+    if !output.status.success() || output.stdout.is_empty() {
+        debug!(
+            "FFmpeg could not decode {}: {}",
+            input.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return Err(unsupported());
+    }
+
+    Ok(image::load_from_memory_with_format(
+        &output.stdout,
+        ImageLibFormat::Png,
+    )?)
+}
+
+/// One output of a quality sweep
+#[derive(Debug, Clone)]
+pub struct QualitySample {
+    pub quality: u8,
+    pub size: u64,
+    pub path: PathBuf,
+}
+
+/// Renders a static HTML page showing quality sweep outputs side by side
+/// Images are referenced by file name, so the page lives next to them
+fn comparison_html_page(input: &Path, samples: &[QualitySample]) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    let title = escape(&input.file_name().unwrap_or_default().to_string_lossy());
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Quality comparison: {}</title>\n\
+         <style>body{{font-family:sans-serif}}figure{{display:inline-block;margin:8px}}img{{max-width:480px}}</style>\n\
+         </head>\n<body>\n<h1>{}</h1>\n",
+        title, title
+    );
+
+    for sample in samples {
+        let file_name = escape(
+            &sample
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+        );
+        html.push_str(&format!(
+            "<figure><img src=\"{}\" alt=\"quality {}\"><figcaption>Quality {} &middot; {}</figcaption></figure>\n",
+            file_name,
+            sample.quality,
+            sample.quality,
+            ByteSize::b(sample.size)
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Encodes a JPEG with explicit chroma subsampling, optionally as progressive scans
+/// Every JPEG goes through here, so output is the same with or without --chroma;
+/// JPEG has no alpha channel, so the image is flattened to RGB first
+fn encode_jpeg_with_chroma(
+    img: &DynamicImage,
+    output: &mut Vec<u8>,
+    quality: u8,
+    chroma: ChromaSubsampling,
+    progressive: bool,
+) -> Result<()> {
+    let rgb = img.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => {
+            return Err(CompressError::invalid_parameter(
+                "dimensions",
+                format!("{}x{} exceeds the JPEG limit of 65535x65535", width, height),
+            ));
+        }
+    };
+
+    let sampling = match chroma {
+        ChromaSubsampling::Yuv420 => SamplingFactor::R_4_2_0,
+        ChromaSubsampling::Yuv422 => SamplingFactor::R_4_2_2,
+        ChromaSubsampling::Yuv444 => SamplingFactor::R_4_4_4,
+    };
+
+    let mut encoder = Encoder::new(output, quality);
+    encoder.set_sampling_factor(sampling);
+    encoder.set_progressive(progressive);
+    encoder
+        .encode(rgb.as_raw(), width, height, JpegColorType::Rgb)
+        .map_err(|e| CompressError::process_failed(format!("JPEG encoder: {}", e)))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use image::codecs::jpeg::JpegEncoder;
 
     #[test]
     fn test_parse_resize_dimensions() {
@@ -351,25 +1366,36 @@ mod tests {
         assert!(compressor.parse_resize_dimensions("800x0").is_err());
     }
 
+    #[test]
+    fn test_resize_percentage() {
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+
+        assert_eq!(
+            compressor.resize_target("50%", 800, 601).unwrap(),
+            (400, 301)
+        );
+        assert_eq!(
+            compressor.resize_target("200%", 800, 600).unwrap(),
+            (1600, 1200)
+        );
+        assert_eq!(compressor.resize_target("1%", 10, 10).unwrap(), (1, 1));
+        assert_eq!(
+            compressor.resize_target("640x480", 800, 600).unwrap(),
+            (640, 480)
+        );
+        assert!(compressor.resize_target("abc%", 800, 600).is_err());
+        assert!(compressor.resize_target("0%", 800, 600).is_err());
+        assert!(compressor.resize_target("1001%", 800, 600).is_err());
+    }
+
     #[test]
     fn test_determine_output_format() {
         let config = Config::default();
         let compressor = ImageCompressor::new(config, false, false);
 
         let options = ImageCompressionOptions {
-            input: PathBuf::from("test.jpg"),
-            output: None,
-            quality: 85,
-            format: None,
-            resize: None,
-            max_width: None,
-            max_height: None,
-            optimize: false,
-            progressive: false,
-            lossless: false,
-            preset: None,
-            output_dir: None,
-            overwrite: false,
+            quality: Some(85),
+            ..ImageCompressionOptions::new("test.jpg")
         };
 
         let format = compressor.determine_output_format(&options).unwrap();
@@ -390,25 +1416,549 @@ mod tests {
         let compressor = ImageCompressor::new(config, false, false);
 
         let mut options = ImageCompressionOptions {
-            input: PathBuf::from("test.jpg"),
-            output: None,
-            quality: DEFAULT_IMAGE_QUALITY, // Default quality
-            format: None,
-            resize: None,
-            max_width: None,
-            max_height: None,
-            optimize: false,
-            progressive: false,
-            lossless: false,
+            quality: None, // Not explicitly set
             preset: Some("high".to_string()),
-            output_dir: None,
-            overwrite: false,
+            ..ImageCompressionOptions::new("test.jpg")
         };
 
         compressor.apply_preset_config(&mut options).unwrap();
 
         // Should have applied the "high" preset quality (95)
-        assert_eq!(options.quality, 95);
+        assert_eq!(options.quality, Some(95));
         assert!(options.optimize); // Should be enabled by preset
     }
+
+    #[test]
+    fn test_preset_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let preset_path = dir.path().join("shared.toml");
+        std::fs::write(
+            &preset_path,
+            "quality = 42\noptimize = true\nprogressive = false\nlossless = false\n",
+        )
+        .unwrap();
+
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let mut options = ImageCompressionOptions {
+            preset_from: Some(preset_path.clone()),
+            ..ImageCompressionOptions::new("test.jpg")
+        };
+
+        compressor.apply_preset_config(&mut options).unwrap();
+        assert_eq!(options.quality, Some(42));
+        assert!(options.optimize);
+
+        // Explicit flags still win over the file
+        options.quality = Some(70);
+        compressor.apply_preset_config(&mut options).unwrap();
+        assert_eq!(options.quality, Some(70));
+
+        std::fs::write(&preset_path, "quality = \"high\"\n").unwrap();
+        assert!(compressor.apply_preset_config(&mut options).is_err());
+    }
+
+    #[test]
+    fn test_format_quality_default_precedence() {
+        let config = Config::default();
+        let compressor = ImageCompressor::new(config, false, false);
+
+        let mut options = ImageCompressionOptions {
+            format: Some(ImageFormat::Avif),
+            ..ImageCompressionOptions::new("test.png")
+        };
+
+        // Format default applies when nothing else sets quality
+        compressor.apply_format_quality_default(&mut options, &ImageFormat::Avif);
+        assert_eq!(options.quality, Some(55));
+
+        // Explicit quality always wins
+        options.quality = Some(70);
+        compressor.apply_format_quality_default(&mut options, &ImageFormat::Avif);
+        assert_eq!(options.quality, Some(70));
+
+        // Formats without a configured default use the global default
+        options.quality = None;
+        compressor.apply_format_quality_default(&mut options, &ImageFormat::Png);
+        assert_eq!(options.quality, Some(DEFAULT_IMAGE_QUALITY));
+    }
+
+    #[test]
+    fn test_chroma_subsampling_changes_output() {
+        // Thin red strokes on white, like colored text
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 64, |x, y| {
+            if x % 3 == 0 || y % 5 == 0 {
+                image::Rgb([220, 20, 20])
+            } else {
+                image::Rgb([255, 255, 255])
+            }
+        }));
+        let mut subsampled = Vec::new();
+        let mut full = Vec::new();
+
+        encode_jpeg_with_chroma(&img, &mut subsampled, 90, ChromaSubsampling::Yuv420, false)
+            .unwrap();
+        encode_jpeg_with_chroma(&img, &mut full, 90, ChromaSubsampling::Yuv444, false).unwrap();
+
+        // Sum the color error over the red strokes, where subsampling smears chroma
+        let source = img.to_rgb8();
+        let stroke_error = |jpeg: &[u8]| -> u64 {
+            let decoded = image::load_from_memory(jpeg).unwrap().to_rgb8();
+            source
+                .enumerate_pixels()
+                .filter(|(_, _, pixel)| pixel.0 == [220, 20, 20])
+                .map(|(x, y, pixel)| {
+                    let out = decoded.get_pixel(x, y);
+                    (0..3)
+                        .map(|c| (i64::from(out[c]) - i64::from(pixel[c])).unsigned_abs())
+                        .sum::<u64>()
+                })
+                .sum()
+        };
+
+        let subsampled_error = stroke_error(&subsampled);
+        let full_error = stroke_error(&full);
+        assert!(
+            full_error < subsampled_error,
+            "4:4:4 error {} should be below 4:2:0 error {}",
+            full_error,
+            subsampled_error
+        );
+    }
+
+    #[test]
+    fn test_jpeg_defaults_to_420_and_honors_progressive() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(32, 32, |x, y| {
+            image::Rgb([(x * 8) as u8, (y * 8) as u8, 128])
+        }));
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let options = ImageCompressionOptions::new("photo.png");
+        let encode = |options: &ImageCompressionOptions| {
+            compressor
+                .encode_image(&img, &ImageFormat::Jpeg, options)
+                .unwrap()
+        };
+        // SOF2 starts a progressive frame, SOF0 a baseline one
+        let progressive = |jpeg: &[u8]| jpeg.windows(2).any(|w| w == [0xFF, 0xC2]);
+
+        let mut explicit = Vec::new();
+        encode_jpeg_with_chroma(
+            &img,
+            &mut explicit,
+            DEFAULT_IMAGE_QUALITY,
+            ChromaSubsampling::Yuv420,
+            false,
+        )
+        .unwrap();
+        let plain = encode(&options);
+        assert_eq!(plain, explicit);
+        assert!(!progressive(&plain));
+
+        // --progressive applies without --chroma too
+        let plain_progressive = encode(&ImageCompressionOptions {
+            progressive: true,
+            ..options.clone()
+        });
+        assert!(progressive(&plain_progressive));
+        let chroma_progressive = encode(&ImageCompressionOptions {
+            progressive: true,
+            chroma: Some(ChromaSubsampling::Yuv420),
+            ..options
+        });
+        assert_eq!(plain_progressive, chroma_progressive);
+    }
+
+    #[tokio::test]
+    async fn test_jpeg_quality_changes_output_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("gradient.png");
+        image::RgbImage::from_fn(256, 256, |x, y| {
+            image::Rgb([x as u8, y as u8, (x * y % 251) as u8])
+        })
+        .save(&input)
+        .unwrap();
+
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let mut sizes = Vec::new();
+        for quality in [20, 90] {
+            let options = ImageCompressionOptions {
+                output: Some(dir.path().join(format!("q{}.jpg", quality))),
+                quality: Some(quality),
+                format: Some(ImageFormat::Jpeg),
+                ..ImageCompressionOptions::new(input.clone())
+            };
+            let output = compressor.compress(options).await.unwrap();
+            sizes.push(std::fs::metadata(output).unwrap().len());
+        }
+
+        assert!(
+            sizes[0] * 2 < sizes[1],
+            "quality 20 ({} bytes) should be well under quality 90 ({} bytes)",
+            sizes[0],
+            sizes[1]
+        );
+    }
+
+    #[cfg(feature = "avif")]
+    #[test]
+    fn test_avif_encoding() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(32, 32, |x, y| {
+            image::Rgba([(x * 8) as u8, (y * 8) as u8, 128, 200])
+        }));
+
+        let data = encode_with_quality(&img, &ImageFormat::Avif, 60).unwrap();
+        assert!(!data.is_empty());
+        // Decoding AVIF needs dav1d, so check the container instead of re-opening
+        assert_eq!(image::guess_format(&data).unwrap(), ImageLibFormat::Avif);
+    }
+
+    #[test]
+    fn test_png_optimize_compresses_harder() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(256, 256, |x, y| {
+            image::Rgb([x as u8, y as u8, ((x + y) / 2) as u8])
+        }));
+        let mut default = Vec::new();
+        let mut optimized = Vec::new();
+
+        encode_png(&img, &mut default, false).unwrap();
+        encode_png(&img, &mut optimized, true).unwrap();
+
+        assert!(optimized.len() < default.len());
+        assert_eq!(image::load_from_memory(&optimized).unwrap(), img);
+    }
+
+    #[test]
+    fn test_webp_lossless_and_quality() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(128, 128, |x, y| {
+            image::Rgb([x as u8 * 2, y as u8 * 2, (x * y % 251) as u8])
+        }));
+
+        let encode = |quality, lossless| {
+            let mut data = Vec::new();
+            encode_webp(&img, &mut data, quality, lossless).unwrap();
+            data
+        };
+        let lossless = encode(50, true);
+        let lossy = encode(50, false);
+        let lossy_high = encode(95, false);
+
+        assert_ne!(lossless.len(), lossy.len());
+        assert!(lossy.len() < lossy_high.len());
+        assert_eq!(
+            image::load_from_memory(&lossless).unwrap().to_rgb8(),
+            img.to_rgb8()
+        );
+    }
+
+    #[test]
+    fn test_strip_metadata_drops_exif() {
+        let mut jpeg = Vec::new();
+        image::RgbImage::from_pixel(16, 16, image::Rgb([90, 140, 200]))
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+            .unwrap();
+
+        // Camera-style EXIF segment right after SOI
+        let exif = b"Exif\0\0GPS 51.5N 0.1W";
+        let mut with_exif = jpeg[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        with_exif.extend_from_slice(exif);
+        with_exif.extend_from_slice(&jpeg[2..]);
+        let has_exif = |data: &[u8]| {
+            data.windows(4).any(|w| w == b"Exif") || data.windows(2).any(|w| w == [0xFF, 0xE1])
+        };
+
+        let mut options = ImageCompressionOptions::new("photo.jpg");
+
+        // Without the flag the EXIF block is left alone
+        let kept = apply_metadata_stripping(with_exif.clone(), &options);
+        assert!(has_exif(&kept));
+        assert_eq!(kept, with_exif);
+
+        options.strip_metadata = true;
+        let stripped = apply_metadata_stripping(with_exif, &options);
+        assert!(!has_exif(&stripped));
+        assert!(image::load_from_memory(&stripped).is_ok());
+    }
+
+    #[test]
+    fn test_encode_within_budget() {
+        // Noise keeps the encoded size sensitive to quality
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(96, 96, |x, y| {
+            let v = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)) as u8;
+            image::Rgb([v, v.wrapping_mul(3), v.wrapping_add(91)])
+        }));
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let options = ImageCompressionOptions {
+            quality: Some(95),
+            format: Some(ImageFormat::Jpeg),
+            ..ImageCompressionOptions::new("noise.png")
+        };
+
+        let full = compressor
+            .encode_image(&img, &ImageFormat::Jpeg, &options)
+            .unwrap();
+        let budget = full.len() as u64 * 2 / 3;
+        let (quality, data) = compressor
+            .encode_within_budget(&img, &ImageFormat::Jpeg, &options, budget)
+            .unwrap()
+            .unwrap();
+        assert!(quality < 95);
+        assert!(data.len() as u64 <= budget);
+
+        // The next quality step up no longer fits
+        let above = ImageCompressionOptions {
+            quality: Some(quality + 1),
+            ..options.clone()
+        };
+        let above = compressor
+            .encode_image(&img, &ImageFormat::Jpeg, &above)
+            .unwrap();
+        assert!(above.len() as u64 > budget);
+
+        assert!(
+            compressor
+                .encode_within_budget(&img, &ImageFormat::Jpeg, &options, 100)
+                .unwrap()
+                .is_none()
+        );
+
+        // A target size searches above the requested quality and falls back to quality 1
+        let (quality, data) = compressor
+            .encode_near_target(&img, &ImageFormat::Jpeg, &options, full.len() as u64 * 2)
+            .unwrap();
+        assert!(quality > 95);
+        assert!(data.len() <= full.len() * 2);
+        let (quality, _) = compressor
+            .encode_near_target(&img, &ImageFormat::Jpeg, &options, 100)
+            .unwrap();
+        assert_eq!(quality, 1);
+    }
+
+    #[test]
+    fn test_comparison_html_page() {
+        let samples = vec![
+            QualitySample {
+                quality: 60,
+                size: 2048,
+                path: PathBuf::from("out/photo_q60_2KB.jpg"),
+            },
+            QualitySample {
+                quality: 90,
+                size: 8192,
+                path: PathBuf::from("out/photo_q90_8KB.jpg"),
+            },
+        ];
+
+        let html = comparison_html_page(Path::new("photo <1>.png"), &samples);
+        assert!(html.contains("<img src=\"photo_q60_2KB.jpg\""));
+        assert!(html.contains("Quality 90"));
+        assert!(html.contains("photo &lt;1&gt;.png"));
+    }
+
+    #[test]
+    fn test_parse_input_format() {
+        assert_eq!(
+            parse_input_format(Some("PNG")).unwrap(),
+            Some(ImageLibFormat::Png)
+        );
+        assert_eq!(
+            parse_input_format(Some("jpg")).unwrap(),
+            Some(ImageLibFormat::Jpeg)
+        );
+        assert_eq!(parse_input_format(None).unwrap(), None);
+        assert!(parse_input_format(Some("docx")).is_err());
+    }
+
+    #[test]
+    fn test_collage_layout() {
+        assert_eq!(collage_grid(5, 3), (3, 2));
+        // Columns beyond the image count would only add empty space
+        assert_eq!(collage_grid(2, 4), (2, 1));
+        assert_eq!(collage_canvas_size(3, 2, (100, 50), 10), (340, 130));
+        assert_eq!(collage_tile_origin(4, 3, (100, 50), 10), (120, 70));
+
+        assert_eq!(
+            parse_hex_color("#ff8000").unwrap(),
+            Rgba([255, 128, 0, 255])
+        );
+        assert_eq!(parse_hex_color("00000080").unwrap(), Rgba([0, 0, 0, 128]));
+        assert!(parse_hex_color("#fff").is_err());
+        assert!(parse_hex_color("#gggggg").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_collage_centers_tiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let wide = dir.path().join("wide.png");
+        let tall = dir.path().join("tall.png");
+        image::RgbImage::from_pixel(40, 20, image::Rgb([255, 0, 0]))
+            .save(&wide)
+            .unwrap();
+        image::RgbImage::from_pixel(10, 40, image::Rgb([0, 0, 255]))
+            .save(&tall)
+            .unwrap();
+
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let output = compressor
+            .collage(CollageOptions {
+                inputs: vec![wide.clone(), tall],
+                output: None,
+                columns: 2,
+                tile_size: "20x20".to_string(),
+                padding: 2,
+                background: "#ffffff".to_string(),
+                format: ImageFormat::Png,
+                quality: None,
+                output_dir: None,
+                overwrite: false,
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, dir.path().join("wide_collage.png"));
+
+        let collage = image::open(&output).unwrap().to_rgba8();
+        assert_eq!(collage.dimensions(), (46, 24));
+        // The wide image is scaled to 20x10 and centered vertically in its tile
+        assert_eq!(collage.get_pixel(12, 4), &Rgba([255, 255, 255, 255]));
+        assert_eq!(collage.get_pixel(12, 12), &Rgba([255, 0, 0, 255]));
+        assert_eq!(collage.get_pixel(33, 12), &Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_auto_orient_before_constraints() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("portrait.jpg");
+        let mut jpeg = Vec::new();
+        image::RgbImage::new(40, 20)
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+            .unwrap();
+
+        // Big-endian TIFF header with a single IFD entry: Orientation (SHORT) = 6
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend_from_slice(b"\x01\x12\0\x03\0\0\0\x01\0\x06\0\0\0\0\0\0");
+        let mut with_exif = jpeg[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        with_exif.extend_from_slice(&exif);
+        with_exif.extend_from_slice(&jpeg[2..]);
+        std::fs::write(&input, &with_exif).unwrap();
+
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let mut options = ImageCompressionOptions {
+            max_width: Some(10),
+            ..ImageCompressionOptions::new(input)
+        };
+        let img = load_image(&options).unwrap();
+
+        // Orientation 6 is a 90° clockwise rotation, so the 40x20 pixels display as 20x40
+        let result = compressor
+            .apply_transformations(img.clone(), &options)
+            .unwrap();
+        assert_eq!((result.width(), result.height()), (10, 20));
+
+        options.auto_orient = false;
+        let result = compressor.apply_transformations(img, &options).unwrap();
+        assert_eq!((result.width(), result.height()), (10, 5));
+    }
+
+    #[test]
+    fn test_resize_modes() {
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(100, 50));
+        let mut options = ImageCompressionOptions {
+            resize: Some("50x50".to_string()),
+            resize_mode: ResizeMode::Fit,
+            ..ImageCompressionOptions::new("wide.png")
+        };
+
+        let fit = compressor
+            .apply_transformations(img.clone(), &options)
+            .unwrap();
+        assert_eq!((fit.width(), fit.height()), (50, 25));
+
+        options.resize_mode = ResizeMode::Fill;
+        let fill = compressor
+            .apply_transformations(img.clone(), &options)
+            .unwrap();
+        assert_eq!((fill.width(), fill.height()), (50, 50));
+
+        options.resize_mode = ResizeMode::Exact;
+        let exact = compressor.apply_transformations(img, &options).unwrap();
+        assert_eq!((exact.width(), exact.height()), (50, 50));
+    }
+
+    #[test]
+    fn test_resize_filter_mapping() {
+        assert_eq!(
+            FilterType::from(ResizeFilter::default()),
+            FilterType::Lanczos3
+        );
+        assert_eq!(FilterType::from(ResizeFilter::Nearest), FilterType::Nearest);
+        assert_eq!(
+            FilterType::from(ResizeFilter::Triangle),
+            FilterType::Triangle
+        );
+        assert_eq!(
+            FilterType::from(ResizeFilter::CatmullRom),
+            FilterType::CatmullRom
+        );
+        assert_eq!(
+            FilterType::from(ResizeFilter::Gaussian),
+            FilterType::Gaussian
+        );
+
+        let parsed = <ResizeFilter as clap::ValueEnum>::from_str("catmull-rom", true).unwrap();
+        assert_eq!(parsed, ResizeFilter::CatmullRom);
+    }
+
+    #[test]
+    fn test_crop() {
+        assert!(CropRegion::parse("10,20,30").is_err());
+        assert!(CropRegion::parse("10,20,0,40").is_err());
+        assert!(CropRegion::parse("a,b,c,d").is_err());
+
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(100, 80, |x, y| {
+            image::Rgb([x as u8, y as u8, 0])
+        }));
+        let mut options = ImageCompressionOptions {
+            crop: Some(CropRegion::parse("10, 20, 30, 40").unwrap()),
+            ..ImageCompressionOptions::new("photo.png")
+        };
+
+        let cropped = compressor
+            .apply_transformations(img.clone(), &options)
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(cropped.dimensions(), (30, 40));
+        assert_eq!(cropped.get_pixel(0, 0), &image::Rgb([10, 20, 0]));
+
+        options.crop = Some(CropRegion::parse("80,20,30,40").unwrap());
+        let err = compressor
+            .apply_transformations(img, &options)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("outside the 100x80 image"));
+    }
+
+    #[test]
+    fn test_downscale_only_keeps_small_images() {
+        let compressor = ImageCompressor::new(Config::default(), false, false);
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(80, 60));
+
+        let mut options = ImageCompressionOptions {
+            resize: Some("4000x4000".to_string()),
+            downscale_only: true,
+            ..ImageCompressionOptions::new("small.png")
+        };
+
+        let result = compressor
+            .apply_transformations(img.clone(), &options)
+            .unwrap();
+        assert_eq!((result.width(), result.height()), (80, 60));
+
+        options.resize = Some("40x30".to_string());
+        let result = compressor.apply_transformations(img, &options).unwrap();
+        assert_eq!((result.width(), result.height()), (40, 30));
+    }
 }
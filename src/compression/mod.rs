@@ -1,13 +1,25 @@
 //! Compression functionality for CompressCLI
 //!
 //! This module contains all compression-related functionality including
-//! video compression, image compression, and batch processing operations.
+//! video compression, image compression, batch processing and watch mode.
 
+pub mod audio;
 pub mod batch;
+pub mod estimate;
 pub mod image;
+pub mod thumbnail;
 pub mod video;
+pub mod visualize;
+pub mod watch;
 
 // Re-export main compression types
-pub use batch::{BatchOptions, BatchProcessor};
-pub use image::{ImageCompressionOptions, ImageCompressor};
+pub use audio::{AudioExtractor, ExtractAudioOptions};
+pub use batch::{BatchOptions, BatchProcessor, verify_video_encoders};
+pub use estimate::{EstimateOptions, SizeEstimator};
+pub use image::{CollageOptions, CropRegion, ImageCompressionOptions, ImageCompressor};
+pub use thumbnail::{
+    ContactSheetOptions, FrameExtractionOptions, ThumbnailGenerator, ThumbnailOptions,
+};
 pub use video::{VideoCompressionOptions, VideoCompressor};
+pub use visualize::{AudioVisualizer, VisualizeOptions};
+pub use watch::DirectoryWatcher;
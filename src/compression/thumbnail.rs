@@ -0,0 +1,654 @@
+use crate::cli::args::ImageFormat;
+use crate::core::{CompressError, Config, DEFAULT_IMAGE_EXTENSION, DEFAULT_IMAGE_QUALITY, Result};
+use crate::ui::progress::print_success;
+use crate::utils::{
+    FFmpegCommandBuilder, FFmpegProgressParser, check_ffmpeg_filter, check_output_overwrite,
+    ensure_parent_dir, escape_filter_path, find_default_font, generate_output_path,
+    monitor_ffmpeg_progress, parse_time, probe_duration, validate_input_file, validate_safe_path,
+};
+use log::{debug, info};
+use std::path::{Path, PathBuf};
+
+pub struct ThumbnailGenerator {
+    pub config: Config,
+    pub dry_run: bool,
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContactSheetOptions {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub columns: u32,
+    pub rows: u32,
+    pub tile_width: u32,
+    pub timestamps: bool,
+    pub font: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FrameExtractionOptions {
+    pub input: PathBuf,
+    pub interval: f64,
+    pub format: ImageFormat,
+    pub quality: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThumbnailOptions {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub at: String,
+    pub format: ImageFormat,
+    pub quality: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+impl ThumbnailGenerator {
+    /// Creates a new ThumbnailGenerator instance
+    /// Initializes with configuration, dry-run mode, and verbosity settings
+    pub fn new(config: Config, dry_run: bool, verbose: bool) -> Self {
+        Self {
+            config,
+            dry_run,
+            verbose,
+        }
+    }
+
+    /// Generates a contact sheet (grid of evenly spaced frames) from a video
+    /// Optionally overlays each tile with the timestamp of its frame
+    /// Returns the path to the generated image
+    pub async fn contact_sheet(&self, options: ContactSheetOptions) -> Result<PathBuf> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
+
+        if options.columns == 0 || options.rows == 0 {
+            return Err(CompressError::invalid_parameter(
+                "grid",
+                "Columns and rows must be greater than 0",
+            ));
+        }
+        if options.tile_width == 0 {
+            return Err(CompressError::invalid_parameter(
+                "tile_width",
+                "Tile width must be greater than 0",
+            ));
+        }
+
+        let font = if options.timestamps {
+            Some(self.resolve_font(&options)?)
+        } else {
+            None
+        };
+
+        let output_path = self.generate_output_path(&options)?;
+        ensure_parent_dir(&output_path)?;
+        check_output_overwrite(&output_path, options.overwrite)?;
+
+        info!(
+            "Generating contact sheet: {} -> {}",
+            options.input.display(),
+            output_path.display()
+        );
+
+        if self.dry_run {
+            self.print_dry_run_info(&options, font.as_deref(), &output_path);
+            return Ok(output_path);
+        }
+
+        let duration = probe_duration(&options.input)?.ok_or_else(|| {
+            CompressError::ffmpeg_error("Could not determine video duration", None)
+        })?;
+
+        let filter = build_contact_sheet_filter(&options, duration, font.as_deref());
+
+        let mut command = FFmpegCommandBuilder::new()
+            .input(&options.input)?
+            .video_filter(&filter)
+            .frames(1)
+            .no_audio()
+            .progress()
+            .overwrite()
+            .output(&output_path)?
+            .build();
+
+        if self.verbose {
+            debug!("Executing FFmpeg command: {:?}", command);
+        }
+
+        let child = command.spawn().map_err(|e| {
+            CompressError::ffmpeg_error(
+                format!("Failed to start FFmpeg: {}", e),
+                Some(format!("{:?}", command)),
+            )
+        })?;
+
+        let progress_parser = FFmpegProgressParser::new(Some(duration));
+        progress_parser.set_message("Generating contact sheet...");
+
+        monitor_ffmpeg_progress(child, progress_parser).await?;
+
+        print_success(&format!(
+            "Contact sheet generated: {}x{} tiles",
+            options.columns, options.rows
+        ));
+
+        Ok(output_path)
+    }
+
+    /// Extracts one frame every `interval` seconds into a numbered image sequence
+    /// Frames are written as `<stem>_000001.<ext>`, ... in the output directory
+    /// Returns the directory the frames were written to
+    pub async fn extract_frames(&self, options: FrameExtractionOptions) -> Result<PathBuf> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
+
+        if !options.interval.is_finite() || options.interval <= 0.0 {
+            return Err(CompressError::invalid_parameter(
+                "interval",
+                "Interval must be greater than 0",
+            ));
+        }
+
+        let quality = options
+            .quality
+            .or_else(|| self.config.format_quality_default(&options.format))
+            .unwrap_or(DEFAULT_IMAGE_QUALITY);
+
+        let output_dir = frames_output_dir(&options);
+        validate_safe_path(&output_dir)?;
+        let pattern = frame_output_pattern(&options.input, &output_dir, &options.format);
+        let first_frame = frame_path(&pattern, 1);
+        ensure_parent_dir(&first_frame)?;
+        check_output_overwrite(&first_frame, options.overwrite)?;
+
+        info!(
+            "Extracting frames every {}s: {} -> {}",
+            options.interval,
+            options.input.display(),
+            pattern.display()
+        );
+
+        if self.dry_run {
+            self.print_frames_dry_run_info(&options, quality, &pattern);
+            return Ok(output_dir);
+        }
+
+        let duration = probe_duration(&options.input)?.ok_or_else(|| {
+            CompressError::ffmpeg_error("Could not determine video duration", None)
+        })?;
+
+        let mut command = FFmpegCommandBuilder::new()
+            .input(&options.input)?
+            .video_filter(&format!("fps=1/{}", options.interval))
+            .custom_args(frame_encoder_args(&options.format, quality))
+            .no_audio()
+            .progress()
+            .overwrite()
+            .output(&pattern)?
+            .build();
+
+        if self.verbose {
+            debug!("Executing FFmpeg command: {:?}", command);
+        }
+
+        let child = command.spawn().map_err(|e| {
+            CompressError::ffmpeg_error(
+                format!("Failed to start FFmpeg: {}", e),
+                Some(format!("{:?}", command)),
+            )
+        })?;
+
+        let progress_parser = FFmpegProgressParser::new(Some(duration));
+        progress_parser.set_message("Extracting frames...");
+
+        monitor_ffmpeg_progress(child, progress_parser).await?;
+
+        print_success(&format!(
+            "Extracted {} frames",
+            expected_frame_count(duration, options.interval)
+        ));
+
+        Ok(output_dir)
+    }
+
+    /// Extracts the frame at `options.at` into a single image
+    /// Seeks on the input side so long videos don't have to be decoded up to the timestamp
+    /// Returns the path to the generated image
+    pub async fn thumbnail(&self, options: ThumbnailOptions) -> Result<PathBuf> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
+
+        let seconds = parse_time(&options.at)?;
+        let quality = options
+            .quality
+            .or_else(|| self.config.format_quality_default(&options.format))
+            .unwrap_or(DEFAULT_IMAGE_QUALITY);
+
+        let output_path = self.thumbnail_output_path(&options)?;
+        ensure_parent_dir(&output_path)?;
+        check_output_overwrite(&output_path, options.overwrite)?;
+
+        info!(
+            "Extracting thumbnail at {}s: {} -> {}",
+            seconds,
+            options.input.display(),
+            output_path.display()
+        );
+
+        if self.dry_run {
+            self.print_thumbnail_dry_run_info(&options, seconds, quality, &output_path);
+            return Ok(output_path);
+        }
+
+        if let Some(duration) = probe_duration(&options.input)?
+            && seconds >= duration
+        {
+            return Err(CompressError::invalid_parameter(
+                "at",
+                format!(
+                    "{}s is past the end of the video ({:.2}s)",
+                    seconds, duration
+                ),
+            ));
+        }
+
+        let mut command = build_thumbnail_command(&options, quality, &output_path)?.build();
+
+        if self.verbose {
+            debug!("Executing FFmpeg command: {:?}", command);
+        }
+
+        let output = command.output().map_err(|e| {
+            CompressError::ffmpeg_error(
+                format!("Failed to start FFmpeg: {}", e),
+                Some(format!("{:?}", command)),
+            )
+        })?;
+
+        if !output.status.success() || !output_path.exists() {
+            return Err(CompressError::ffmpeg_error(
+                "Failed to extract thumbnail",
+                Some(String::from_utf8_lossy(&output.stderr).to_string()),
+            ));
+        }
+
+        print_success(&format!("Thumbnail extracted at {}s", seconds));
+
+        Ok(output_path)
+    }
+
+    /// Resolves the font used for timestamp overlays
+    /// Validates that the font exists and that FFmpeg supports the drawtext filter
+    fn resolve_font(&self, options: &ContactSheetOptions) -> Result<PathBuf> {
+        let font = match &options.font {
+            Some(font) => {
+                if !font.is_file() {
+                    return Err(CompressError::invalid_parameter(
+                        "font",
+                        format!("Font file not found: {}", font.display()),
+                    ));
+                }
+                font.clone()
+            }
+            None => find_default_font().ok_or_else(|| {
+                CompressError::config(
+                    "No default font found for timestamp overlay, specify one with --font",
+                )
+            })?,
+        };
+
+        if !self.dry_run && !check_ffmpeg_filter("drawtext") {
+            return Err(CompressError::missing_dependency(
+                "FFmpeg drawtext filter (FFmpeg must be built with libfreetype)",
+            ));
+        }
+
+        Ok(font)
+    }
+
+    /// Generates output path with proper naming and validation
+    fn generate_output_path(&self, options: &ContactSheetOptions) -> Result<PathBuf> {
+        if let Some(output) = &options.output {
+            validate_safe_path(output)?;
+            Ok(output.clone())
+        } else {
+            let output_path = generate_output_path(
+                &options.input,
+                options.output_dir.as_deref(),
+                Some("_contact_sheet"),
+                Some(DEFAULT_IMAGE_EXTENSION),
+            );
+            Ok(output_path)
+        }
+    }
+
+    /// Generates the thumbnail output path, `<stem>_thumb.<format>` by default
+    fn thumbnail_output_path(&self, options: &ThumbnailOptions) -> Result<PathBuf> {
+        if let Some(output) = &options.output {
+            validate_safe_path(output)?;
+            Ok(output.clone())
+        } else {
+            Ok(generate_output_path(
+                &options.input,
+                options.output_dir.as_deref(),
+                Some("_thumb"),
+                Some(&options.format.to_string()),
+            ))
+        }
+    }
+
+    /// Prints dry run information
+    fn print_dry_run_info(
+        &self,
+        options: &ContactSheetOptions,
+        font: Option<&Path>,
+        output_path: &Path,
+    ) {
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+        println!("Input:  {}", options.input.display());
+        println!("Output: {}", output_path.display());
+        println!("Grid:   {}x{}", options.columns, options.rows);
+        println!("Tile width: {}", options.tile_width);
+
+        if let Some(font) = font {
+            println!("Timestamps: enabled ({})", font.display());
+        }
+    }
+
+    /// Prints dry run information for thumbnail extraction
+    fn print_thumbnail_dry_run_info(
+        &self,
+        options: &ThumbnailOptions,
+        seconds: f64,
+        quality: u8,
+        output_path: &Path,
+    ) {
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+        println!("Input:  {}", options.input.display());
+        println!("Output: {}", output_path.display());
+        println!("At:     {}s", seconds);
+        println!("Format: {}", options.format);
+        if !matches!(options.format, ImageFormat::Png) {
+            println!("Quality: {}", quality);
+        }
+    }
+
+    /// Prints dry run information for frame extraction
+    fn print_frames_dry_run_info(
+        &self,
+        options: &FrameExtractionOptions,
+        quality: u8,
+        pattern: &Path,
+    ) {
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+        println!("Input:    {}", options.input.display());
+        println!("Output:   {}", pattern.display());
+        println!("Interval: {}s", options.interval);
+        println!("Format:   {}", options.format);
+        if !matches!(options.format, ImageFormat::Png) {
+            println!("Quality:  {}", quality);
+        }
+    }
+}
+
+/// Builds the FFmpeg command that writes the single frame at `options.at`
+fn build_thumbnail_command(
+    options: &ThumbnailOptions,
+    quality: u8,
+    output_path: &Path,
+) -> Result<FFmpegCommandBuilder> {
+    FFmpegCommandBuilder::new()
+        .start_time(&options.at)?
+        .input(&options.input)?
+        .frames(1)
+        .custom_args(frame_encoder_args(&options.format, quality))
+        .no_audio()
+        .overwrite()
+        .output(output_path)
+}
+
+/// Resolves the directory extracted frames are written to
+/// Defaults to a `<stem>_frames` directory next to the input
+fn frames_output_dir(options: &FrameExtractionOptions) -> PathBuf {
+    match &options.output_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            let stem = options.input.file_stem().unwrap_or_default();
+            let parent = options.input.parent().unwrap_or(Path::new("."));
+            parent.join(format!("{}_frames", stem.to_string_lossy()))
+        }
+    }
+}
+
+/// Builds the FFmpeg output pattern for a numbered frame sequence
+fn frame_output_pattern(input: &Path, output_dir: &Path, format: &ImageFormat) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default();
+    output_dir.join(format!("{}_%06d.{}", stem.to_string_lossy(), format))
+}
+
+/// Resolves the path of a single frame from the output pattern
+fn frame_path(pattern: &Path, index: u64) -> PathBuf {
+    PathBuf::from(
+        pattern
+            .to_string_lossy()
+            .replace("%06d", &format!("{:06}", index)),
+    )
+}
+
+/// Maps an image quality (1-100) to FFmpeg encoder arguments for the frame format
+fn frame_encoder_args(format: &ImageFormat, quality: u8) -> Vec<String> {
+    let quality = quality.clamp(1, 100) as u32;
+    match format {
+        // MJPEG qscale runs from 2 (best) to 31 (worst)
+        ImageFormat::Jpeg => {
+            let qscale = 2 + (100 - quality) * 29 / 99;
+            vec!["-q:v".to_string(), qscale.to_string()]
+        }
+        ImageFormat::Png => Vec::new(),
+        ImageFormat::Webp => vec![
+            "-c:v".to_string(),
+            "libwebp".to_string(),
+            "-quality".to_string(),
+            quality.to_string(),
+        ],
+        // AV1 CRF runs from 0 (best) to 63 (worst)
+        ImageFormat::Avif => vec![
+            "-c:v".to_string(),
+            "libaom-av1".to_string(),
+            "-still-picture".to_string(),
+            "1".to_string(),
+            "-crf".to_string(),
+            ((100 - quality) * 63 / 99).to_string(),
+        ],
+    }
+}
+
+/// Number of frames the `fps=1/interval` filter produces for a duration
+fn expected_frame_count(duration: f64, interval: f64) -> u64 {
+    (duration / interval).ceil().max(1.0) as u64
+}
+
+/// Builds the filtergraph for a contact sheet
+/// Selects the first frame at or after each interval, scales it, optionally
+/// draws its timestamp, and tiles the result into a single image
+fn build_contact_sheet_filter(
+    options: &ContactSheetOptions,
+    duration: f64,
+    font: Option<&Path>,
+) -> String {
+    let tiles = options.columns * options.rows;
+    let interval = duration / tiles as f64;
+
+    let mut filters = vec![
+        format!(
+            "select='isnan(prev_selected_t)+gte(t-prev_selected_t\\,{:.3})'",
+            interval
+        ),
+        format!("scale={}:-2", options.tile_width),
+    ];
+
+    if let Some(font) = font {
+        filters.push(format!(
+            "drawtext=fontfile='{}':text='%{{pts\\:hms}}':x=8:y=h-th-8:fontsize=18:fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=4",
+            escape_filter_path(font)
+        ));
+    }
+
+    filters.push(format!("tile={}x{}", options.columns, options.rows));
+    filters.join(",")
+}
+
+// Make ThumbnailGenerator cloneable for async processing
+impl Clone for ThumbnailGenerator {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            dry_run: self.dry_run,
+            verbose: self.verbose,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_options() -> ContactSheetOptions {
+        ContactSheetOptions {
+            input: PathBuf::from("/test/input.mp4"),
+            output: None,
+            columns: 4,
+            rows: 3,
+            tile_width: 320,
+            timestamps: false,
+            font: None,
+            output_dir: None,
+            overwrite: false,
+        }
+    }
+
+    #[test]
+    fn test_contact_sheet_filter() {
+        let options = sample_options();
+        let filter = build_contact_sheet_filter(&options, 120.0, None);
+
+        assert!(filter.contains("gte(t-prev_selected_t\\,10.000)"));
+        assert!(filter.contains("scale=320:-2"));
+        assert!(filter.ends_with("tile=4x3"));
+        assert!(!filter.contains("drawtext"));
+    }
+
+    #[test]
+    fn test_contact_sheet_filter_with_timestamps() {
+        let options = sample_options();
+        let filter =
+            build_contact_sheet_filter(&options, 120.0, Some(Path::new("C:\\Fonts\\arial.ttf")));
+
+        assert!(filter.contains("drawtext=fontfile='C\\:/Fonts/arial.ttf'"));
+        assert!(filter.contains("%{pts\\:hms}"));
+        assert!(filter.find("drawtext").unwrap() < filter.find("tile=").unwrap());
+    }
+
+    #[test]
+    fn test_frame_output_paths() {
+        let options = FrameExtractionOptions {
+            input: PathBuf::from("/videos/clip.mp4"),
+            interval: 2.0,
+            format: ImageFormat::Png,
+            quality: None,
+            output_dir: None,
+            overwrite: false,
+        };
+        let output_dir = frames_output_dir(&options);
+        let pattern = frame_output_pattern(&options.input, &output_dir, &options.format);
+
+        assert_eq!(output_dir, PathBuf::from("/videos/clip_frames"));
+        assert_eq!(pattern, PathBuf::from("/videos/clip_frames/clip_%06d.png"));
+        assert_eq!(
+            frame_path(&pattern, 1),
+            PathBuf::from("/videos/clip_frames/clip_000001.png")
+        );
+    }
+
+    #[test]
+    fn test_frame_encoder_args() {
+        assert_eq!(frame_encoder_args(&ImageFormat::Jpeg, 100), ["-q:v", "2"]);
+        assert_eq!(frame_encoder_args(&ImageFormat::Jpeg, 1), ["-q:v", "31"]);
+        assert!(frame_encoder_args(&ImageFormat::Png, 80).is_empty());
+        assert!(frame_encoder_args(&ImageFormat::Webp, 80).contains(&"80".to_string()));
+    }
+
+    #[test]
+    fn test_expected_frame_count() {
+        assert_eq!(expected_frame_count(10.0, 3.0), 4);
+        assert_eq!(expected_frame_count(9.0, 3.0), 3);
+        assert_eq!(expected_frame_count(0.5, 5.0), 1);
+    }
+
+    #[test]
+    fn test_thumbnail_command_seeks_and_writes_one_frame() {
+        let options = ThumbnailOptions {
+            input: PathBuf::from("/videos/clip.mp4"),
+            output: None,
+            at: "00:00:10".to_string(),
+            format: ImageFormat::Jpeg,
+            quality: None,
+            output_dir: None,
+            overwrite: false,
+        };
+        let generator = ThumbnailGenerator::new(Config::default(), true, false);
+        let output = generator.thumbnail_output_path(&options).unwrap();
+        assert_eq!(output, PathBuf::from("/videos/clip_thumb.jpg"));
+
+        let command = build_thumbnail_command(&options, 80, &output)
+            .unwrap()
+            .build();
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        let seek = args.iter().position(|arg| arg == "-ss").unwrap();
+        let input = args.iter().position(|arg| arg == "-i").unwrap();
+        assert_eq!(args[seek + 1], "10");
+        assert!(seek < input, "seek should happen on the input side");
+        let frames = args.iter().position(|arg| arg == "-frames:v").unwrap();
+        assert_eq!(args[frames + 1], "1");
+
+        let options = ThumbnailOptions {
+            at: "soon".to_string(),
+            ..options
+        };
+        assert!(build_thumbnail_command(&options, 80, &output).is_err());
+    }
+
+    #[test]
+    fn test_contact_sheet_output_path() {
+        let generator = ThumbnailGenerator::new(Config::default(), false, false);
+        let output = generator.generate_output_path(&sample_options()).unwrap();
+
+        assert!(output.to_string_lossy().contains("_contact_sheet"));
+        assert_eq!(output.extension().unwrap(), "jpg");
+    }
+}
@@ -1,18 +1,32 @@
-use crate::cli::args::{AudioCodec, VideoCodec, VideoPreset};
-use crate::core::{CompressError, Config, DEFAULT_VIDEO_EXTENSION, Result, VideoPresetConfig};
-use crate::ui::progress::print_success;
+use crate::cli::args::{
+    AudioCodec, FieldOrder, HwAccel, Rotation, Tune, VideoCodec, VideoContainer, VideoPreset,
+    WatermarkPosition,
+};
+use crate::core::{
+    AUDIO_BITRATE_MEDIUM, CompressError, Config, DEFAULT_VIDEO_EXTENSION, MIN_TARGET_VIDEO_BITRATE,
+    Result, TARGET_SIZE_MUX_OVERHEAD, VideoPresetConfig,
+};
+use crate::ui::progress::{print_info, print_success, print_warning};
 use crate::utils::{
-    FFmpegCommandBuilder, FFmpegProgressParser, FFprobeCommandBuilder, calculate_compression_ratio,
-    check_output_overwrite, ensure_parent_dir, generate_output_path, get_file_size,
-    monitor_ffmpeg_progress, validate_input_file, validate_safe_path,
+    FFmpegCommandBuilder, FFmpegProgressParser, HookContext, MediaInfo, backup_original,
+    calculate_compression_ratio, check_ffmpeg_demuxer, check_ffmpeg_filter, check_output_overwrite,
+    ensure_parent_dir, escape_filter_path, escape_filter_value, expand_hook, ffmpeg_encoders,
+    find_default_font, generate_output_path, get_extension_lowercase, get_file_size,
+    is_special_file, monitor_ffmpeg_pass, monitor_ffmpeg_progress, parse_bitrate, parse_crop,
+    parse_resolution, parse_time, probe_duration, probe_media, quiet_output, run_hook,
+    validate_input_file, validate_safe_path,
 };
-use log::{debug, info, warn};
+use bytesize::ByteSize;
+use indicatif::MultiProgress;
+use log::{debug, info};
 use std::path::{Path, PathBuf};
 
 pub struct VideoCompressor {
     pub config: Config,
     pub dry_run: bool,
     pub verbose: bool,
+    /// Display to draw FFmpeg progress in when several files compress at once
+    pub multi_progress: Option<MultiProgress>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,8 +45,38 @@ pub struct VideoCompressionOptions {
     pub start: Option<String>,
     pub end: Option<String>,
     pub two_pass: bool,
+    pub deterministic: bool,
+    pub container: Option<VideoContainer>,
+    pub cfr: bool,
+    pub metadata_from: Option<PathBuf>,
+    pub rotate_metadata: Option<Rotation>,
+    pub fallback_codec: Vec<VideoCodec>,
+    pub input_format: Option<String>,
+    pub preset_from: Option<PathBuf>,
+    pub scene_cut: Option<u8>,
+    pub fade_in: Option<f64>,
+    pub fade_out: Option<f64>,
+    pub speed: Option<f64>,
+    pub deinterlace: bool,
+    pub tune: Option<Tune>,
+    pub keyframe_interval: Option<u32>,
+    pub pix_fmt: Option<String>,
+    pub threads: Option<usize>,
+    pub field_order: Option<FieldOrder>,
+    pub max_duration: Option<String>,
+    pub watermark: Option<PathBuf>,
+    pub watermark_text: Option<String>,
+    pub watermark_position: WatermarkPosition,
+    pub watermark_opacity: f32,
+    pub hwaccel: HwAccel,
+    pub copy: bool,
+    pub target_size: Option<ByteSize>,
+    pub crop: Option<String>,
+    pub rotate: Option<Rotation>,
     pub output_dir: Option<PathBuf>,
     pub overwrite: bool,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
 }
 
 impl VideoCompressor {
@@ -43,23 +87,114 @@ impl VideoCompressor {
             config,
             dry_run,
             verbose,
+            multi_progress: None,
+        }
+    }
+
+    /// Draws this compressor's progress bars inside a shared multi-progress display
+    pub fn with_multi_progress(mut self, multi_progress: MultiProgress) -> Self {
+        self.multi_progress = Some(multi_progress);
+        self
+    }
+
+    /// Checks up front that FFmpeg has an encoder for the chosen codec or one of its fallbacks
+    /// Builds without e.g. libaom-av1 would otherwise fail with a cryptic error mid-run
+    pub fn check_encoder_available(&self, options: &VideoCompressionOptions) -> Result<()> {
+        let codec = self.get_preset_config(options)?.codec;
+
+        // Stream copy needs no encoder, and hardware encoders are checked by their backend
+        if matches!(codec, VideoCodec::Copy) || options.hwaccel != HwAccel::None {
+            return Ok(());
         }
+        // If the list can't be read, FFmpeg reports the problem itself
+        let Some(encoders) = ffmpeg_encoders() else {
+            return Ok(());
+        };
+
+        let candidates: Vec<String> = std::iter::once(&codec)
+            .chain(&options.fallback_codec)
+            .map(ToString::to_string)
+            .collect();
+        if candidates
+            .iter()
+            .any(|candidate| encoders.contains(candidate))
+        {
+            return Ok(());
+        }
+
+        Err(CompressError::unsupported_format(format!(
+            "video encoder {} isn't in this FFmpeg build; install one that includes it, \
+             pick another --codec, or add --fallback-codec",
+            candidates.join(", ")
+        )))
     }
 
     /// Compresses a video file using the specified options
     /// Handles preset application, FFmpeg command building, and execution
     /// Returns the path to the compressed output file
-    pub async fn compress(&self, options: VideoCompressionOptions) -> Result<PathBuf> {
+    pub async fn compress(&self, mut options: VideoCompressionOptions) -> Result<PathBuf> {
         // Validate input file exists and is accessible
         validate_input_file(&options.input)?;
         validate_safe_path(&options.input)?;
 
+        // Validate metadata reference file if provided
+        if let Some(reference) = &options.metadata_from {
+            validate_input_file(reference)?;
+            validate_safe_path(reference)?;
+        }
+
+        self.validate_watermark(&options)?;
+
+        // Make sure FFmpeg knows the forced input format
+        if let Some(format) = &options.input_format
+            && !check_ffmpeg_demuxer(format)
+        {
+            return Err(CompressError::invalid_parameter(
+                "input_format",
+                format!("{} (not a demuxer known to FFmpeg)", format),
+            ));
+        }
+
+        // Parse the duration limit, trim range and crop before doing any work
+        let max_duration = options
+            .max_duration
+            .as_deref()
+            .map(parse_time)
+            .transpose()?;
+        trim_duration(options.start.as_deref(), options.end.as_deref())?;
+        options.crop.as_deref().map(parse_crop).transpose()?;
+
         // Get video preset configuration from config
-        let preset_config = self.get_preset_config(&options)?;
+        let mut preset_config = self.get_preset_config(&options)?;
+
+        // Fail before probing if the hardware backend can't encode this codec
+        hardware_encoder(options.hwaccel, &preset_config.codec)?;
+        let two_pass = options.bitrate.is_some() || options.target_size.is_some();
+        if options.hwaccel != HwAccel::None && preset_config.two_pass && two_pass {
+            return Err(CompressError::invalid_parameter(
+                "hwaccel",
+                format!(
+                    "{} can't be combined with two-pass encoding",
+                    options.hwaccel
+                ),
+            ));
+        }
 
         // Generate output path with appropriate naming
         let output_path = self.generate_output_path(&options)?;
 
+        // Containers like GIF can't hold audio at all
+        let container = get_extension_lowercase(&output_path);
+        if !options.no_audio && should_drop_audio(container.as_deref(), None) {
+            debug!("Output container has no audio support; dropping audio");
+            options.no_audio = true;
+        }
+
+        // Pick an audio codec that suits the output container
+        self.resolve_audio_codec(&options, &mut preset_config, &output_path);
+        warn_ignored_audio_bitrate(&options, &preset_config);
+        warn_ignored_scene_cut(&options, &preset_config);
+
         // Ensure parent directory exists
         ensure_parent_dir(&output_path)?;
 
@@ -75,21 +210,89 @@ impl VideoCompressor {
             output_path.display()
         );
 
+        // Let the user's pre-hook validate or prepare the input
+        self.run_pre_hook(&options, &output_path, original_size.as_u64())?;
+
         if self.dry_run {
             self.print_dry_run_info(&options, &preset_config, &output_path);
             return Ok(output_path);
         }
 
+        // Keep a copy of the source in case the output replaces it
+        if self.config.default_settings.backup_originals {
+            let backup = backup_original(&options.input)?;
+            debug!("Backed up original to {}", backup.display());
+        }
+
+        // Stream copy only works if the source codec fits the target container
+        if matches!(preset_config.codec, VideoCodec::Copy) {
+            self.check_copy_compatibility(&options.input, &output_path)?;
+        }
+
+        let media_info = probe_media(&options.input).ok();
+
+        // Sources without an audio stream get no audio settings
+        if !options.no_audio && should_drop_audio(container.as_deref(), media_info.as_ref()) {
+            debug!("Input has no audio stream; skipping audio settings");
+            options.no_audio = true;
+        }
+
+        if self.verbose
+            && let Some(order) = media_info.as_ref().and_then(|i| i.field_order.as_deref())
+        {
+            print_info(&format!("Detected field order: {}", order));
+        }
+
+        // Variable frame rate sources drift out of sync unless converted
+        if let Some(info) = &media_info {
+            self.handle_variable_frame_rate(&mut options, info);
+        }
+
         // Get video duration for progress tracking
         let duration = self.get_video_duration(&options.input).await?;
+        check_max_duration(duration, max_duration)?;
+
+        // Fade-outs are placed from the end, so fail now rather than mid-encode
+        fade_filters("fade", &options, duration)?;
+
+        // A size target becomes a two-pass bitrate once the duration is known
+        if let Some(target) = options.target_size {
+            let encoded = output_duration(duration, &options)?.ok_or_else(|| {
+                CompressError::ffmpeg_error(
+                    "Could not determine video duration for --target-size",
+                    None,
+                )
+            })?;
+            let audio_bitrate = if options.no_audio {
+                0
+            } else {
+                parse_bitrate(
+                    preset_config
+                        .audio_bitrate
+                        .as_deref()
+                        .unwrap_or(AUDIO_BITRATE_MEDIUM),
+                )?
+            };
+            let video_bitrate = target_video_bitrate(target, encoded, audio_bitrate)?;
+            let bitrate = format!("{}k", video_bitrate / 1000);
+            print_info(&format!(
+                "Targeting {} over {:.1}s: video bitrate {}",
+                target, encoded, bitrate
+            ));
+
+            options.bitrate = Some(bitrate.clone());
+            preset_config.bitrate = Some(bitrate);
+        }
 
-        // Execute compression
-        if preset_config.two_pass && options.bitrate.is_some() {
-            self.execute_two_pass_compression(&options, &preset_config, &output_path, duration)
-                .await?;
-        } else {
-            self.execute_single_pass_compression(&options, &preset_config, &output_path, duration)
-                .await?;
+        // Execute compression, falling back to other codecs if the encoder is unavailable
+        self.execute_with_fallback(&options, &mut preset_config, &output_path, duration)
+            .await?;
+
+        // Streamed outputs have no meaningful size to compare
+        if is_special_file(&output_path) {
+            print_success("Video compressed successfully (streamed output)");
+            self.run_post_hook(&options, &output_path, original_size.as_u64(), None)?;
+            return Ok(output_path);
         }
 
         // Get compressed file size and calculate ratio
@@ -102,41 +305,365 @@ impl VideoCompressor {
             original_size, compressed_size, compression_ratio
         ));
 
+        self.run_post_hook(
+            &options,
+            &output_path,
+            original_size.as_u64(),
+            Some(compressed_size.as_u64()),
+        )?;
+
         Ok(output_path)
     }
 
+    /// Runs the user's pre-hook before compressing a file
+    /// A non-zero exit aborts this file; in dry-run mode the command is only printed
+    fn run_pre_hook(
+        &self,
+        options: &VideoCompressionOptions,
+        output_path: &Path,
+        input_size: u64,
+    ) -> Result<()> {
+        if let Some(hook) = &options.pre_hook {
+            let context = HookContext {
+                input: &options.input,
+                output: Some(output_path),
+                input_size: Some(input_size),
+                output_size: None,
+            };
+            if self.dry_run {
+                if !quiet_output() {
+                    println!("Pre-hook (not run): {}", expand_hook(hook, &context));
+                }
+            } else {
+                run_hook("Pre-hook", hook, &context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the user's post-hook for a successfully compressed file
+    fn run_post_hook(
+        &self,
+        options: &VideoCompressionOptions,
+        output_path: &Path,
+        input_size: u64,
+        output_size: Option<u64>,
+    ) -> Result<()> {
+        if let Some(hook) = &options.post_hook {
+            let context = HookContext {
+                input: &options.input,
+                output: Some(output_path),
+                input_size: Some(input_size),
+                output_size,
+            };
+            run_hook("Post-hook", hook, &context)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the output path that compressing with these options would produce
+    /// Used for planning batch runs without touching any files
+    pub fn planned_output_path(&self, options: &VideoCompressionOptions) -> Result<PathBuf> {
+        self.generate_output_path(options)
+    }
+
+    /// Validates watermark settings and the FFmpeg filters they need
+    fn validate_watermark(&self, options: &VideoCompressionOptions) -> Result<()> {
+        if options.watermark.is_none() && options.watermark_text.is_none() {
+            return Ok(());
+        }
+
+        if !(0.0..=1.0).contains(&options.watermark_opacity) {
+            return Err(CompressError::invalid_parameter(
+                "watermark_opacity",
+                format!(
+                    "{} (must be between 0.0 and 1.0)",
+                    options.watermark_opacity
+                ),
+            ));
+        }
+
+        let mut filters = Vec::new();
+        if let Some(watermark) = &options.watermark {
+            validate_input_file(watermark)?;
+            validate_safe_path(watermark)?;
+            filters.push("overlay");
+        }
+        if options.watermark_text.is_some() {
+            if find_default_font().is_none() {
+                return Err(CompressError::config(
+                    "No default font found for --watermark-text",
+                ));
+            }
+            filters.push("drawtext");
+        }
+
+        if !self.dry_run
+            && let Some(filter) = filters.iter().find(|filter| !check_ffmpeg_filter(filter))
+        {
+            return Err(CompressError::missing_dependency(format!(
+                "FFmpeg {} filter",
+                filter
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Gets preset configuration with command-line overrides applied
     fn get_preset_config(&self, options: &VideoCompressionOptions) -> Result<VideoPresetConfig> {
-        if let Some(preset_config) = self.config.get_video_preset(&options.preset) {
-            let mut config = preset_config.clone();
+        // A preset file replaces the named preset as the base for this run
+        let mut config = match &options.preset_from {
+            Some(path) => Config::load_preset_file::<VideoPresetConfig, _>(path)?,
+            None => self
+                .config
+                .get_video_preset(&options.preset)
+                .cloned()
+                .ok_or_else(|| {
+                    CompressError::config(format!("Unknown preset: {}", options.preset))
+                })?,
+        };
+
+        // Override with command-line options
+        if let Some(codec) = &options.codec {
+            config.codec = codec.clone();
+        }
+        if let Some(crf) = options.crf {
+            config.crf = Some(crf);
+        }
+        if let Some(bitrate) = &options.bitrate {
+            config.bitrate = Some(bitrate.clone());
+        }
+        if let Some(audio_codec) = &options.audio_codec {
+            config.audio_codec = audio_codec.clone();
+        }
+        if let Some(audio_bitrate) = &options.audio_bitrate {
+            config.audio_bitrate = Some(audio_bitrate.clone());
+        }
+        if options.two_pass {
+            config.two_pass = true;
+        }
+        // The bitrate is computed from the probed duration, so CRF is replaced later
+        if options.target_size.is_some() {
+            config.crf = None;
+            config.two_pass = true;
+        }
+
+        // --copy remuxes both streams, so quality and scaling settings can't apply
+        if options.copy && options.target_size.is_some() {
+            return Err(CompressError::invalid_parameter(
+                "target_size",
+                "cannot be combined with --copy, which doesn't re-encode",
+            ));
+        }
+        if options.copy {
+            if options.crf.is_some() || options.bitrate.is_some() || options.resolution.is_some() {
+                return Err(CompressError::invalid_parameter(
+                    "copy",
+                    "cannot be combined with --crf, --bitrate or --resolution",
+                ));
+            }
+            config.codec = VideoCodec::Copy;
+            config.audio_codec = AudioCodec::Copy;
+        }
+
+        if options.deterministic && options.metadata_from.is_some() {
+            return Err(CompressError::invalid_parameter(
+                "metadata_from",
+                "cannot be combined with --deterministic, which strips metadata",
+            ));
+        }
+
+        // Both are passed through -x265-params, and only one of those is honored
+        if options.deterministic
+            && options.scene_cut.is_some()
+            && matches!(config.codec, VideoCodec::H265)
+        {
+            return Err(CompressError::invalid_parameter(
+                "scene_cut",
+                "cannot be combined with --deterministic for H.265",
+            ));
+        }
+
+        if options.pix_fmt.is_some() && matches!(config.codec, VideoCodec::Copy) {
+            return Err(CompressError::invalid_parameter(
+                "pix_fmt",
+                "can't be changed when the video stream is copied",
+            ));
+        }
+
+        if let Some(tune) = options.tune {
+            if options.hwaccel != HwAccel::None {
+                return Err(CompressError::invalid_parameter(
+                    "tune",
+                    "only applies to software encoding (libx264/libx265), not --hwaccel",
+                ));
+            }
+            if !tune.supports(&config.codec) {
+                return Err(CompressError::invalid_parameter(
+                    "tune",
+                    format!("{} isn't a valid tune for {}", tune, config.codec),
+                ));
+            }
+        }
 
-            // Override with command-line options
-            if let Some(codec) = &options.codec {
-                config.codec = codec.clone();
+        if let Some(speed) = options.speed {
+            if !speed.is_finite() || speed <= 0.0 {
+                return Err(CompressError::invalid_parameter(
+                    "speed",
+                    "must be a positive factor, e.g. 2.0 or 0.5",
+                ));
             }
-            if let Some(crf) = options.crf {
-                config.crf = Some(crf);
+            if !options.no_audio && matches!(config.audio_codec, AudioCodec::Copy) {
+                return Err(CompressError::invalid_parameter(
+                    "speed",
+                    "audio can't be copied when its tempo changes; drop --audio-codec copy or add --no-audio",
+                ));
             }
-            if let Some(bitrate) = &options.bitrate {
-                config.bitrate = Some(bitrate.clone());
+        }
+
+        for (name, fade) in [("fade_in", options.fade_in), ("fade_out", options.fade_out)] {
+            if let Some(seconds) = fade
+                && (!seconds.is_finite() || seconds <= 0.0)
+            {
+                return Err(CompressError::invalid_parameter(
+                    name,
+                    "must be a positive number of seconds",
+                ));
+            }
+        }
+
+        if let Some(interval) = options.keyframe_interval {
+            if interval == 0 {
+                return Err(CompressError::invalid_parameter(
+                    "keyframe_interval",
+                    "must be greater than 0",
+                ));
             }
-            if let Some(audio_codec) = &options.audio_codec {
-                config.audio_codec = audio_codec.clone();
+            if matches!(config.codec, VideoCodec::Copy) {
+                return Err(CompressError::invalid_parameter(
+                    "keyframe_interval",
+                    "can't be changed when the video stream is copied",
+                ));
             }
-            if let Some(audio_bitrate) = &options.audio_bitrate {
-                config.audio_bitrate = Some(audio_bitrate.clone());
+        }
+
+        if options
+            .fallback_codec
+            .iter()
+            .any(|codec| matches!(codec, VideoCodec::Copy))
+        {
+            return Err(CompressError::invalid_parameter(
+                "fallback_codec",
+                "copy can't be used as a fallback encoder",
+            ));
+        }
+
+        // Stream copy has no rate control, so quality settings don't apply
+        if matches!(config.codec, VideoCodec::Copy) {
+            if options.resolution.is_some()
+                || options.crop.is_some()
+                || options.rotate.is_some()
+                || options.fps.is_some()
+                || options.cfr
+                || options.field_order.is_some()
+                || options.deinterlace
+                || options.speed.is_some()
+                || options.fade_in.is_some()
+                || options.fade_out.is_some()
+                || options.watermark.is_some()
+                || options.watermark_text.is_some()
+            {
+                return Err(CompressError::invalid_parameter(
+                    "codec",
+                    "copy cannot be combined with filters (--resolution, --crop, --rotate, --fps, --cfr, --field-order, --deinterlace, --speed, --fade-in, --fade-out, --watermark)",
+                ));
             }
-            if options.two_pass {
-                config.two_pass = true;
+            config.crf = None;
+            config.bitrate = None;
+            config.two_pass = false;
+        }
+
+        Ok(config)
+    }
+
+    /// Adapts the audio codec to the output container and video codec
+    /// When no audio codec was requested, the container default replaces the preset's
+    /// codec (passthrough is kept); explicit incompatible choices only produce a warning
+    fn resolve_audio_codec(
+        &self,
+        options: &VideoCompressionOptions,
+        preset_config: &mut VideoPresetConfig,
+        output_path: &Path,
+    ) {
+        if options.no_audio {
+            return;
+        }
+
+        let container = get_extension_lowercase(output_path);
+
+        if options.audio_codec.is_some() {
+            if !is_audio_codec_compatible(container.as_deref(), &preset_config.audio_codec) {
+                print_warning(&format!(
+                    "Audio codec {} is not well supported in a .{} container",
+                    preset_config.audio_codec,
+                    container.unwrap_or_default()
+                ));
             }
+        } else if matches!(preset_config.codec, VideoCodec::Copy)
+            && container.as_deref() != Some("webm")
+        {
+            // Remuxing keeps audio as-is unless the container can't hold it
+            preset_config.audio_codec = AudioCodec::Copy;
+        } else if !matches!(preset_config.audio_codec, AudioCodec::Copy) {
+            let audio_codec = default_audio_codec(container.as_deref(), &preset_config.codec);
+            debug!("Using {} audio for output container", audio_codec);
+            preset_config.audio_codec = audio_codec;
+        }
+    }
+
+    /// Detects variable frame rate input and prepares constant frame rate output
+    /// With --cfr the average rate becomes the target unless --fps was given;
+    /// without it a warning is printed since audio may drift out of sync
+    fn handle_variable_frame_rate(&self, options: &mut VideoCompressionOptions, info: &MediaInfo) {
+        if !info.is_variable_frame_rate() {
+            return;
+        }
 
-            Ok(config)
+        if options.cfr {
+            if options.fps.is_none() {
+                options.fps = info
+                    .frame_rate
+                    .map(|fps| (fps * 1000.0).round() as f32 / 1000.0);
+            }
+            debug!(
+                "Converting variable frame rate input to {:?} fps",
+                options.fps
+            );
         } else {
-            Err(CompressError::config(format!(
-                "Unknown preset: {}",
-                options.preset
-            )))
+            print_warning(
+                "Input has a variable frame rate; audio may drift out of sync. Use --cfr to convert",
+            );
+        }
+    }
+
+    /// Verifies that the source video stream can be copied into the output container
+    fn check_copy_compatibility(&self, input: &Path, output_path: &Path) -> Result<()> {
+        let Some(container) = get_extension_lowercase(output_path) else {
+            return Ok(());
+        };
+        let Some(source_codec) = probe_media(input)?.video_codec else {
+            return Ok(());
+        };
+
+        if !is_copy_compatible(&source_codec, &container) {
+            return Err(CompressError::codec_error(format!(
+                "Cannot copy {} video into a .{} container without re-encoding",
+                source_codec, container
+            )));
         }
+
+        Ok(())
     }
 
     /// Generates output path with proper naming and validation
@@ -145,12 +672,26 @@ impl VideoCompressor {
             validate_safe_path(output)?;
             Ok(output.clone())
         } else {
-            let suffix = format!("_compressed_{}", options.preset);
+            // Name outputs after the preset file when one is used
+            let preset_name = match &options.preset_from {
+                Some(path) => path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                None => options.preset.to_string(),
+            };
+            let suffix = format!("_compressed_{}", preset_name);
+            let extension = options
+                .container
+                .as_ref()
+                .map(VideoContainer::to_string)
+                .unwrap_or_else(|| DEFAULT_VIDEO_EXTENSION.to_string());
             let output_path = generate_output_path(
                 &options.input,
                 options.output_dir.as_deref(),
                 Some(&suffix),
-                Some(DEFAULT_VIDEO_EXTENSION),
+                Some(&extension),
             );
             Ok(output_path)
         }
@@ -162,19 +703,55 @@ impl VideoCompressor {
         options: &VideoCompressionOptions,
         preset_config: &VideoPresetConfig,
         output_path: &Path,
+        duration: Option<f64>,
     ) -> Result<FFmpegCommandBuilder> {
-        let mut builder = FFmpegCommandBuilder::new()
-            .input(&options.input)?
-            .video_codec(preset_config.codec.clone())
-            .preset(&preset_config.preset)
-            .progress()
-            .overwrite();
+        let hw_encoder = hardware_encoder(options.hwaccel, &preset_config.codec)?;
+        let mut builder = FFmpegCommandBuilder::new();
+
+        // Hardware decoding is an input option, so it must precede the source input
+        if hw_encoder.is_some() {
+            builder = builder.hwaccel(options.hwaccel);
+        }
+
+        // Display rotation is an input option, so it must precede the source input
+        if let Some(rotation) = options.rotate_metadata {
+            let reencode = !matches!(preset_config.codec, VideoCodec::Copy);
+            builder = builder.display_rotation(rotation, reencode);
+        }
+
+        if let Some(format) = &options.input_format {
+            builder = builder.input_format(format);
+        }
+
+        let mut builder = builder.input(&options.input)?;
+
+        // Reference input must directly follow the source input
+        if let Some(reference) = &options.metadata_from {
+            builder = builder.metadata_source(reference)?;
+        }
+
+        // The watermark image is an input too, so it must precede the output options
+        if let Some(watermark) = &options.watermark {
+            builder = builder.overlay_input(watermark)?;
+        }
+
+        builder = match hw_encoder {
+            Some(encoder) => builder.video_encoder(encoder),
+            None => builder.video_codec(preset_config.codec.clone()),
+        };
+        builder = builder.progress().overwrite();
+
+        // Encoder speed preset only applies when re-encoding in software; hardware
+        // encoders name their presets differently, so they keep their defaults
+        if !matches!(preset_config.codec, VideoCodec::Copy) && hw_encoder.is_none() {
+            builder = builder.preset(&preset_config.preset);
+        }
 
         // Video quality/bitrate
         if let Some(bitrate) = &preset_config.bitrate {
             builder = builder.bitrate(bitrate)?;
         } else if let Some(crf) = preset_config.crf {
-            builder = builder.crf(crf)?;
+            builder = builder.hw_quality(options.hwaccel, crf)?;
         }
 
         // Start time
@@ -183,25 +760,21 @@ impl VideoCompressor {
         }
 
         // Duration (calculated from start and end times)
-        if let Some(end) = &options.end {
-            if let Some(start) = &options.start {
-                let start_seconds = crate::utils::parse_time(start)?;
-                let end_seconds = crate::utils::parse_time(end)?;
-                let duration = end_seconds - start_seconds;
-                builder = builder.duration(&duration.to_string())?;
-            } else {
-                builder = builder.duration(end)?;
-            }
+        if let Some(duration) = trim_duration(options.start.as_deref(), options.end.as_deref())? {
+            builder = builder.duration(duration)?;
         }
 
-        // Resolution
-        if let Some(resolution) = &options.resolution {
-            builder = builder.resolution(resolution)?;
+        // Field order fix-up, frame rate, cropping, rotation, scaling and watermarks share one -vf,
+        // or a -filter_complex when a watermark image is overlaid
+        builder = apply_video_filters(builder, options, duration)?;
+        if let Some(order) = options.field_order {
+            builder = builder.field_order(order);
         }
-
-        // Frame rate
-        if let Some(fps) = options.fps {
-            builder = builder.framerate(fps)?;
+        if options.cfr {
+            builder = builder.constant_frame_rate();
+        }
+        if let Some(pix_fmt) = &options.pix_fmt {
+            builder = builder.pixel_format(pix_fmt);
         }
 
         // Audio handling
@@ -209,9 +782,55 @@ impl VideoCompressor {
             builder = builder.no_audio();
         } else {
             builder = builder.audio_codec(preset_config.audio_codec.clone());
-            if let Some(audio_bitrate) = &preset_config.audio_bitrate {
+            if let Some(audio_bitrate) = &preset_config.audio_bitrate
+                && !matches!(preset_config.audio_codec, AudioCodec::Copy)
+            {
                 builder = builder.audio_bitrate(audio_bitrate)?;
             }
+            if let Some(speed) = options.speed {
+                builder = builder.audio_filter(&atempo_filter(speed));
+            }
+            // Copied audio can't be filtered, so it keeps playing through the fades
+            if !matches!(preset_config.audio_codec, AudioCodec::Copy) {
+                for fade in fade_filters("afade", options, duration)? {
+                    builder = builder.audio_filter(&fade);
+                }
+            }
+        }
+
+        // Encoder tuning; a fallback codec without -tune simply drops it
+        if let Some(tune) = options.tune {
+            builder = builder.tune(&preset_config.codec, tune);
+        }
+
+        // Keyframe spacing and keyframes at scene changes
+        if let Some(interval) = options.keyframe_interval {
+            builder = builder.keyframe_interval(interval);
+        }
+        if let Some(threshold) = options.scene_cut {
+            builder = builder.scene_cut(&preset_config.codec, threshold);
+        }
+
+        // Reproducible output
+        if options.deterministic {
+            builder = builder.deterministic(&preset_config.codec);
+        } else if !matches!(preset_config.codec, VideoCodec::Copy) {
+            // --deterministic already pins a single thread
+            let threads = options
+                .threads
+                .unwrap_or(self.config.default_settings.ffmpeg_threads);
+            builder = builder.threads(threads);
+        }
+
+        // Keep or strip creation time and tags, unless another flag already decides;
+        // streamed MP4 sets its own movflags, which a second -movflags would replace
+        if options.metadata_from.is_none() && !options.deterministic {
+            let mp4_tags = matches!(
+                get_extension_lowercase(output_path).as_deref(),
+                Some("mp4" | "m4v" | "mov")
+            ) && !is_special_file(output_path);
+            builder =
+                builder.map_metadata(self.config.default_settings.preserve_metadata, mp4_tags);
         }
 
         // Extra arguments from preset
@@ -219,12 +838,49 @@ impl VideoCompressor {
             builder = builder.custom_args(&preset_config.extra_args);
         }
 
+        // Pipes can't be seeked, so use a streamable container
+        if is_special_file(output_path) {
+            builder = builder.stream_output(get_extension_lowercase(output_path).as_deref());
+        }
+
         // Output file
         builder = builder.output(output_path)?;
 
         Ok(builder)
     }
 
+    /// Runs the encode, retrying once per fallback codec when the encoder can't be initialized
+    async fn execute_with_fallback(
+        &self,
+        options: &VideoCompressionOptions,
+        preset_config: &mut VideoPresetConfig,
+        output_path: &Path,
+        duration: Option<f64>,
+    ) -> Result<()> {
+        let mut fallbacks = options.fallback_codec.iter();
+
+        loop {
+            let result = if preset_config.two_pass && options.bitrate.is_some() {
+                self.execute_two_pass_compression(options, preset_config, output_path, duration)
+                    .await
+            } else {
+                self.execute_single_pass_compression(options, preset_config, output_path, duration)
+                    .await
+            };
+
+            match (result, fallbacks.next()) {
+                (Err(CompressError::CodecError { message }), Some(fallback)) => {
+                    print_warning(&format!(
+                        "Encoder {} failed ({}); retrying with {}",
+                        preset_config.codec, message, fallback
+                    ));
+                    preset_config.codec = fallback.clone();
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
     /// Executes single-pass compression with progress tracking
     async fn execute_single_pass_compression(
         &self,
@@ -233,7 +889,7 @@ impl VideoCompressor {
         output_path: &Path,
         duration: Option<f64>,
     ) -> Result<()> {
-        let builder = self.build_ffmpeg_command(options, preset_config, output_path)?;
+        let builder = self.build_ffmpeg_command(options, preset_config, output_path, duration)?;
         let mut command = builder.build();
 
         if self.verbose {
@@ -247,7 +903,8 @@ impl VideoCompressor {
             )
         })?;
 
-        let progress_parser = FFmpegProgressParser::new(duration);
+        let progress_parser =
+            FFmpegProgressParser::new(duration).in_multi_progress(self.multi_progress.as_ref());
         progress_parser.set_message("Compressing video...");
 
         monitor_ffmpeg_progress(child, progress_parser).await?;
@@ -267,7 +924,7 @@ impl VideoCompressor {
 
         // First pass
         let mut first_pass_builder =
-            self.build_ffmpeg_command(options, preset_config, output_path)?;
+            self.build_ffmpeg_command(options, preset_config, output_path, duration)?;
         first_pass_builder = first_pass_builder.first_pass();
         let mut first_pass_cmd = first_pass_builder.build();
 
@@ -282,14 +939,16 @@ impl VideoCompressor {
             )
         })?;
 
-        let first_pass_parser = FFmpegProgressParser::new(duration);
-        first_pass_parser.set_message("Pass 1/2: Analyzing video...");
+        // Both passes share one progress bar: pass 1 fills 0-50%, pass 2 fills 50-100%
+        let mut progress_parser =
+            FFmpegProgressParser::new(duration).in_multi_progress(self.multi_progress.as_ref());
+        progress_parser.start_pass(1, 2, "Pass 1/2: Analyzing video...");
 
-        monitor_ffmpeg_progress(first_pass_child, first_pass_parser).await?;
+        monitor_ffmpeg_pass(first_pass_child, &progress_parser).await?;
 
         // Second pass
         let mut second_pass_builder =
-            self.build_ffmpeg_command(options, preset_config, output_path)?;
+            self.build_ffmpeg_command(options, preset_config, output_path, duration)?;
         second_pass_builder = second_pass_builder.second_pass();
         let mut second_pass_cmd = second_pass_builder.build();
 
@@ -304,39 +963,17 @@ impl VideoCompressor {
             )
         })?;
 
-        let second_pass_parser = FFmpegProgressParser::new(duration);
-        second_pass_parser.set_message("Pass 2/2: Encoding video...");
+        progress_parser.start_pass(2, 2, "Pass 2/2: Encoding video...");
 
-        monitor_ffmpeg_progress(second_pass_child, second_pass_parser).await?;
+        monitor_ffmpeg_pass(second_pass_child, &progress_parser).await?;
+        progress_parser.finish();
 
         Ok(())
     }
 
     /// Gets video duration using FFprobe
     async fn get_video_duration(&self, input: &Path) -> Result<Option<f64>> {
-        let mut command = FFprobeCommandBuilder::new()
-            .input(input)?
-            .duration()
-            .build();
-
-        let output = command.output().map_err(|e| {
-            CompressError::ffmpeg_error(
-                format!("Failed to run FFprobe: {}", e),
-                Some(format!("{:?}", command)),
-            )
-        })?;
-
-        if !output.status.success() {
-            warn!("FFprobe failed to get duration, continuing without progress tracking");
-            return Ok(None);
-        }
-
-        let duration_str = String::from_utf8_lossy(&output.stdout);
-        let duration: f64 = duration_str.trim().parse().map_err(|e| {
-            CompressError::progress_error(format!("Failed to parse video duration: {}", e))
-        })?;
-
-        Ok(Some(duration))
+        probe_duration(input)
     }
 
     /// Prints dry run information
@@ -346,6 +983,9 @@ impl VideoCompressor {
         preset_config: &VideoPresetConfig,
         output_path: &Path,
     ) {
+        if quiet_output() {
+            return;
+        }
         println!(
             "\n{}",
             console::style("DRY RUN - No files will be modified")
@@ -354,8 +994,14 @@ impl VideoCompressor {
         );
         println!("Input:  {}", options.input.display());
         println!("Output: {}", output_path.display());
-        println!("Preset: {}", options.preset);
-        println!("Codec:  {}", preset_config.codec);
+        match &options.preset_from {
+            Some(path) => println!("Preset: {}", path.display()),
+            None => println!("Preset: {}", options.preset),
+        }
+        match hardware_encoder(options.hwaccel, &preset_config.codec) {
+            Ok(Some(encoder)) => println!("Codec:  {} ({})", encoder, options.hwaccel),
+            _ => println!("Codec:  {}", preset_config.codec),
+        }
 
         if let Some(crf) = preset_config.crf {
             println!("CRF:    {}", crf);
@@ -363,12 +1009,30 @@ impl VideoCompressor {
         if let Some(bitrate) = &preset_config.bitrate {
             println!("Bitrate: {}", bitrate);
         }
+        if let Some(target) = options.target_size {
+            println!(
+                "Target size: {} (bitrate computed from the duration)",
+                target
+            );
+        }
+        if let Some(crop) = &options.crop {
+            println!("Crop:   {}", crop);
+        }
+        if let Some(rotation) = options.rotate {
+            println!("Rotate: {} degrees", rotation.degrees());
+        }
         if let Some(resolution) = &options.resolution {
             println!("Resolution: {}", resolution);
         }
         if let Some(fps) = options.fps {
             println!("FPS:    {}", fps);
         }
+        if let Some(watermark) = &options.watermark {
+            println!("Watermark: {}", watermark.display());
+        }
+        if let Some(text) = &options.watermark_text {
+            println!("Watermark text: {}", text);
+        }
 
         let audio_info = if options.no_audio {
             "Disabled".to_string()
@@ -380,65 +1044,399 @@ impl VideoCompressor {
         if preset_config.two_pass {
             println!("Mode:   Two-pass encoding");
         }
+        if !options.fallback_codec.is_empty() {
+            let fallbacks: Vec<String> = options
+                .fallback_codec
+                .iter()
+                .map(|codec| codec.to_string())
+                .collect();
+            println!("Fallback: {}", fallbacks.join(" -> "));
+        }
+        if options.deterministic {
+            println!("Deterministic: single-threaded, bitexact");
+        }
+        if let Some(reference) = &options.metadata_from {
+            println!("Metadata from: {}", reference.display());
+        }
+        if let Some(hook) = &options.post_hook {
+            println!("Post-hook: {}", hook);
+        }
     }
 }
 
-// Make VideoCompressor cloneable for async processing
-impl Clone for VideoCompressor {
-    fn clone(&self) -> Self {
-        Self {
-            config: self.config.clone(),
-            dry_run: self.dry_run,
-            verbose: self.verbose,
-        }
+/// Chooses the default audio codec for an output container and video codec
+/// WebM requires Opus and MP4-family containers expect AAC; other containers
+/// follow the video codec (Opus alongside VP9/AV1, AAC alongside H.264/H.265)
+fn default_audio_codec(container: Option<&str>, codec: &VideoCodec) -> AudioCodec {
+    match container {
+        Some("webm") => AudioCodec::Opus,
+        Some("mp4" | "m4v" | "mov" | "3gp") => AudioCodec::Aac,
+        _ => match codec {
+            VideoCodec::Vp9 | VideoCodec::Av1 => AudioCodec::Opus,
+            VideoCodec::H264 | VideoCodec::H265 | VideoCodec::Copy => AudioCodec::Aac,
+        },
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Adds the encode's video filters to the builder's -vf chain
+/// Filters run as field order fix-up, frame rate, cropping, rotation, scaling, then watermarks, so
+/// watermarks keep their size and margins regardless of the output resolution. A watermark
+/// image needs the builder's `overlay_input`, and turns the chain into a -filter_complex
+fn apply_video_filters(
+    mut builder: FFmpegCommandBuilder,
+    options: &VideoCompressionOptions,
+    duration: Option<f64>,
+) -> Result<FFmpegCommandBuilder> {
+    if let Some(order) = options.field_order {
+        builder = builder.video_filter(&format!("setfield={}", order.setfield_mode()));
+    }
+    // Deinterlace before anything resamples the frame, or the combing gets scaled in
+    if options.deinterlace {
+        builder = builder.deinterlace();
+    }
+    // Retime before --fps, so the frame rate applies to the sped-up timeline
+    if let Some(speed) = options.speed {
+        builder = builder.video_filter(&format!("setpts=PTS/{}", speed));
+    }
+    if let Some(fps) = options.fps {
+        builder = builder.framerate(fps)?;
+    }
+    if let Some(crop) = &options.crop {
+        let (width, height, x, y) = parse_crop(crop)?;
+        builder = builder.video_filter(&format!("crop={}:{}:{}:{}", width, height, x, y));
+    }
+    if let Some(rotation) = options.rotate {
+        builder = builder.rotate(rotation);
+    }
+    if let Some(resolution) = &options.resolution {
+        builder = builder.scale(parse_resolution(resolution)?);
+    }
 
-    #[test]
-    fn test_generate_output_path() {
-        let input = PathBuf::from("/test/input.mp4");
-        let options = VideoCompressionOptions {
-            input: input.clone(),
-            output: None,
-            preset: VideoPreset::Medium,
-            codec: None,
-            crf: None,
-            bitrate: None,
-            resolution: None,
-            fps: None,
-            audio_codec: None,
-            audio_bitrate: None,
-            no_audio: false,
-            start: None,
-            end: None,
-            two_pass: false,
-            output_dir: None,
-            overwrite: false,
-        };
+    if options.watermark.is_some() {
+        builder = builder.overlay(
+            &format!(
+                "format=rgba,colorchannelmixer=aa={}",
+                options.watermark_opacity
+            ),
+            overlay_position(options.watermark_position),
+        )?;
+    }
 
-        let config = Config::default();
-        let compressor = VideoCompressor::new(config, false, false);
-        let output = compressor.generate_output_path(&options).unwrap();
+    if let Some(text) = &options.watermark_text {
+        let font = find_default_font()
+            .ok_or_else(|| CompressError::config("No default font found for --watermark-text"))?;
+        builder = builder.video_filter(&format!(
+            "drawtext=fontfile='{}':text='{}':expansion=none:fontsize=h/20:fontcolor=white@{}:borderw=2:bordercolor=black@{}:{}",
+            escape_filter_path(&font),
+            escape_filter_value(text),
+            options.watermark_opacity,
+            options.watermark_opacity,
+            drawtext_position(options.watermark_position)
+        ));
+    }
 
-        assert!(output.to_string_lossy().contains("_compressed_medium"));
-        assert!(output.extension().unwrap() == "mp4");
+    for fade in fade_filters("fade", options, duration)? {
+        builder = builder.video_filter(&fade);
     }
 
-    #[test]
-    fn test_preset_config_override() {
-        let config = Config::default();
-        let compressor = VideoCompressor::new(config, false, false);
+    // VA-API encoders only take frames uploaded to the GPU
+    if options.hwaccel == HwAccel::Vaapi {
+        builder = builder.video_filter("format=nv12,hwupload");
+    }
 
-        let options = VideoCompressionOptions {
-            input: PathBuf::from("test.mp4"),
-            output: None,
-            preset: VideoPreset::Medium,
-            codec: Some(VideoCodec::H265),
-            crf: Some(20),
+    Ok(builder)
+}
+
+/// Resolves the hardware encoder for `--hwaccel`, or None for software encoding
+fn hardware_encoder(accel: HwAccel, codec: &VideoCodec) -> Result<Option<&'static str>> {
+    if accel == HwAccel::None {
+        return Ok(None);
+    }
+
+    accel.encoder(codec).map(Some).ok_or_else(|| {
+        let supported: Vec<String> = [
+            VideoCodec::H264,
+            VideoCodec::H265,
+            VideoCodec::Vp9,
+            VideoCodec::Av1,
+        ]
+        .iter()
+        .filter(|codec| accel.encoder(codec).is_some())
+        .map(|codec| format!("{:?}", codec).to_lowercase())
+        .collect();
+        CompressError::invalid_parameter(
+            "hwaccel",
+            format!(
+                "{} has no encoder for {:?}; supported codecs: {}",
+                accel,
+                codec,
+                supported.join(", ")
+            ),
+        )
+    })
+}
+
+/// Overlay coordinates for a watermark image, with a 10 pixel margin
+fn overlay_position(position: WatermarkPosition) -> &'static str {
+    match position {
+        WatermarkPosition::TopLeft => "10:10",
+        WatermarkPosition::TopRight => "W-w-10:10",
+        WatermarkPosition::BottomLeft => "10:H-h-10",
+        WatermarkPosition::BottomRight => "W-w-10:H-h-10",
+        WatermarkPosition::Center => "(W-w)/2:(H-h)/2",
+    }
+}
+
+/// Drawtext coordinates for a text watermark, with a 10 pixel margin
+fn drawtext_position(position: WatermarkPosition) -> &'static str {
+    match position {
+        WatermarkPosition::TopLeft => "x=10:y=10",
+        WatermarkPosition::TopRight => "x=w-tw-10:y=10",
+        WatermarkPosition::BottomLeft => "x=10:y=h-th-10",
+        WatermarkPosition::BottomRight => "x=w-tw-10:y=h-th-10",
+        WatermarkPosition::Center => "x=(w-tw)/2:y=(h-th)/2",
+    }
+}
+
+/// Duration of the encoded output: the trimmed range, or what's left after `--start`
+/// -ss and -t apply after the filters, so they count time on the `--speed` timeline
+fn output_duration(source: Option<f64>, options: &VideoCompressionOptions) -> Result<Option<f64>> {
+    if let Some(trimmed) = trim_duration(options.start.as_deref(), options.end.as_deref())? {
+        return Ok(Some(trimmed));
+    }
+    let speed = options.speed.unwrap_or(1.0);
+    let start = options.start.as_deref().map(parse_time).transpose()?;
+    Ok(source.map(|duration| (duration / speed - start.unwrap_or(0.0)).max(0.0)))
+}
+
+/// Builds the fade-in and fade-out filters for `fade` (video) or `afade` (audio)
+/// Fades are timed on the filter timeline, so they start at `--start` and the
+/// fade-out ends where the encoded output does, which needs the probed duration
+fn fade_filters(
+    filter: &str,
+    options: &VideoCompressionOptions,
+    source: Option<f64>,
+) -> Result<Vec<String>> {
+    let start = options
+        .start
+        .as_deref()
+        .map(parse_time)
+        .transpose()?
+        .unwrap_or(0.0);
+    let mut fades = Vec::new();
+
+    if let Some(fade_in) = options.fade_in {
+        fades.push(format!("{}=t=in:st={}:d={}", filter, start, fade_in));
+    }
+
+    if let Some(fade_out) = options.fade_out {
+        let encoded = output_duration(source, options)?.ok_or_else(|| {
+            CompressError::invalid_parameter(
+                "fade_out",
+                "could not determine the video duration to place the fade-out",
+            )
+        })?;
+        let fade_start = start + (encoded - fade_out).max(0.0);
+        fades.push(format!("{}=t=out:st={}:d={}", filter, fade_start, fade_out));
+    }
+
+    Ok(fades)
+}
+
+/// Builds the atempo chain for a speed factor
+/// A single atempo stage is limited to 0.5-2.0, so larger changes are split into
+/// several stages whose factors multiply to the total
+fn atempo_filter(speed: f64) -> String {
+    let mut stages = Vec::new();
+    let mut remaining = speed;
+    while remaining > 2.0 {
+        stages.push(2.0);
+        remaining /= 2.0;
+    }
+    while remaining < 0.5 {
+        stages.push(0.5);
+        remaining /= 0.5;
+    }
+    stages.push(remaining);
+
+    stages
+        .iter()
+        .map(|stage| format!("atempo={}", stage))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Computes the video bitrate (bits/s) that lands `duration` seconds of output at `target`
+/// Audio takes its share first and a small fraction is reserved for container overhead
+fn target_video_bitrate(target: ByteSize, duration: f64, audio_bitrate: u64) -> Result<u64> {
+    if duration <= 0.0 {
+        return Err(CompressError::invalid_parameter(
+            "target_size",
+            "the video has no duration to spread the size over",
+        ));
+    }
+
+    let total_bits = target.as_u64() as f64 * 8.0 * (1.0 - TARGET_SIZE_MUX_OVERHEAD);
+    let video_bitrate = total_bits / duration - audio_bitrate as f64;
+
+    if video_bitrate < MIN_TARGET_VIDEO_BITRATE as f64 {
+        return Err(CompressError::invalid_parameter(
+            "target_size",
+            format!(
+                "{} is too small for {:.1}s of video; it leaves {:.0} kbps for video",
+                target,
+                duration,
+                (video_bitrate / 1000.0).max(0.0)
+            ),
+        ));
+    }
+
+    Ok(video_bitrate as u64)
+}
+
+/// Computes the `-t` output duration for `--end`, measured from `--start` (or 0)
+/// `--end` is an absolute timestamp in the source, so it must come after the start
+fn trim_duration(start: Option<&str>, end: Option<&str>) -> Result<Option<f64>> {
+    let Some(end) = end else {
+        return Ok(None);
+    };
+    let start_seconds = start.map(parse_time).transpose()?.unwrap_or(0.0);
+    let end_seconds = parse_time(end)?;
+
+    if end_seconds <= start_seconds {
+        return Err(CompressError::invalid_parameter(
+            "end",
+            format!(
+                "End time ({}s) must be after the start time ({}s)",
+                end_seconds, start_seconds
+            ),
+        ));
+    }
+
+    Ok(Some(end_seconds - start_seconds))
+}
+
+/// Refuses inputs longer than the --max-duration limit
+/// An unknown duration can't be checked against the limit, so it's refused as well
+fn check_max_duration(duration: Option<f64>, max_duration: Option<f64>) -> Result<()> {
+    let Some(limit) = max_duration else {
+        return Ok(());
+    };
+
+    match duration {
+        Some(duration) if duration > limit => Err(CompressError::invalid_parameter(
+            "max_duration",
+            format!(
+                "input is {:.1}s long, over the {:.1}s limit (raise or drop --max-duration to encode it)",
+                duration, limit
+            ),
+        )),
+        Some(_) => Ok(()),
+        None => Err(CompressError::invalid_parameter(
+            "max_duration",
+            "could not determine the input duration to check against the limit",
+        )),
+    }
+}
+
+/// Warns when --scene-cut can't take effect
+/// Only libx264 and libx265 expose a scene-change threshold; VP9 and AV1 place
+/// keyframes with their own lookahead and stream copy doesn't encode at all
+fn warn_ignored_scene_cut(options: &VideoCompressionOptions, preset_config: &VideoPresetConfig) {
+    if options.scene_cut.is_some()
+        && !matches!(preset_config.codec, VideoCodec::H264 | VideoCodec::H265)
+    {
+        print_warning(&format!(
+            "--scene-cut is ignored for {}; only H.264 and H.265 support it",
+            preset_config.codec
+        ));
+    }
+}
+
+/// Warns when an explicit --audio-bitrate can't take effect
+/// Bitrates only apply when audio is re-encoded, not when it's copied or removed
+fn warn_ignored_audio_bitrate(
+    options: &VideoCompressionOptions,
+    preset_config: &VideoPresetConfig,
+) {
+    if options.audio_bitrate.is_none() {
+        return;
+    }
+
+    if options.no_audio {
+        print_warning("--audio-bitrate is ignored because audio is removed");
+    } else if matches!(preset_config.audio_codec, AudioCodec::Copy) {
+        print_warning("--audio-bitrate is ignored because audio is copied without re-encoding");
+    }
+}
+
+/// Decides whether audio has to be dropped instead of emitting codec flags FFmpeg rejects
+/// Applies to containers without audio support (GIF, image sequences) and, once probed,
+/// to sources that have a video stream but no audio stream
+fn should_drop_audio(container: Option<&str>, source: Option<&MediaInfo>) -> bool {
+    let audio_capable = !matches!(
+        container,
+        Some("gif" | "apng" | "png" | "jpg" | "jpeg" | "webp" | "bmp")
+    );
+    let source_without_audio =
+        source.is_some_and(|info| info.video_codec.is_some() && info.audio_codec.is_none());
+
+    !audio_capable || source_without_audio
+}
+
+/// Checks whether a source video codec (FFprobe name) can be stream-copied into a container
+/// WebM only holds VP8/VP9/AV1, MP4-family containers hold MPEG/H.26x/VP9/AV1, AVI holds
+/// legacy MPEG-4 and H.264 codecs; Matroska accepts everything
+fn is_copy_compatible(source_codec: &str, container: &str) -> bool {
+    match container {
+        "webm" => matches!(source_codec, "vp8" | "vp9" | "av1"),
+        "mp4" | "m4v" | "mov" => matches!(
+            source_codec,
+            "h264" | "hevc" | "mpeg4" | "mpeg2video" | "mpeg1video" | "av1" | "vp9" | "mjpeg"
+        ),
+        "avi" => matches!(
+            source_codec,
+            "h264" | "mpeg4" | "msmpeg4v2" | "msmpeg4v3" | "mjpeg" | "mpeg2video" | "mpeg1video"
+        ),
+        _ => true,
+    }
+}
+
+/// Checks whether an audio codec can be muxed into the given container without issues
+fn is_audio_codec_compatible(container: Option<&str>, audio_codec: &AudioCodec) -> bool {
+    match (container, audio_codec) {
+        (_, AudioCodec::Copy) => true,
+        (Some("webm"), codec) => matches!(codec, AudioCodec::Opus),
+        (Some("mp4" | "m4v" | "mov" | "3gp"), codec) => {
+            matches!(codec, AudioCodec::Aac | AudioCodec::Mp3)
+        }
+        _ => true,
+    }
+}
+
+// Make VideoCompressor cloneable for async processing
+impl Clone for VideoCompressor {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            dry_run: self.dry_run,
+            verbose: self.verbose,
+            multi_progress: self.multi_progress.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_options(input: &str) -> VideoCompressionOptions {
+        VideoCompressionOptions {
+            input: PathBuf::from(input),
+            output: None,
+            preset: VideoPreset::Medium,
+            codec: None,
+            crf: None,
             bitrate: None,
             resolution: None,
             fps: None,
@@ -448,12 +1446,692 @@ mod tests {
             start: None,
             end: None,
             two_pass: false,
+            deterministic: false,
+            container: None,
+            cfr: false,
+            metadata_from: None,
+            rotate_metadata: None,
+            fallback_codec: Vec::new(),
+            input_format: None,
+            preset_from: None,
+            scene_cut: None,
+            fade_in: None,
+            fade_out: None,
+            speed: None,
+            deinterlace: false,
+            tune: None,
+            keyframe_interval: None,
+            pix_fmt: None,
+            threads: None,
+            field_order: None,
+            max_duration: None,
+            watermark: None,
+            watermark_text: None,
+            watermark_position: WatermarkPosition::BottomRight,
+            watermark_opacity: 1.0,
+            hwaccel: HwAccel::None,
+            copy: false,
+            target_size: None,
+            crop: None,
+            rotate: None,
             output_dir: None,
             overwrite: false,
+            pre_hook: None,
+            post_hook: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_output_path() {
+        let options = sample_options("/test/input.mp4");
+
+        let config = Config::default();
+        let compressor = VideoCompressor::new(config, false, false);
+        let output = compressor.generate_output_path(&options).unwrap();
+
+        assert!(output.to_string_lossy().contains("_compressed_medium"));
+        assert!(output.extension().unwrap() == "mp4");
+    }
+
+    #[test]
+    fn test_preset_config_override() {
+        let config = Config::default();
+        let compressor = VideoCompressor::new(config, false, false);
+
+        let options = VideoCompressionOptions {
+            codec: Some(VideoCodec::H265),
+            crf: Some(20),
+            ..sample_options("test.mp4")
         };
 
         let preset_config = compressor.get_preset_config(&options).unwrap();
         assert!(matches!(preset_config.codec, VideoCodec::H265));
         assert_eq!(preset_config.crf, Some(20));
     }
+
+    #[test]
+    fn test_default_audio_codec_per_container() {
+        assert!(matches!(
+            default_audio_codec(Some("webm"), &VideoCodec::Vp9),
+            AudioCodec::Opus
+        ));
+        assert!(matches!(
+            default_audio_codec(Some("mp4"), &VideoCodec::H264),
+            AudioCodec::Aac
+        ));
+        assert!(matches!(
+            default_audio_codec(Some("mp4"), &VideoCodec::Av1),
+            AudioCodec::Aac
+        ));
+        assert!(matches!(
+            default_audio_codec(Some("mkv"), &VideoCodec::Av1),
+            AudioCodec::Opus
+        ));
+        assert!(matches!(
+            default_audio_codec(Some("mkv"), &VideoCodec::H265),
+            AudioCodec::Aac
+        ));
+    }
+
+    #[test]
+    fn test_audio_dropped_for_gif_and_silent_sources() {
+        // GIF can't hold audio, whatever the source has
+        assert!(should_drop_audio(Some("gif"), None));
+
+        let silent = MediaInfo {
+            video_codec: Some("h264".to_string()),
+            audio_codec: None,
+            ..Default::default()
+        };
+        assert!(should_drop_audio(Some("mp4"), Some(&silent)));
+
+        let with_audio = MediaInfo {
+            video_codec: Some("h264".to_string()),
+            audio_codec: Some("aac".to_string()),
+            ..Default::default()
+        };
+        assert!(!should_drop_audio(Some("mp4"), Some(&with_audio)));
+
+        // An empty probe result isn't evidence of a silent source
+        assert!(!should_drop_audio(Some("mkv"), Some(&MediaInfo::default())));
+    }
+
+    #[test]
+    fn test_copy_compatibility() {
+        assert!(is_copy_compatible("h264", "mp4"));
+        assert!(is_copy_compatible("mpeg4", "mp4"));
+        assert!(!is_copy_compatible("mpeg4", "webm"));
+        assert!(!is_copy_compatible("wmv3", "mp4"));
+        assert!(is_copy_compatible("wmv3", "mkv"));
+    }
+
+    #[test]
+    fn test_copy_codec_disables_rate_control() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            preset: VideoPreset::Slow,
+            codec: Some(VideoCodec::Copy),
+            container: Some(VideoContainer::Mp4),
+            ..sample_options("test.avi")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        assert_eq!(preset_config.crf, None);
+        assert!(!preset_config.two_pass);
+
+        let output = compressor.generate_output_path(&options).unwrap();
+        assert_eq!(output.extension().unwrap(), "mp4");
+
+        let with_resize = VideoCompressionOptions {
+            resolution: Some("720p".to_string()),
+            ..options
+        };
+        assert!(compressor.get_preset_config(&with_resize).is_err());
+    }
+
+    #[test]
+    fn test_copy_flag_remuxes_both_streams() {
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            start: Some("10".to_string()),
+            container: Some(VideoContainer::Mp4),
+            copy: true,
+            ..sample_options("test.mkv")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-c:v\" \"copy\""));
+        assert!(cmd_str.contains("\"-c:a\" \"copy\""));
+        assert!(!cmd_str.contains("-crf"));
+        assert!(!cmd_str.contains("-preset"));
+
+        let with_crf = VideoCompressionOptions {
+            crf: Some(20),
+            ..options.clone()
+        };
+        assert!(compressor.get_preset_config(&with_crf).is_err());
+
+        let with_resize = VideoCompressionOptions {
+            resolution: Some("720p".to_string()),
+            ..options
+        };
+        assert!(compressor.get_preset_config(&with_resize).is_err());
+    }
+
+    #[test]
+    fn test_audio_bitrate_skipped_when_audio_copied() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            audio_codec: Some(AudioCodec::Copy),
+            audio_bitrate: Some("192k".to_string()),
+            ..sample_options("test.mkv")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mkv"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-c:a\" \"copy\""));
+        assert!(!cmd_str.contains("-b:a"));
+    }
+
+    #[test]
+    fn test_field_order_shares_filtergraph_with_scaling() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            resolution: Some("720p".to_string()),
+            field_order: Some(FieldOrder::Tff),
+            ..sample_options("capture.ts")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-vf\" \"setfield=tff,scale=1280:720\""));
+        assert!(cmd_str.contains("\"-field_order\" \"tff\""));
+        assert_eq!(cmd_str.matches("\"-vf\"").count(), 1);
+    }
+
+    #[test]
+    fn test_deinterlace_precedes_crop_and_scale() {
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            deinterlace: true,
+            crop: Some("704:576:8:0".to_string()),
+            resolution: Some("480p".to_string()),
+            ..sample_options("dv.avi")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-vf\" \"yadif,crop=704:576:8:0,scale=640:480\""));
+    }
+
+    #[test]
+    fn test_crop_and_scale_share_one_filter() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            resolution: Some("720p".to_string()),
+            crop: Some("1280:720:0:140".to_string()),
+            ..sample_options("letterboxed.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-vf\" \"crop=1280:720:0:140,scale=1280:720\""));
+        assert_eq!(cmd_str.matches("\"-vf\"").count(), 1);
+    }
+
+    #[test]
+    fn test_preserve_metadata_maps_metadata() {
+        let mut compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            ..sample_options("camera.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-map_metadata\" \"0\""));
+        assert!(cmd_str.contains("\"-movflags\" \"use_metadata_tags\""));
+
+        compressor.config.default_settings.preserve_metadata = false;
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-map_metadata\" \"-1\""));
+        assert!(!cmd_str.contains("use_metadata_tags"));
+    }
+
+    #[test]
+    fn test_pixel_format() {
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            codec: Some(VideoCodec::H265),
+            pix_fmt: Some("yuv420p".to_string()),
+            ..sample_options("clip.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+        assert!(format!("{:?}", command).contains("\"-pix_fmt\" \"yuv420p\""));
+
+        let copy = VideoCompressionOptions {
+            codec: Some(VideoCodec::Copy),
+            ..options
+        };
+        assert!(compressor.get_preset_config(&copy).is_err());
+    }
+
+    #[test]
+    fn test_tune_per_codec() {
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            codec: Some(VideoCodec::H264),
+            tune: Some(Tune::Film),
+            ..sample_options("clip.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+        assert!(format!("{:?}", command).contains("\"-tune\" \"film\""));
+
+        let vp9 = VideoCompressionOptions {
+            codec: Some(VideoCodec::Vp9),
+            ..options.clone()
+        };
+        assert!(compressor.get_preset_config(&vp9).is_err());
+
+        // libx265 has no film tune
+        let h265 = VideoCompressionOptions {
+            codec: Some(VideoCodec::H265),
+            ..options
+        };
+        assert!(compressor.get_preset_config(&h265).is_err());
+    }
+
+    #[test]
+    fn test_speed_filters() {
+        assert_eq!(atempo_filter(2.0), "atempo=2");
+        assert_eq!(atempo_filter(4.0), "atempo=2,atempo=2");
+        assert_eq!(atempo_filter(0.25), "atempo=0.5,atempo=0.5");
+        assert_eq!(atempo_filter(3.0), "atempo=2,atempo=1.5");
+
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            speed: Some(4.0),
+            ..sample_options("screencast.mp4")
+        };
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let build = |options: &VideoCompressionOptions| {
+            let command = compressor
+                .build_ffmpeg_command(options, &preset_config, Path::new("out.mp4"), None)
+                .unwrap()
+                .build();
+            format!("{:?}", command)
+        };
+
+        let cmd_str = build(&options);
+        assert!(cmd_str.contains("\"-vf\" \"setpts=PTS/4\""));
+        assert!(cmd_str.contains("\"-af\" \"atempo=2,atempo=2\""));
+
+        let silent = VideoCompressionOptions {
+            no_audio: true,
+            ..options.clone()
+        };
+        let cmd_str = build(&silent);
+        assert!(cmd_str.contains("setpts=PTS/4"));
+        assert!(!cmd_str.contains("-af"));
+
+        assert_eq!(output_duration(Some(60.0), &options).unwrap(), Some(15.0));
+
+        // -ss/-t are output options, so the trim is measured on the sped-up timeline
+        let trimmed = VideoCompressionOptions {
+            start: Some("5".to_string()),
+            end: Some("10".to_string()),
+            ..options.clone()
+        };
+        assert_eq!(output_duration(Some(60.0), &trimmed).unwrap(), Some(5.0));
+        let from_start = VideoCompressionOptions {
+            start: Some("5".to_string()),
+            ..options.clone()
+        };
+        assert_eq!(
+            output_duration(Some(60.0), &from_start).unwrap(),
+            Some(10.0)
+        );
+
+        // A preset that copies audio can't have its tempo changed either
+        let mut config = Config::default();
+        config
+            .video_presets
+            .get_mut(&VideoPreset::Medium.to_string())
+            .unwrap()
+            .audio_codec = AudioCodec::Copy;
+        let compressor = VideoCompressor::new(config, true, false);
+        assert!(compressor.get_preset_config(&options).is_err());
+        assert!(compressor.get_preset_config(&silent).is_ok());
+    }
+
+    #[test]
+    fn test_fade_filters() {
+        let options = VideoCompressionOptions {
+            fade_in: Some(1.5),
+            fade_out: Some(2.0),
+            ..sample_options("clip.mp4")
+        };
+
+        assert_eq!(
+            fade_filters("fade", &options, Some(60.0)).unwrap(),
+            ["fade=t=in:st=0:d=1.5", "fade=t=out:st=58:d=2"]
+        );
+        assert_eq!(
+            fade_filters("afade", &options, Some(60.0)).unwrap(),
+            ["afade=t=in:st=0:d=1.5", "afade=t=out:st=58:d=2"]
+        );
+
+        // Trimmed outputs fade out at --end
+        let trimmed = VideoCompressionOptions {
+            start: Some("10".to_string()),
+            end: Some("40".to_string()),
+            ..options.clone()
+        };
+        assert_eq!(
+            fade_filters("fade", &trimmed, Some(60.0)).unwrap(),
+            ["fade=t=in:st=10:d=1.5", "fade=t=out:st=38:d=2"]
+        );
+
+        // The fade-out can't be placed without a duration
+        assert!(fade_filters("fade", &options, None).is_err());
+        let fade_in_only = VideoCompressionOptions {
+            fade_out: None,
+            ..options
+        };
+        assert_eq!(
+            fade_filters("fade", &fade_in_only, None).unwrap(),
+            ["fade=t=in:st=0:d=1.5"]
+        );
+    }
+
+    #[test]
+    fn test_keyframe_interval() {
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            keyframe_interval: Some(48),
+            ..sample_options("clip.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+        assert!(format!("{:?}", command).contains("\"-g\" \"48\""));
+
+        let zero = VideoCompressionOptions {
+            keyframe_interval: Some(0),
+            ..options
+        };
+        assert!(compressor.get_preset_config(&zero).is_err());
+    }
+
+    #[test]
+    fn test_threads_limit() {
+        let mut compressor = VideoCompressor::new(Config::default(), true, false);
+        let options = VideoCompressionOptions {
+            threads: Some(2),
+            ..sample_options("clip.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let build = |compressor: &VideoCompressor, options: &VideoCompressionOptions| {
+            let command = compressor
+                .build_ffmpeg_command(options, &preset_config, Path::new("out.mp4"), None)
+                .unwrap()
+                .build();
+            format!("{:?}", command)
+        };
+        assert!(build(&compressor, &options).contains("\"-threads\" \"2\""));
+
+        // Zero leaves the thread count to FFmpeg
+        let auto = VideoCompressionOptions {
+            threads: Some(0),
+            ..options.clone()
+        };
+        assert!(!build(&compressor, &auto).contains("-threads"));
+
+        compressor.config.default_settings.ffmpeg_threads = 4;
+        let from_config = VideoCompressionOptions {
+            threads: None,
+            ..options
+        };
+        assert!(build(&compressor, &from_config).contains("\"-threads\" \"4\""));
+    }
+
+    #[test]
+    fn test_rotate_adds_transpose_filter() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            rotate: Some(Rotation::Cw90),
+            ..sample_options("portrait.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-vf\" \"transpose=1\""));
+        assert!(!cmd_str.contains("display_rotation"));
+        assert_eq!(cmd_str.matches("\"-vf\"").count(), 1);
+    }
+
+    #[test]
+    fn test_hwaccel_substitutes_encoder() {
+        let compressor = VideoCompressor::new(Config::default(), false, false);
+        let options = VideoCompressionOptions {
+            codec: Some(VideoCodec::H264),
+            hwaccel: HwAccel::Nvenc,
+            ..sample_options("clip.mp4")
+        };
+
+        let preset_config = compressor.get_preset_config(&options).unwrap();
+        let command = compressor
+            .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", command);
+        assert!(cmd_str.contains("\"-c:v\" \"h264_nvenc\""));
+        assert!(cmd_str.contains("\"-hwaccel\" \"cuda\" \"-i\""));
+        assert!(cmd_str.contains("\"-cq\""));
+        assert!(!cmd_str.contains("libx264"));
+        assert!(!cmd_str.contains("\"-crf\""));
+
+        let err = hardware_encoder(HwAccel::Nvenc, &VideoCodec::Vp9)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("supported codecs: h264, h265, av1"));
+    }
+
+    #[test]
+    fn test_watermark_position_and_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let logo = dir.path().join("logo.png");
+        std::fs::write(&logo, b"png").unwrap();
+        let compressor = VideoCompressor::new(Config::default(), true, false);
+
+        for (position, overlay) in [
+            (WatermarkPosition::TopLeft, "overlay=10:10"),
+            (WatermarkPosition::TopRight, "overlay=W-w-10:10"),
+            (WatermarkPosition::BottomLeft, "overlay=10:H-h-10"),
+            (WatermarkPosition::BottomRight, "overlay=W-w-10:H-h-10"),
+            (WatermarkPosition::Center, "overlay=(W-w)/2:(H-h)/2"),
+        ] {
+            let options = VideoCompressionOptions {
+                watermark: Some(logo.clone()),
+                watermark_position: position,
+                ..sample_options("clip.mp4")
+            };
+            let preset_config = compressor.get_preset_config(&options).unwrap();
+            let command = compressor
+                .build_ffmpeg_command(&options, &preset_config, Path::new("out.mp4"), None)
+                .unwrap()
+                .build();
+            let args: Vec<String> = command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect();
+
+            // The logo is a second input, composited in a -filter_complex
+            assert_eq!(args.iter().filter(|arg| *arg == "-i").count(), 2);
+            assert_eq!(
+                args[args.iter().rposition(|arg| arg == "-i").unwrap() + 1],
+                logo.to_string_lossy()
+            );
+            let graph = &args[args
+                .iter()
+                .position(|arg| arg == "-filter_complex")
+                .unwrap()
+                + 1];
+            assert!(graph.contains("[1:v]format=rgba"), "{}", graph);
+            assert!(graph.contains(overlay), "{}", graph);
+            assert!(!args.contains(&"-vf".to_string()));
+        }
+
+        let missing = VideoCompressionOptions {
+            watermark: Some(dir.path().join("missing.png")),
+            ..sample_options("clip.mp4")
+        };
+        assert!(compressor.validate_watermark(&missing).is_err());
+    }
+
+    #[test]
+    fn test_watermark_overlays_after_scaling() {
+        let options = VideoCompressionOptions {
+            resolution: Some("720p".to_string()),
+            watermark: Some(PathBuf::from("/brand/logo.png")),
+            watermark_opacity: 0.5,
+            ..sample_options("clip.mp4")
+        };
+
+        let builder = FFmpegCommandBuilder::new()
+            .input("clip.mp4")
+            .unwrap()
+            .overlay_input("/brand/logo.png")
+            .unwrap();
+        let command = apply_video_filters(builder, &options, None)
+            .unwrap()
+            .build();
+        let args: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            args,
+            [
+                "-i",
+                "clip.mp4",
+                "-i",
+                "/brand/logo.png",
+                "-filter_complex",
+                "[0:v]scale=1280:720[base];[1:v]format=rgba,colorchannelmixer=aa=0.5[overlay];[base][overlay]overlay=W-w-10:H-h-10[video]",
+                "-map",
+                "[video]",
+                "-map",
+                "0:a?"
+            ]
+        );
+
+        let options = VideoCompressionOptions {
+            watermark: None,
+            resolution: None,
+            ..options
+        };
+        let command = apply_video_filters(FFmpegCommandBuilder::new(), &options, None)
+            .unwrap()
+            .build();
+        assert_eq!(command.get_args().count(), 0);
+    }
+
+    #[test]
+    fn test_max_duration_limit() {
+        // Probed duration of a 2 hour input against a 1 hour limit
+        let err = check_max_duration(Some(7200.0), Some(parse_time("1:00:00").unwrap()))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("7200.0s"));
+
+        assert!(check_max_duration(Some(1800.0), Some(3600.0)).is_ok());
+        assert!(check_max_duration(None, None).is_ok());
+        assert!(check_max_duration(None, Some(3600.0)).is_err());
+    }
+
+    #[test]
+    fn test_audio_codec_compatibility() {
+        assert!(!is_audio_codec_compatible(Some("webm"), &AudioCodec::Aac));
+        assert!(!is_audio_codec_compatible(Some("mp4"), &AudioCodec::Opus));
+        assert!(is_audio_codec_compatible(Some("mp4"), &AudioCodec::Mp3));
+        assert!(is_audio_codec_compatible(Some("mkv"), &AudioCodec::Opus));
+        assert!(is_audio_codec_compatible(Some("webm"), &AudioCodec::Copy));
+    }
+
+    #[test]
+    fn test_trim_duration() {
+        assert_eq!(trim_duration(Some("10"), Some("1:00")).unwrap(), Some(50.0));
+        assert_eq!(trim_duration(None, Some("00:00:30")).unwrap(), Some(30.0));
+        assert_eq!(trim_duration(Some("10"), None).unwrap(), None);
+        assert!(trim_duration(Some("1:00"), Some("30")).is_err());
+        assert!(trim_duration(Some("30"), Some("30")).is_err());
+    }
+
+    #[test]
+    fn test_target_video_bitrate() {
+        // 10 MB over 100s is 800 kbps total; 2% overhead and 128k audio leave 656 kbps
+        let bitrate = target_video_bitrate(ByteSize::mb(10), 100.0, 128_000).unwrap();
+        assert_eq!(bitrate, 656_000);
+
+        assert_eq!(
+            target_video_bitrate(ByteSize::mb(10), 100.0, 0).unwrap(),
+            784_000
+        );
+        assert!(target_video_bitrate(ByteSize::kb(100), 600.0, 128_000).is_err());
+        assert!(target_video_bitrate(ByteSize::mb(10), 0.0, 0).is_err());
+    }
 }
@@ -0,0 +1,279 @@
+use crate::cli::args::{AudioCodec, AudioVisual, VideoCodec, VideoPreset};
+use crate::core::{CompressError, Config, DEFAULT_VIDEO_EXTENSION, Result, VideoPresetConfig};
+use crate::ui::progress::print_success;
+use crate::utils::{
+    FFmpegCommandBuilder, FFmpegProgressParser, check_output_overwrite, ensure_parent_dir,
+    generate_output_path, monitor_ffmpeg_progress, parse_resolution, probe_duration, probe_media,
+    validate_input_file, validate_safe_path,
+};
+use log::{debug, info};
+use std::path::{Path, PathBuf};
+
+/// Frame rate of generated visualizations
+const VISUALIZATION_FPS: u32 = 30;
+
+pub struct AudioVisualizer {
+    pub config: Config,
+    pub dry_run: bool,
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct VisualizeOptions {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub visual: AudioVisual,
+    pub resolution: String,
+    pub preset: VideoPreset,
+    pub codec: Option<VideoCodec>,
+    pub crf: Option<u8>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+impl AudioVisualizer {
+    /// Creates a new AudioVisualizer instance
+    /// Initializes with configuration, dry-run mode, and verbosity settings
+    pub fn new(config: Config, dry_run: bool, verbose: bool) -> Self {
+        Self {
+            config,
+            dry_run,
+            verbose,
+        }
+    }
+
+    /// Generates a video from an audio file, drawing a waveform or spectrum
+    /// The video stream is encoded with the preset's codec settings and the
+    /// original audio is muxed alongside it
+    /// Returns the path to the generated video
+    pub async fn visualize(&self, options: VisualizeOptions) -> Result<PathBuf> {
+        validate_input_file(&options.input)?;
+        validate_safe_path(&options.input)?;
+
+        let preset_config = self.get_preset_config(&options)?;
+        let filter = build_visualization_filter(&options)?;
+
+        let output_path = self.generate_output_path(&options)?;
+        ensure_parent_dir(&output_path)?;
+        check_output_overwrite(&output_path, options.overwrite)?;
+
+        info!(
+            "Visualizing audio: {} -> {}",
+            options.input.display(),
+            output_path.display()
+        );
+
+        if self.dry_run {
+            self.print_dry_run_info(&options, &preset_config, &output_path);
+            return Ok(output_path);
+        }
+
+        if probe_media(&options.input)?.audio_codec.is_none() {
+            return Err(CompressError::invalid_parameter(
+                "input",
+                format!("{} has no audio stream", options.input.display()),
+            ));
+        }
+        let duration = probe_duration(&options.input)?;
+
+        let mut builder = FFmpegCommandBuilder::new()
+            .input(&options.input)?
+            .custom_args(["-filter_complex", &filter, "-map", "[v]", "-map", "0:a"])
+            .video_codec(preset_config.codec.clone())
+            .preset(&preset_config.preset);
+
+        if let Some(bitrate) = &preset_config.bitrate {
+            builder = builder.bitrate(bitrate)?;
+        } else if let Some(crf) = preset_config.crf {
+            builder = builder.crf(crf)?;
+        }
+
+        builder = builder.audio_codec(preset_config.audio_codec.clone());
+        if let Some(audio_bitrate) = &preset_config.audio_bitrate
+            && !matches!(preset_config.audio_codec, AudioCodec::Copy)
+        {
+            builder = builder.audio_bitrate(audio_bitrate)?;
+        }
+
+        let mut command = builder
+            .custom_args(["-shortest"])
+            .progress()
+            .overwrite()
+            .output(&output_path)?
+            .build();
+
+        if self.verbose {
+            debug!("Executing FFmpeg command: {:?}", command);
+        }
+
+        let child = command.spawn().map_err(|e| {
+            CompressError::ffmpeg_error(
+                format!("Failed to start FFmpeg: {}", e),
+                Some(format!("{:?}", command)),
+            )
+        })?;
+
+        let progress_parser = FFmpegProgressParser::new(duration);
+        progress_parser.set_message("Rendering visualization...");
+
+        monitor_ffmpeg_progress(child, progress_parser).await?;
+
+        print_success(&format!("Visualization rendered at {}", options.resolution));
+
+        Ok(output_path)
+    }
+
+    /// Gets the video preset with command-line overrides applied
+    /// The video stream is generated, so it always has to be encoded
+    fn get_preset_config(&self, options: &VisualizeOptions) -> Result<VideoPresetConfig> {
+        let mut config = self
+            .config
+            .get_video_preset(&options.preset)
+            .cloned()
+            .ok_or_else(|| CompressError::config(format!("Unknown preset: {}", options.preset)))?;
+
+        if let Some(codec) = &options.codec {
+            config.codec = codec.clone();
+        }
+        if let Some(crf) = options.crf {
+            config.crf = Some(crf);
+            config.bitrate = None;
+        }
+
+        if matches!(config.codec, VideoCodec::Copy) {
+            return Err(CompressError::invalid_parameter(
+                "codec",
+                "copy can't be used for a generated video stream",
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Generates output path with proper naming and validation
+    fn generate_output_path(&self, options: &VisualizeOptions) -> Result<PathBuf> {
+        if let Some(output) = &options.output {
+            validate_safe_path(output)?;
+            Ok(output.clone())
+        } else {
+            let suffix = match options.visual {
+                AudioVisual::Waves => "_waves",
+                AudioVisual::Spectrum => "_spectrum",
+            };
+            Ok(generate_output_path(
+                &options.input,
+                options.output_dir.as_deref(),
+                Some(suffix),
+                Some(DEFAULT_VIDEO_EXTENSION),
+            ))
+        }
+    }
+
+    /// Prints dry run information
+    fn print_dry_run_info(
+        &self,
+        options: &VisualizeOptions,
+        preset_config: &VideoPresetConfig,
+        output_path: &Path,
+    ) {
+        println!(
+            "\n{}",
+            console::style("DRY RUN - No files will be modified")
+                .yellow()
+                .bold()
+        );
+        println!("Input:  {}", options.input.display());
+        println!("Output: {}", output_path.display());
+        println!("Visual: {:?}", options.visual);
+        println!("Resolution: {}", options.resolution);
+        println!("Codec:  {}", preset_config.codec);
+        if let Some(crf) = preset_config.crf {
+            println!("CRF:    {}", crf);
+        }
+        println!("Audio:  {}", preset_config.audio_codec);
+    }
+}
+
+/// Builds the filtergraph that renders the audio into a `[v]` video stream
+fn build_visualization_filter(options: &VisualizeOptions) -> Result<String> {
+    let (width, height) = parse_resolution(&options.resolution)?
+        .dimensions()
+        .ok_or_else(|| {
+            CompressError::invalid_parameter(
+                "resolution",
+                "visualizations need both a width and a height",
+            )
+        })?;
+
+    let visual = match options.visual {
+        AudioVisual::Waves => format!(
+            "showwaves=s={}x{}:mode=cline:rate={}",
+            width, height, VISUALIZATION_FPS
+        ),
+        AudioVisual::Spectrum => format!(
+            "showspectrum=s={}x{}:slide=scroll:mode=combined:color=intensity,fps={}",
+            width, height, VISUALIZATION_FPS
+        ),
+    };
+
+    // Most players only handle 4:2:0 video
+    Ok(format!("[0:a]{},format=yuv420p[v]", visual))
+}
+
+// Make AudioVisualizer cloneable for async processing
+impl Clone for AudioVisualizer {
+    fn clone(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            dry_run: self.dry_run,
+            verbose: self.verbose,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_options(visual: AudioVisual) -> VisualizeOptions {
+        VisualizeOptions {
+            input: PathBuf::from("/music/track.flac"),
+            output: None,
+            visual,
+            resolution: "720p".to_string(),
+            preset: VideoPreset::Medium,
+            codec: None,
+            crf: None,
+            output_dir: None,
+            overwrite: false,
+        }
+    }
+
+    #[test]
+    fn test_visualization_filter() {
+        let waves = build_visualization_filter(&sample_options(AudioVisual::Waves)).unwrap();
+        assert_eq!(
+            waves,
+            "[0:a]showwaves=s=1280x720:mode=cline:rate=30,format=yuv420p[v]"
+        );
+
+        let spectrum = build_visualization_filter(&sample_options(AudioVisual::Spectrum)).unwrap();
+        assert!(spectrum.starts_with("[0:a]showspectrum=s=1280x720"));
+        assert!(spectrum.ends_with("[v]"));
+    }
+
+    #[test]
+    fn test_visualize_output_path_and_copy_rejected() {
+        let visualizer = AudioVisualizer::new(Config::default(), false, false);
+        let output = visualizer
+            .generate_output_path(&sample_options(AudioVisual::Spectrum))
+            .unwrap();
+        assert_eq!(output, PathBuf::from("/music/track_spectrum.mp4"));
+
+        let options = VisualizeOptions {
+            codec: Some(VideoCodec::Copy),
+            ..sample_options(AudioVisual::Waves)
+        };
+        assert!(visualizer.get_preset_config(&options).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::compression::{BatchOptions, BatchProcessor};
+use crate::core::{CompressError, Config, Result, WATCH_DEBOUNCE_MS};
+use crate::ui::progress::{print_error, print_header, print_info, print_success};
+use crate::utils::{is_compressed_output, is_image_file, is_video_file};
+use glob::Pattern;
+use log::{debug, warn};
+use notify::event::{AccessKind, AccessMode, ModifyKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+pub struct DirectoryWatcher {
+    pub config: Config,
+    pub dry_run: bool,
+    pub verbose: bool,
+}
+
+impl DirectoryWatcher {
+    /// Creates a new DirectoryWatcher instance
+    /// Initializes with configuration, dry-run mode, and verbosity settings
+    pub fn new(config: Config, dry_run: bool, verbose: bool) -> Self {
+        Self {
+            config,
+            dry_run,
+            verbose,
+        }
+    }
+
+    /// Compresses files as they appear in a directory until Ctrl-C is pressed
+    /// Watch mode reuses the batch settings; a file is picked up once it has gone
+    /// quiet for a moment, so files still being copied in aren't read half-written
+    pub async fn watch(&self, options: BatchOptions) -> Result<()> {
+        let pattern = Pattern::new(&options.pattern)
+            .map_err(|e| CompressError::invalid_parameter("pattern", e.to_string()))?;
+
+        // The watcher stops when dropped, so it has to live as long as the loop
+        let (_watcher, events) = start_watcher(&options.directory, options.recursive)?;
+
+        print_header(&format!("Watching: {}", options.directory.display()));
+        print_info("Press Ctrl-C to stop");
+
+        let processor = BatchProcessor::new(self.config.clone(), self.dry_run, self.verbose);
+        let shutdown = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                warn!("Failed to listen for Ctrl-C: {}", e);
+                std::future::pending::<()>().await;
+            }
+        };
+
+        watch_events(events, &pattern, &options, shutdown, |file| {
+            processor.process_file(file, &options)
+        })
+        .await
+    }
+}
+
+/// Starts watching a directory and forwards the paths of arriving files
+fn start_watcher(
+    directory: &Path,
+    recursive: bool,
+) -> Result<(RecommendedWatcher, UnboundedReceiver<PathBuf>)> {
+    let watch_error = |e: notify::Error| {
+        CompressError::process_failed(format!("Failed to watch directory: {}", e))
+    };
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if is_file_arrival(&event.kind) => {
+                for path in event.paths {
+                    let _ = sender.send(path);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("File watcher error: {}", e),
+        })
+        .map_err(watch_error)?;
+
+    let mode = if recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    watcher.watch(directory, mode).map_err(watch_error)?;
+
+    Ok((watcher, receiver))
+}
+
+/// Checks whether an event means a file was created, written or moved in
+fn is_file_arrival(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_))
+            | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    )
+}
+
+/// Collects arriving files and compresses each once it has had no events for the
+/// debounce period; runs until `shutdown` completes
+async fn watch_events<F, Fut>(
+    mut events: UnboundedReceiver<PathBuf>,
+    pattern: &Pattern,
+    options: &BatchOptions,
+    shutdown: impl Future<Output = ()>,
+    mut process: F,
+) -> Result<()>
+where
+    F: FnMut(PathBuf) -> Fut,
+    Fut: Future<Output = Result<PathBuf>>,
+{
+    let debounce = Duration::from_millis(WATCH_DEBOUNCE_MS);
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut tick = tokio::time::interval(debounce / 4);
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => {
+                print_info("Stopped watching");
+                return Ok(());
+            }
+            event = events.recv() => match event {
+                Some(path) if is_watch_candidate(&path, pattern, options) => {
+                    debug!("File event: {}", path.display());
+                    pending.insert(path, Instant::now());
+                }
+                Some(_) => {}
+                None => {
+                    return Err(CompressError::process_failed("File watcher stopped unexpectedly"));
+                }
+            },
+            _ = tick.tick() => {
+                let mut ready: Vec<PathBuf> = pending
+                    .iter()
+                    .filter(|(_, last_event)| last_event.elapsed() >= debounce)
+                    .map(|(path, _)| path.clone())
+                    .collect();
+                ready.sort();
+
+                for file in ready {
+                    pending.remove(&file);
+
+                    // Files can be removed again before they settle
+                    if !file.is_file() {
+                        continue;
+                    }
+
+                    match process(file.clone()).await {
+                        Ok(output) => print_success(&format!(
+                            "{} -> {}",
+                            file.display(),
+                            output.display()
+                        )),
+                        Err(e) => print_error(&format!("{}: {}", file.display(), e)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether a path is a file watch mode should compress
+/// Our own outputs land in the same directory by default, so they're skipped
+fn is_watch_candidate(path: &Path, pattern: &Pattern, options: &BatchOptions) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+
+    let wanted = (options.videos && is_video_file(path)) || (options.images && is_image_file(path));
+    wanted && pattern.matches(name) && !is_compressed_output(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{BatchSort, VideoPreset};
+    use std::sync::{Arc, Mutex};
+
+    fn sample_options(directory: PathBuf) -> BatchOptions {
+        BatchOptions {
+            directory,
+            pattern: "*".to_string(),
+            exclude: Vec::new(),
+            videos: true,
+            images: true,
+            recursive: false,
+            video_preset: VideoPreset::Medium,
+            image_quality: None,
+            max_memory: None,
+            image_preset: None,
+            skip_existing: false,
+            delete_originals: false,
+            only_stale: false,
+            sort: BatchSort::Name,
+            fallback_codec: Vec::new(),
+            jobs: 1,
+            output_dir: None,
+            overwrite: false,
+            pre_hook: None,
+            post_hook: None,
+        }
+    }
+
+    #[test]
+    fn test_watch_skips_compressed_outputs() {
+        let options = sample_options(PathBuf::from("drop"));
+        let pattern = Pattern::new("*").unwrap();
+
+        assert!(is_watch_candidate(
+            Path::new("drop/clip.mp4"),
+            &pattern,
+            &options
+        ));
+        assert!(is_watch_candidate(
+            Path::new("drop/photo.jpg"),
+            &pattern,
+            &options
+        ));
+        assert!(!is_watch_candidate(
+            Path::new("drop/clip_compressed_medium.mp4"),
+            &pattern,
+            &options
+        ));
+        assert!(!is_watch_candidate(
+            Path::new("drop/photo_compressed.jpg"),
+            &pattern,
+            &options
+        ));
+        assert!(!is_watch_candidate(
+            Path::new("drop/notes.txt"),
+            &pattern,
+            &options
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_created_file_triggers_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = sample_options(dir.path().to_path_buf());
+        let pattern = Pattern::new(&options.pattern).unwrap();
+        let (_watcher, events) = start_watcher(dir.path(), false).unwrap();
+
+        std::fs::write(dir.path().join("photo.jpg"), b"not really a jpeg").unwrap();
+
+        let processor = BatchProcessor::new(Config::default(), true, false);
+        let outputs = Arc::new(Mutex::new(Vec::new()));
+        let shutdown = {
+            let outputs = Arc::clone(&outputs);
+            async move {
+                while outputs.lock().unwrap().is_empty() {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        };
+
+        let watch = watch_events(events, &pattern, &options, shutdown, |file| {
+            let outputs = Arc::clone(&outputs);
+            let compress = processor.process_file(file, &options);
+            async move {
+                let output = compress.await?;
+                outputs.lock().unwrap().push(output.clone());
+                Ok(output)
+            }
+        });
+        tokio::time::timeout(Duration::from_secs(10), watch)
+            .await
+            .expect("no file event within 10s")
+            .unwrap();
+
+        assert_eq!(
+            *outputs.lock().unwrap(),
+            [dir.path().join("photo_compressed.jpg")]
+        );
+    }
+}
@@ -1,19 +1,131 @@
-use crate::cli::args::{AudioCodec, VideoCodec, VideoPreset};
+use crate::cli::args::{AudioCodec, ImageFormat, ProgressTheme, VideoCodec, VideoPreset};
 use crate::core::constants::*;
 use crate::core::error::{CompressError, Result};
+use crate::utils::{parse_bitrate, parse_resolution};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Explanations written above each setting by `init`
+/// Keys that repeat (like preset fields) are only explained where they first appear
+const CONFIG_COMMENTS: &[(&str, &str)] = &[
+    (
+        "video_presets",
+        "Video presets, selected with --preset <name>; add your own alongside the built-ins",
+    ),
+    ("codec", "Video codec: H264, H265, Vp9, Av1 or Copy"),
+    (
+        "crf",
+        "Constant Rate Factor (0-51, lower = better quality); ignored when bitrate is set",
+    ),
+    ("bitrate", "Target video bitrate such as 2M or 800k"),
+    ("audio_codec", "Audio codec: Aac, Mp3, Opus or Copy"),
+    ("audio_bitrate", "Audio bitrate such as 128k"),
+    ("preset", "Encoder speed preset (ultrafast ... veryslow)"),
+    (
+        "two_pass",
+        "Encode twice for more accurate bitrate targeting",
+    ),
+    (
+        "extra_args",
+        "Extra FFmpeg arguments appended to the command",
+    ),
+    (
+        "image_presets",
+        "Image presets, selected with --preset <name>",
+    ),
+    ("quality", "Image quality (1-100)"),
+    ("optimize", "Spend extra time on smaller output"),
+    ("progressive", "Write progressive JPEGs"),
+    ("lossless", "Lossless encoding where the format supports it"),
+    (
+        "default_settings",
+        "Defaults applied when the matching flag isn't given",
+    ),
+    (
+        "output_dir",
+        "Directory for outputs (default: next to the input)",
+    ),
+    ("overwrite", "Replace existing output files"),
+    ("parallel_jobs", "Files processed at once in batch mode"),
+    (
+        "preserve_metadata",
+        "Keep creation time and tags in compressed videos",
+    ),
+    (
+        "backup_originals",
+        "Copy each source to <name>.bak before compressing",
+    ),
+    (
+        "default_video_preset",
+        "Video preset used when --preset isn't given",
+    ),
+    (
+        "default_image_preset",
+        "Image preset used when --preset isn't given",
+    ),
+    ("progress_style", "auto, unicode or ascii"),
+    (
+        "progress_template",
+        "Custom indicatif template for progress bars",
+    ),
+    (
+        "retry_attempts",
+        "Batch retries for transient errors such as locked files",
+    ),
+    (
+        "ffmpeg_threads",
+        "FFmpeg threads per encode (0 lets FFmpeg decide)",
+    ),
+    (
+        "ffmpeg_path",
+        "FFmpeg binary to use instead of the one on the PATH",
+    ),
+    (
+        "ffprobe_path",
+        "FFprobe binary to use instead of the one on the PATH",
+    ),
+    (
+        "format_quality_defaults",
+        "Image quality per output format when no quality or preset is given",
+    ),
+    (
+        "profiles",
+        "Named settings selected with --profile <name>; command-line flags still win",
+    ),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub video_presets: HashMap<String, VideoPresetConfig>,
     pub image_presets: HashMap<String, ImagePresetConfig>,
     pub default_settings: DefaultSettings,
+    /// Default image quality per output format, used when no quality or preset is given
+    #[serde(default = "default_format_quality")]
+    pub format_quality_defaults: HashMap<String, u8>,
+    /// Named settings bundles, selected with `--profile`
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A named bundle of settings such as "youtube" or "archive"
+/// Unset fields leave the command's own defaults in place
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub codec: Option<VideoCodec>,
+    /// Target resolution such as 1080p or 1280x720
+    pub resolution: Option<String>,
+    pub audio_codec: Option<AudioCodec>,
+    pub audio_bitrate: Option<String>,
+    pub output_dir: Option<PathBuf>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VideoPresetConfig {
     pub codec: VideoCodec,
     pub crf: Option<u8>,
@@ -25,7 +137,7 @@ pub struct VideoPresetConfig {
     pub extra_args: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImagePresetConfig {
     pub quality: u8,
     pub optimize: bool,
@@ -33,6 +145,25 @@ pub struct ImagePresetConfig {
     pub lossless: bool,
 }
 
+/// Which preset table a preset name refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetKind {
+    Video,
+    Image,
+}
+
+/// Where a preset's settings come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresetOrigin {
+    /// A built-in preset with its default settings
+    Builtin,
+    /// A built-in name whose settings were changed in the config
+    Overridden,
+    /// A preset added by the user
+    User,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefaultSettings {
     pub output_dir: Option<PathBuf>,
@@ -40,12 +171,36 @@ pub struct DefaultSettings {
     pub parallel_jobs: usize,
     pub preserve_metadata: bool,
     pub backup_originals: bool,
+    /// Video preset used when `--preset` isn't given
+    #[serde(default)]
+    pub default_video_preset: Option<String>,
+    /// Image preset used when `--preset` isn't given
+    #[serde(default)]
+    pub default_image_preset: Option<String>,
+    /// Progress bar and status symbol style
+    #[serde(default)]
+    pub progress_style: ProgressTheme,
+    /// Custom indicatif template for progress bars
+    #[serde(default)]
+    pub progress_template: Option<String>,
+    /// Retries for a batch file that fails with a transient error (e.g. a locked file)
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: usize,
+    /// FFmpeg threads per encode; 0 lets FFmpeg decide
+    #[serde(default)]
+    pub ffmpeg_threads: usize,
+    /// FFmpeg binary to run instead of `ffmpeg` from the PATH
+    #[serde(default)]
+    pub ffmpeg_path: Option<PathBuf>,
+    /// FFprobe binary to run instead of `ffprobe` from the PATH
+    #[serde(default)]
+    pub ffprobe_path: Option<PathBuf>,
 }
 
-impl Config {
+impl Default for Config {
     /// Creates a new Config instance with default presets
     /// This initializes built-in video and image presets for common use cases
-    pub fn default() -> Self {
+    fn default() -> Self {
         let mut video_presets = HashMap::new();
         let mut image_presets = HashMap::new();
 
@@ -120,16 +275,28 @@ impl Config {
         Self {
             video_presets,
             image_presets,
+            format_quality_defaults: default_format_quality(),
+            profiles: HashMap::new(),
             default_settings: DefaultSettings {
                 output_dir: None,
                 overwrite: false,
                 parallel_jobs: num_cpus::get().max(1), // Ensure at least 1 job
                 preserve_metadata: true,
                 backup_originals: false,
+                default_video_preset: None,
+                default_image_preset: None,
+                progress_style: ProgressTheme::Auto,
+                progress_template: None,
+                retry_attempts: MAX_RETRY_ATTEMPTS,
+                ffmpeg_threads: 0,
+                ffmpeg_path: None,
+                ffprobe_path: None,
             },
         }
     }
+}
 
+impl Config {
     /// Loads configuration from a YAML or TOML file
     /// Automatically detects file format based on extension
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -143,25 +310,53 @@ impl Config {
         Ok(config)
     }
 
+    /// Loads a single video or image preset from a YAML or TOML file
+    /// Used to apply a shared preset file for one run without installing it
+    pub fn load_preset_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+        let path_ref = path.as_ref();
+        let content = fs::read_to_string(path_ref).map_err(|e| {
+            CompressError::config(format!(
+                "Failed to read preset file {}: {}",
+                path_ref.display(),
+                e
+            ))
+        })?;
+
+        let parsed = if path_ref.extension().and_then(|s| s.to_str()) == Some("toml") {
+            toml::from_str(&content).map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        };
+
+        parsed.map_err(|e| {
+            CompressError::config(format!("Invalid preset file {}: {}", path_ref.display(), e))
+        })
+    }
+
     /// Saves the current configuration to a file
     /// Creates parent directories if they don't exist
     /// Format is determined by file extension (.toml or .yaml/.yml)
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let path_ref = path.as_ref();
+        let content = self.serialize_for(path_ref)?;
+        write_config_file(path_ref, &content)
+    }
 
-        // Create parent directory if it doesn't exist
-        if let Some(parent) = path_ref.parent() {
-            fs::create_dir_all(parent)?;
-        }
+    /// Saves the configuration with a comment explaining each setting
+    /// Used by `init` to give new users a documented starting point
+    pub fn save_commented<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path_ref = path.as_ref();
+        let content = annotate_config(&self.serialize_for(path_ref)?);
+        write_config_file(path_ref, &content)
+    }
 
-        let content = if path_ref.extension().and_then(|s| s.to_str()) == Some("toml") {
-            toml::to_string_pretty(self)?
+    /// Serializes to TOML or YAML depending on the file extension
+    fn serialize_for(&self, path: &Path) -> Result<String> {
+        if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+            Ok(toml::to_string_pretty(self)?)
         } else {
-            serde_yaml::to_string(self)?
-        };
-
-        fs::write(path_ref, content)?;
-        Ok(())
+            Ok(serde_yaml::to_string(self)?)
+        }
     }
 
     /// Gets the configuration directory for CompressCLI
@@ -205,12 +400,178 @@ impl Config {
         self.video_presets.get(&preset.to_string())
     }
 
+    /// Resolves the video preset to use for a command
+    /// Precedence: explicit `--preset` > `default_video_preset` > medium
+    pub fn resolve_video_preset(&self, preset: Option<VideoPreset>) -> Result<VideoPreset> {
+        if let Some(preset) = preset {
+            return Ok(preset);
+        }
+
+        match &self.default_settings.default_video_preset {
+            Some(name) => VideoPreset::from_str(name, true).map_err(|_| {
+                CompressError::config(format!("Unknown default_video_preset: {}", name))
+            }),
+            None => Ok(VideoPreset::Medium),
+        }
+    }
+
+    /// Checks preset values the loader accepts but FFmpeg or the encoders would reject
+    /// Returns one message per problem, in a stable order
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut video_presets: Vec<_> = self.video_presets.iter().collect();
+        video_presets.sort_by_key(|(name, _)| name.as_str());
+        for (name, preset) in video_presets {
+            if let Some(crf) = preset.crf
+                && crf > 51
+            {
+                problems.push(format!(
+                    "video preset '{}': crf {} is out of range (0-51)",
+                    name, crf
+                ));
+            }
+            for (field, value) in [
+                ("bitrate", &preset.bitrate),
+                ("audio_bitrate", &preset.audio_bitrate),
+            ] {
+                if let Some(value) = value
+                    && parse_bitrate(value).is_err()
+                {
+                    problems.push(format!(
+                        "video preset '{}': {} '{}' is not a bitrate like 2M or 128k",
+                        name, field, value
+                    ));
+                }
+            }
+        }
+
+        let mut image_presets: Vec<_> = self.image_presets.iter().collect();
+        image_presets.sort_by_key(|(name, _)| name.as_str());
+        for (name, preset) in image_presets {
+            if !(1..=100).contains(&preset.quality) {
+                problems.push(format!(
+                    "image preset '{}': quality {} is out of range (1-100)",
+                    name, preset.quality
+                ));
+            }
+        }
+
+        let mut profiles: Vec<_> = self.profiles.iter().collect();
+        profiles.sort_by_key(|(name, _)| name.as_str());
+        for (name, profile) in profiles {
+            if let Some(value) = &profile.resolution
+                && parse_resolution(value).is_err()
+            {
+                problems.push(format!(
+                    "profile '{}': resolution '{}' is not a resolution like 1080p or 1280x720",
+                    name, value
+                ));
+            }
+            if let Some(value) = &profile.audio_bitrate
+                && parse_bitrate(value).is_err()
+            {
+                problems.push(format!(
+                    "profile '{}': audio_bitrate '{}' is not a bitrate like 128k",
+                    name, value
+                ));
+            }
+        }
+
+        let mut format_defaults: Vec<_> = self.format_quality_defaults.iter().collect();
+        format_defaults.sort();
+        for (format, quality) in format_defaults {
+            if !(1..=100).contains(quality) {
+                problems.push(format!(
+                    "format_quality_defaults: {} quality {} is out of range (1-100)",
+                    format, quality
+                ));
+            }
+        }
+
+        if let Err(e) = self.resolve_video_preset(None) {
+            problems.push(e.to_string());
+        }
+        if let Some(name) = &self.default_settings.default_image_preset
+            && !self.image_presets.contains_key(name)
+        {
+            problems.push(format!("default_image_preset '{}' is not defined", name));
+        }
+
+        problems
+    }
+
+    /// Resolves how many files a batch run processes at once
+    /// Precedence: explicit `--jobs` > `parallel_jobs`, never less than 1
+    pub fn resolve_parallel_jobs(&self, jobs: Option<usize>) -> usize {
+        jobs.unwrap_or(self.default_settings.parallel_jobs).max(1)
+    }
+
+    /// Resolves the output directory for a command
+    /// Precedence: explicit `--output-dir` > `output_dir` > next to the input (None)
+    pub fn resolve_output_dir(&self, output_dir: Option<PathBuf>) -> Option<PathBuf> {
+        output_dir.or_else(|| self.default_settings.output_dir.clone())
+    }
+
+    /// Resolves whether existing outputs may be replaced
+    /// `--overwrite` is a plain flag, so it can only turn the config default on
+    pub fn resolve_overwrite(&self, overwrite: bool) -> bool {
+        overwrite || self.default_settings.overwrite
+    }
+
     /// Gets an image preset configuration by name
     /// Returns None if the preset doesn't exist
     pub fn get_image_preset(&self, name: &str) -> Option<&ImagePresetConfig> {
         self.image_presets.get(name)
     }
 
+    /// Checks whether a preset name belongs to the built-in defaults of its kind
+    /// Used to distinguish built-in presets from user-added ones
+    pub fn is_builtin_preset(kind: PresetKind, name: &str) -> bool {
+        let defaults = Self::default();
+        match kind {
+            PresetKind::Video => defaults.video_presets.contains_key(name),
+            PresetKind::Image => defaults.image_presets.contains_key(name),
+        }
+    }
+
+    /// Reports whether a loaded preset is built-in, a changed built-in or user-added
+    pub fn preset_origin(&self, kind: PresetKind, name: &str) -> PresetOrigin {
+        let defaults = Self::default();
+        let unchanged = match kind {
+            PresetKind::Video => defaults
+                .video_presets
+                .get(name)
+                .map(|preset| self.video_presets.get(name) == Some(preset)),
+            PresetKind::Image => defaults
+                .image_presets
+                .get(name)
+                .map(|preset| self.image_presets.get(name) == Some(preset)),
+        };
+
+        match unchanged {
+            Some(true) => PresetOrigin::Builtin,
+            Some(false) => PresetOrigin::Overridden,
+            None => PresetOrigin::User,
+        }
+    }
+
+    /// Gets the configured default quality for an image output format
+    /// JPEG accepts both "jpeg" and "jpg" keys
+    pub fn format_quality_default(&self, format: &ImageFormat) -> Option<u8> {
+        match format {
+            ImageFormat::Jpeg => self
+                .format_quality_defaults
+                .get("jpeg")
+                .or_else(|| self.format_quality_defaults.get("jpg"))
+                .copied(),
+            other => self
+                .format_quality_defaults
+                .get(&other.to_string())
+                .copied(),
+        }
+    }
+
     /// Adds a new video preset or updates an existing one
     /// This allows users to create custom video compression presets
     pub fn add_video_preset(&mut self, name: String, preset: VideoPresetConfig) {
@@ -223,6 +584,23 @@ impl Config {
         self.image_presets.insert(name, preset);
     }
 
+    /// Gets a profile by name
+    /// Returns None if the profile doesn't exist
+    pub fn get_profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.get(name)
+    }
+
+    /// Adds a new profile or updates an existing one
+    pub fn add_profile(&mut self, name: String, profile: Profile) {
+        self.profiles.insert(name, profile);
+    }
+
+    /// Removes a profile by name
+    /// Returns true if the profile existed and was removed
+    pub fn remove_profile(&mut self, name: &str) -> bool {
+        self.profiles.remove(name).is_some()
+    }
+
     /// Removes a video preset by name
     /// Returns true if the preset existed and was removed
     pub fn remove_video_preset(&mut self, name: &str) -> bool {
@@ -235,3 +613,200 @@ impl Config {
         self.image_presets.remove(name).is_some()
     }
 }
+
+/// Writes a config file, creating parent directories if they don't exist
+fn write_config_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Adds a comment line above the first occurrence of each documented key
+/// Works for both YAML (`key:`) and TOML (`key =`, `[key]`) output
+fn annotate_config(content: &str) -> String {
+    let mut documented = std::collections::HashSet::new();
+    let mut annotated = String::from("# CompressCLI configuration\n\n");
+
+    for line in content.lines() {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let key = line
+            .trim()
+            .trim_start_matches('[')
+            .split([':', ' ', '=', ']'])
+            .next()
+            .unwrap_or_default();
+
+        if let Some((key, comment)) = CONFIG_COMMENTS.iter().find(|(name, _)| *name == key)
+            && documented.insert(*key)
+        {
+            annotated.push_str(&format!("{}# {}\n", indent, comment));
+        }
+        annotated.push_str(line);
+        annotated.push('\n');
+    }
+
+    annotated
+}
+
+/// Batch retry count for config files written before the setting existed
+fn default_retry_attempts() -> usize {
+    MAX_RETRY_ATTEMPTS
+}
+
+/// Built-in per-format quality defaults
+/// AVIF and WebP reach the same perceived quality as JPEG at lower settings
+fn default_format_quality() -> HashMap<String, u8> {
+    HashMap::from([
+        ("avif".to_string(), 55),
+        ("webp".to_string(), 80),
+        ("jpeg".to_string(), DEFAULT_IMAGE_QUALITY),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_jobs_fall_back_to_config() {
+        let mut config = Config::default();
+        config.default_settings.parallel_jobs = 8;
+
+        assert_eq!(config.resolve_parallel_jobs(None), 8);
+        assert_eq!(config.resolve_parallel_jobs(Some(2)), 2);
+
+        config.default_settings.parallel_jobs = 0;
+        assert_eq!(config.resolve_parallel_jobs(None), 1);
+    }
+
+    #[test]
+    fn test_output_settings_fall_back_to_config() {
+        let mut config = Config::default();
+        assert_eq!(config.resolve_output_dir(None), None);
+        assert!(!config.resolve_overwrite(false));
+
+        config.default_settings.output_dir = Some(PathBuf::from("/srv/compressed"));
+        config.default_settings.overwrite = true;
+        assert_eq!(
+            config.resolve_output_dir(None),
+            Some(PathBuf::from("/srv/compressed"))
+        );
+        assert_eq!(
+            config.resolve_output_dir(Some(PathBuf::from("out"))),
+            Some(PathBuf::from("out"))
+        );
+        assert!(config.resolve_overwrite(false));
+    }
+
+    #[test]
+    fn test_validate_reports_out_of_range_values() {
+        assert!(Config::default().validate().is_empty());
+
+        let mut config = Config::default();
+        config.video_presets.get_mut("fast").unwrap().crf = Some(99);
+        config.image_presets.get_mut("web").unwrap().quality = 200;
+        config.add_profile(
+            "youtube".to_string(),
+            Profile {
+                resolution: Some("1080pp".to_string()),
+                audio_bitrate: Some("loud".to_string()),
+                ..Profile::default()
+            },
+        );
+
+        assert_eq!(
+            config.validate(),
+            [
+                "video preset 'fast': crf 99 is out of range (0-51)",
+                "image preset 'web': quality 200 is out of range (1-100)",
+                "profile 'youtube': resolution '1080pp' is not a resolution like 1080p or 1280x720",
+                "profile 'youtube': audio_bitrate 'loud' is not a bitrate like 128k",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preset_origin_checks_kind_and_settings() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.preset_origin(PresetKind::Video, "medium"),
+            PresetOrigin::Builtin
+        );
+        assert_eq!(
+            config.preset_origin(PresetKind::Image, "web"),
+            PresetOrigin::Builtin
+        );
+
+        // A changed built-in is no longer reported as built-in
+        config.image_presets.get_mut("web").unwrap().quality = 60;
+        assert_eq!(
+            config.preset_origin(PresetKind::Image, "web"),
+            PresetOrigin::Overridden
+        );
+
+        // "web" is only a built-in image preset, not a video one
+        let medium = config.video_presets["medium"].clone();
+        config.add_video_preset("web".to_string(), medium);
+        assert_eq!(
+            config.preset_origin(PresetKind::Video, "web"),
+            PresetOrigin::User
+        );
+        assert!(!Config::is_builtin_preset(PresetKind::Video, "web"));
+        assert!(Config::is_builtin_preset(PresetKind::Image, "web"));
+    }
+
+    #[test]
+    fn test_profiles_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.add_profile(
+            "youtube".to_string(),
+            Profile {
+                codec: Some(VideoCodec::H264),
+                resolution: Some("1080p".to_string()),
+                audio_bitrate: Some("192k".to_string()),
+                ..Profile::default()
+            },
+        );
+
+        for name in ["config.yaml", "config.toml"] {
+            let path = dir.path().join(name);
+            config.save_commented(&path).unwrap();
+
+            let loaded = Config::load_from_file(&path).unwrap();
+            let profile = loaded.get_profile("youtube").unwrap();
+            assert!(matches!(profile.codec, Some(VideoCodec::H264)));
+            assert_eq!(profile.resolution.as_deref(), Some("1080p"));
+            assert_eq!(profile.output_dir, None);
+            assert!(loaded.get_profile("archive").is_none());
+        }
+
+        assert!(config.remove_profile("youtube"));
+        assert!(!config.remove_profile("youtube"));
+    }
+
+    #[test]
+    fn test_commented_config_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::default();
+
+        for name in ["config.yaml", "config.toml"] {
+            let path = dir.path().join(name);
+            config.save_commented(&path).unwrap();
+
+            let content = fs::read_to_string(&path).unwrap();
+            assert!(content.contains("# Constant Rate Factor"));
+            assert!(content.contains("# Copy each source to <name>.bak"));
+
+            let loaded = Config::load_from_file(&path).unwrap();
+            assert_eq!(loaded.video_presets.len(), config.video_presets.len());
+            assert_eq!(loaded.image_presets["web"].quality, DEFAULT_IMAGE_QUALITY);
+            assert_eq!(
+                loaded.default_settings.retry_attempts,
+                config.default_settings.retry_attempts
+            );
+        }
+    }
+}
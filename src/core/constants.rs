@@ -6,6 +6,10 @@
 /// Default image quality when no preset is specified
 pub const DEFAULT_IMAGE_QUALITY: u8 = 85;
 
+/// AVIF encoder speed (1 = slowest/smallest, 10 = fastest)
+#[cfg(feature = "avif")]
+pub const DEFAULT_AVIF_SPEED: u8 = 6;
+
 /// Default number of parallel jobs for batch processing
 #[allow(dead_code)]
 pub const DEFAULT_PARALLEL_JOBS: usize = 4;
@@ -13,15 +17,19 @@ pub const DEFAULT_PARALLEL_JOBS: usize = 4;
 /// Progress bar update interval in milliseconds
 pub const PROGRESS_UPDATE_INTERVAL_MS: u64 = 100;
 
-/// Maximum number of retry attempts for failed operations
-#[allow(dead_code)]
+/// How long a watched file must go without new events before it's compressed
+pub const WATCH_DEBOUNCE_MS: u64 = 1000;
+
+/// Default number of retries for a batch file that fails with a transient error
 pub const MAX_RETRY_ATTEMPTS: usize = 3;
 
+/// Pause before the first retry in milliseconds; each further retry waits one step longer
+pub const RETRY_BACKOFF_MS: u64 = 500;
+
 /// Default video file extension for output
 pub const DEFAULT_VIDEO_EXTENSION: &str = "mp4";
 
 /// Default image file extension for output
-#[allow(dead_code)]
 pub const DEFAULT_IMAGE_EXTENSION: &str = "jpg";
 
 /// Supported video file extensions (lowercase)
@@ -30,19 +38,46 @@ pub const VIDEO_EXTENSIONS: &[&str] = &[
 ];
 
 /// Supported image file extensions (lowercase)
-pub const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "bmp", "tiff", "tga", "gif"];
+pub const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "webp", "bmp", "tiff", "tga", "gif", "heic", "heif",
+];
+
+/// HEIC/HEIF images, which the image crate can't decode and are read through FFmpeg
+pub const HEIF_EXTENSIONS: &[&str] = &["heic", "heif"];
 
 /// FFmpeg progress parsing patterns
 pub const FFMPEG_PROGRESS_TIME_PATTERN: &str = "out_time_ms=";
 #[allow(dead_code)]
 pub const FFMPEG_PROGRESS_FRAME_PATTERN: &str = "frame=";
 
+/// Candidate font files used for text overlays when no font is specified
+#[cfg(target_os = "macos")]
+pub const DEFAULT_FONT_PATHS: &[&str] = &[
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+];
+#[cfg(windows)]
+pub const DEFAULT_FONT_PATHS: &[&str] =
+    &["C:/Windows/Fonts/arial.ttf", "C:/Windows/Fonts/segoeui.ttf"];
+#[cfg(all(unix, not(target_os = "macos")))]
+pub const DEFAULT_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+];
+
 /// Cross-platform null device paths
 #[cfg(unix)]
 pub const NULL_DEVICE: &str = "/dev/null";
 #[cfg(windows)]
 pub const NULL_DEVICE: &str = "NUL";
 
+/// DRM render node used for VA-API encoding
+pub const VAAPI_DEVICE: &str = "/dev/dri/renderD128";
+
 /// Default CRF values for different quality presets
 pub const CRF_ULTRAFAST: u8 = 28;
 pub const CRF_FAST: u8 = 25;
@@ -54,3 +89,18 @@ pub const CRF_VERYSLOW: u8 = 18;
 pub const AUDIO_BITRATE_LOW: &str = "128k";
 pub const AUDIO_BITRATE_MEDIUM: &str = "192k";
 pub const AUDIO_BITRATE_HIGH: &str = "256k";
+
+/// Fraction of a video --target-size reserved for container overhead
+pub const TARGET_SIZE_MUX_OVERHEAD: f64 = 0.02;
+
+/// Lowest video bitrate (bits/s) a video --target-size may resolve to
+pub const MIN_TARGET_VIDEO_BITRATE: u64 = 50_000;
+
+/// Oldest FFmpeg (major, minor) that can decode HEIC/HEIF images
+pub const MIN_FFMPEG_HEIF_VERSION: (u32, u32) = (7, 1);
+
+/// Environment variable naming the FFmpeg binary; overrides config ffmpeg_path
+pub const FFMPEG_PATH_ENV: &str = "COMPRESSCLI_FFMPEG";
+
+/// Environment variable naming the FFprobe binary; overrides config ffprobe_path
+pub const FFPROBE_PATH_ENV: &str = "COMPRESSCLI_FFPROBE";
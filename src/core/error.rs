@@ -41,6 +41,9 @@ pub enum CompressError {
     #[error("TOML serialization error: {0}")]
     TomlSer(#[from] toml::ser::Error),
 
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
     #[error("Directory traversal error: {0}")]
     WalkDir(#[from] walkdir::Error),
 
@@ -57,13 +60,45 @@ pub enum CompressError {
     ProgressError { message: String },
 
     #[error("Codec compatibility error: {message}")]
-    #[allow(dead_code)]
     CodecError { message: String },
 }
 
 pub type Result<T> = std::result::Result<T, CompressError>;
 
+/// Checks for Windows sharing and lock violations, which std reports as uncategorized
+#[cfg(windows)]
+fn is_lock_violation(e: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+    matches!(e.raw_os_error(), Some(32) | Some(33))
+}
+
+#[cfg(not(windows))]
+fn is_lock_violation(_e: &std::io::Error) -> bool {
+    false
+}
+
 impl CompressError {
+    /// Checks whether retrying the same operation might succeed
+    /// Only I/O errors that clear up on their own count: interrupted or timed-out
+    /// calls and files locked or busy in another process. Failed hooks, encoders
+    /// and bad input fail the same way every time
+    pub fn is_transient(&self) -> bool {
+        use std::io::ErrorKind;
+
+        match self {
+            Self::Io(e) => {
+                matches!(
+                    e.kind(),
+                    ErrorKind::WouldBlock
+                        | ErrorKind::Interrupted
+                        | ErrorKind::TimedOut
+                        | ErrorKind::ResourceBusy
+                ) || is_lock_violation(e)
+            }
+            _ => false,
+        }
+    }
+
     /// Creates an error for invalid input file paths
     /// This is used when a file doesn't exist or isn't accessible
     pub fn invalid_input<P: AsRef<Path>>(path: P) -> Self {
@@ -138,7 +173,6 @@ impl CompressError {
 
     /// Creates an error for codec compatibility issues
     /// Used when codec/format combinations are not supported
-    #[allow(dead_code)]
     pub fn codec_error<S: Into<String>>(message: S) -> Self {
         Self::CodecError {
             message: message.into(),
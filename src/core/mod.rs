@@ -1,12 +1,14 @@
 //! Core functionality for CompressCLI
 //!
 //! This module contains the fundamental components that are used throughout
-//! the application, including error handling and configuration management.
+//! the application, including error handling, configuration management and result reporting.
 
 pub mod config;
 pub mod constants;
 pub mod error;
+pub mod report;
 
-pub use config::{Config, ImagePresetConfig, VideoPresetConfig};
+pub use config::{Config, ImagePresetConfig, PresetKind, PresetOrigin, Profile, VideoPresetConfig};
 pub use constants::*;
 pub use error::{CompressError, Result};
+pub use report::{BatchReport, CompressionReport, ReportFormat};
@@ -0,0 +1,215 @@
+//! Machine-readable compression results for `--json` and batch `--report` files
+
+use crate::core::error::{CompressError, Result};
+use crate::utils::{
+    calculate_compression_ratio, ensure_parent_dir, get_file_size, is_special_file,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Result of compressing one file
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressionReport {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub original_size: u64,
+    /// None for dry runs and streamed outputs, which have no size to report
+    pub compressed_size: Option<u64>,
+    /// Size reduction as a percentage of the original
+    pub ratio: Option<f64>,
+}
+
+impl CompressionReport {
+    /// Builds a report from the input and the output it was compressed to
+    /// Dry runs only report the input, since the output isn't written
+    pub fn new(input: &Path, output: &Path, dry_run: bool) -> Result<Self> {
+        let original_size = get_file_size(input)?.as_u64();
+        let compressed_size = if dry_run || is_special_file(output) {
+            None
+        } else {
+            Some(get_file_size(output)?.as_u64())
+        };
+
+        Ok(Self {
+            input: input.to_path_buf(),
+            output: output.to_path_buf(),
+            original_size,
+            compressed_size,
+            ratio: compressed_size.map(|size| calculate_compression_ratio(original_size, size)),
+        })
+    }
+}
+
+/// Results of a batch run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchReport {
+    pub files: Vec<CompressionReport>,
+    pub failed: Vec<PathBuf>,
+    pub skipped_fresh: usize,
+    pub skipped_existing: usize,
+}
+
+impl BatchReport {
+    /// Flattens the results into one row per file, failures included
+    pub fn rows(&self, dry_run: bool) -> Vec<ReportRow> {
+        let status = if dry_run {
+            FileStatus::Planned
+        } else {
+            FileStatus::Compressed
+        };
+
+        let processed = self.files.iter().map(|file| ReportRow {
+            input: file.input.clone(),
+            output: Some(file.output.clone()),
+            original_size: Some(file.original_size),
+            compressed_size: file.compressed_size,
+            ratio: file.ratio,
+            status,
+        });
+        let failed = self.failed.iter().map(|input| ReportRow {
+            input: input.clone(),
+            output: None,
+            original_size: get_file_size(input).ok().map(|size| size.as_u64()),
+            compressed_size: None,
+            ratio: None,
+            status: FileStatus::Failed,
+        });
+
+        processed.chain(failed).collect()
+    }
+
+    /// Writes the per-file rows to `path` as JSON or CSV
+    pub fn write_to(&self, path: &Path, format: ReportFormat, dry_run: bool) -> Result<()> {
+        ensure_parent_dir(path)?;
+        let rows = self.rows(dry_run);
+
+        match format {
+            ReportFormat::Json => {
+                std::fs::write(path, serde_json::to_string_pretty(&rows)?)?;
+            }
+            ReportFormat::Csv => {
+                let mut writer = csv::Writer::from_path(path)?;
+                for row in &rows {
+                    writer.serialize(row)?;
+                }
+                writer.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// What happened to a file in a batch run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Compressed,
+    /// Dry runs only plan the output
+    Planned,
+    Failed,
+}
+
+/// One file in a batch report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRow {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub original_size: Option<u64>,
+    pub compressed_size: Option<u64>,
+    pub ratio: Option<f64>,
+    pub status: FileStatus,
+}
+
+/// File format of a batch report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    /// Picks the format from the report file's extension
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("json") => Ok(Self::Json),
+            Some("csv") => Ok(Self::Csv),
+            _ => Err(CompressError::invalid_parameter(
+                "report",
+                format!("{} (expected a .json or .csv file)", path.display()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_report_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("clip.mp4");
+        std::fs::write(&input, vec![0u8; 2048]).unwrap();
+
+        let report =
+            CompressionReport::new(&input, &dir.path().join("clip_compressed.mp4"), true).unwrap();
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+
+        for key in [
+            "input",
+            "output",
+            "original_size",
+            "compressed_size",
+            "ratio",
+        ] {
+            assert!(json.get(key).is_some(), "missing key {}", key);
+        }
+        assert_eq!(json["original_size"], 2048);
+        assert!(json["compressed_size"].is_null());
+    }
+
+    #[test]
+    fn test_batch_report_has_row_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = BatchReport::default();
+        for name in ["a.jpg", "b.jpg"] {
+            let input = dir.path().join(name);
+            std::fs::write(&input, b"data").unwrap();
+            report.files.push(
+                CompressionReport::new(&input, &input.with_extension("out.jpg"), true).unwrap(),
+            );
+        }
+        report.failed.push(dir.path().join("c.jpg"));
+
+        let csv_path = dir.path().join("report.csv");
+        report
+            .write_to(&csv_path, ReportFormat::from_path(&csv_path).unwrap(), true)
+            .unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "input,output,original_size,compressed_size,ratio,status"
+        );
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].ends_with(",4,,,planned"));
+        assert!(lines[3].ends_with(",,,,,failed"));
+
+        let json_path = dir.path().join("report.json");
+        report
+            .write_to(&json_path, ReportFormat::Json, true)
+            .unwrap();
+        let rows: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2]["status"], "failed");
+
+        assert!(ReportFormat::from_path(Path::new("report.txt")).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! CompressCLI - A powerful CLI tool for video and image compression
+//!
+//! The compressors behind the `compresscli` binary, for use from other Rust tools.
+//! Video work needs FFmpeg on the PATH; images are handled in-process.
+//!
+//! ```
+//! use compresscli::{Config, ImageCompressionOptions, ImageCompressor};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> compresscli::Result<()> {
+//! # let dir = tempfile::tempdir()?;
+//! # let photo = dir.path().join("photo.png");
+//! # image::RgbImage::new(16, 16).save(&photo)?;
+//! // A dry run reports where the output would go without writing it
+//! let compressor = ImageCompressor::new(Config::default(), true, false);
+//! let mut options = ImageCompressionOptions::new(&photo);
+//! options.quality = Some(80);
+//!
+//! let output = compressor.compress(options).await?;
+//! assert_eq!(output, dir.path().join("photo_compressed.png"));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cli;
+pub mod compression;
+pub mod core;
+pub mod ui;
+pub mod utils;
+
+pub use compression::{
+    AudioExtractor, BatchOptions, BatchProcessor, DirectoryWatcher, ExtractAudioOptions,
+    ImageCompressionOptions, ImageCompressor, VideoCompressionOptions, VideoCompressor,
+};
+pub use core::{CompressError, CompressionReport, Config, Result};
@@ -1,18 +1,13 @@
 //! CompressCLI - A powerful CLI tool for video and image compression
 //!
 //! This is the main entry point for the CompressCLI application.
-//! It initializes logging, parses CLI arguments, and delegates to the CLI handler.
-
-mod cli;
-mod compression;
-mod core;
-mod ui;
-mod utils;
+//! It initializes logging, parses CLI arguments, and delegates to the CLI handler
+//! in the library crate.
 
 use clap::Parser;
-use cli::{Cli, run_cli};
+use compresscli::cli::{Cli, run_cli};
+use compresscli::ui::progress::print_error;
 use std::process;
-use ui::progress::print_error;
 
 /// Main entry point for the CompressCLI application
 /// Initializes logging, parses CLI arguments, and runs the main logic
@@ -30,7 +25,7 @@ async fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::Config;
+    use compresscli::Config;
 
     #[tokio::test]
     async fn test_config_loading() {
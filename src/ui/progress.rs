@@ -1,4 +1,5 @@
-use console::style;
+use crate::utils::{ascii_output, quiet_output, set_plain_output};
+use console::{Style, Term, style};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::time::Duration;
 
@@ -31,36 +32,148 @@ pub fn create_compression_progress_bar() -> ProgressBar {
     pb
 }
 
+/// Decides once whether output gets colors and progress bars
+/// Both are dropped when stdout isn't a terminal (pipes, CI logs) or with `--no-color`;
+/// otherwise console's own NO_COLOR/CLICOLOR handling still applies
+pub fn configure_terminal_output(no_color: bool) {
+    if wants_plain_output(no_color, Term::stdout().is_term()) {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+        set_plain_output(true);
+    }
+}
+
+/// Checks whether colors and progress bars should be dropped
+fn wants_plain_output(no_color: bool, is_term: bool) -> bool {
+    no_color || !is_term
+}
+
+/// Picks the Unicode symbol or its ASCII fallback for the configured output style
+fn symbol(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if ascii_output() { ascii } else { unicode }
+}
+
+/// Builds a style that emits escape codes only when `color` is set
+fn painter(color: bool) -> Style {
+    Style::new().force_styling(color)
+}
+
 /// Prints a success message with a green checkmark
 /// Used to indicate successful completion of operations
 pub fn print_success(message: &str) {
-    println!("{} {}", style("✓").green().bold(), message);
+    if let Some(line) = success_line(message, quiet_output(), console::colors_enabled()) {
+        println!("{}", line);
+    }
+}
+
+/// Formats a success message, or None when quiet mode suppresses it
+fn success_line(message: &str, quiet: bool, color: bool) -> Option<String> {
+    (!quiet).then(|| {
+        format!(
+            "{} {}",
+            painter(color).green().bold().apply_to(symbol("✓", "+")),
+            message
+        )
+    })
 }
 
 /// Prints an error message with a red X mark to stderr
 /// Used for error reporting and failure notifications
 pub fn print_error(message: &str) {
-    eprintln!("{} {}", style("✗").red().bold(), message);
+    eprintln!("{} {}", style(symbol("✗", "x")).red().bold(), message);
+}
+
+/// Prints a warning message with a yellow warning sign to stderr
+/// Used for non-fatal issues the user should be aware of
+pub fn print_warning(message: &str) {
+    if quiet_output() {
+        return;
+    }
+    eprintln!("{} {}", style(symbol("⚠", "!")).yellow().bold(), message);
 }
 
 /// Prints an informational message with a blue info icon
 /// Used for general status updates and information
 pub fn print_info(message: &str) {
-    println!("{} {}", style("ℹ").blue().bold(), message);
+    if quiet_output() {
+        return;
+    }
+    println!("{} {}", style(symbol("ℹ", "i")).blue().bold(), message);
 }
 
 /// Prints a formatted header with underline
 /// Used for section titles and major operation headers
 pub fn print_header(message: &str) {
-    println!(
+    if quiet_output() {
+        return;
+    }
+    println!("{}", header_text(message, console::colors_enabled()));
+}
+
+/// Formats a header title and its underline
+fn header_text(message: &str, color: bool) -> String {
+    format!(
         "\n{}\n{}",
-        style(message).bold().underlined(),
-        style("─".repeat(message.len())).dim()
-    );
+        painter(color).bold().underlined().apply_to(message),
+        painter(color)
+            .dim()
+            .apply_to(symbol("─", "-").repeat(message.len()))
+    )
 }
 
 /// Prints a horizontal separator line
 /// Used to visually separate different sections of output
 pub fn print_separator() {
-    println!("{}", style("─".repeat(50)).dim());
+    if quiet_output() {
+        return;
+    }
+    println!("{}", style(symbol("─", "-").repeat(50)).dim());
+}
+
+/// Asks the user a yes/no question on the terminal
+/// Returns None when no interactive terminal is available to answer
+pub fn confirm(prompt: &str) -> Option<bool> {
+    let term = console::Term::stderr();
+    if !term.is_term() {
+        return None;
+    }
+
+    term.write_str(&format!("{} {} ", style("?").yellow().bold(), prompt))
+        .ok()?;
+    let answer = term.read_line().ok()?;
+    Some(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_print_success_is_silent_when_quiet() {
+        assert_eq!(success_line("done", true, false), None);
+        assert!(
+            success_line("done", false, false)
+                .unwrap()
+                .ends_with("done")
+        );
+    }
+
+    #[test]
+    fn test_styles_are_plain_without_color() {
+        assert!(wants_plain_output(true, true));
+        assert!(wants_plain_output(false, false));
+        assert!(!wants_plain_output(false, true));
+
+        // The color flag is passed in, so the process-wide color settings don't matter
+        let header = header_text("Done", false);
+        assert!(!header.contains("\x1b["));
+        assert_eq!(header, format!("\nDone\n{}", symbol("─", "-").repeat(4)));
+        assert!(header_text("Done", true).contains("\x1b["));
+
+        assert!(
+            !success_line("done", false, false)
+                .unwrap()
+                .contains("\x1b[")
+        );
+    }
 }
@@ -1,44 +1,225 @@
 //! Command building utilities for FFmpeg and other external tools
 
-use crate::cli::args::{AudioCodec, VideoCodec};
-use crate::core::{CompressError, NULL_DEVICE, Result};
-use crate::utils::{parse_resolution, parse_time, quote_path, validate_safe_path};
+use crate::cli::args::{AudioCodec, FieldOrder, HwAccel, Rotation, Tune, VideoCodec};
+use crate::core::{CompressError, NULL_DEVICE, Result, VAAPI_DEVICE};
+use crate::utils::{
+    Resolution, ffmpeg_binary, ffprobe_binary, parse_time, quote_path, split_bitrate,
+    validate_safe_path,
+};
+use log::warn;
 use std::path::Path;
 use std::process::{Command, Stdio};
 
 /// Builder for constructing FFmpeg commands with proper error handling and validation
+/// Filters and the output path are held back until `build`, so every filter lands in
+/// a single -vf (or -filter_complex) or -af and every option precedes the output it applies to
 pub struct FFmpegCommandBuilder {
     command: Command,
+    video_filters: Vec<String>,
+    audio_filters: Vec<String>,
+    inputs: usize,
+    maps_all_streams: bool,
+    overlay_input: Option<usize>,
+    overlay: Option<Overlay>,
+    output: Option<String>,
+}
+
+/// An image input composited onto the video partway through the filter chain
+struct Overlay {
+    /// Index of the image among the command's inputs
+    input: usize,
+    /// Filters applied to the image before it's overlaid
+    image_filters: String,
+    /// Overlay x:y expression
+    position: String,
+    /// Number of video filters that run before the overlay
+    after_filters: usize,
+}
+
+impl Overlay {
+    /// Builds the filtergraph: video filters up to the overlay on `[0:v]`, the image
+    /// chain on its own input, then the overlay and the remaining video filters
+    fn filter_graph(&self, video_filters: &[String]) -> String {
+        let (before, after) = video_filters.split_at(self.after_filters);
+        let before = if before.is_empty() {
+            "null".to_string()
+        } else {
+            before.join(",")
+        };
+
+        let mut graph = format!(
+            "[0:v]{}[base];[{}:v]{}[overlay];[base][overlay]overlay={}",
+            before, self.input, self.image_filters, self.position
+        );
+        for filter in after {
+            graph.push(',');
+            graph.push_str(filter);
+        }
+        graph.push_str("[video]");
+        graph
+    }
 }
 
 impl FFmpegCommandBuilder {
-    /// Creates a new FFmpeg command builder
+    /// Creates a new FFmpeg command builder for the configured FFmpeg binary
     pub fn new() -> Self {
-        let mut command = Command::new("ffmpeg");
+        Self::with_binary(ffmpeg_binary())
+    }
+
+    /// Creates a new FFmpeg command builder that runs a specific FFmpeg binary
+    pub fn with_binary<P: AsRef<Path>>(binary: P) -> Self {
+        let mut command = Command::new(binary.as_ref());
         command.stdout(Stdio::piped()).stderr(Stdio::piped());
-        Self { command }
+        Self {
+            command,
+            video_filters: Vec::new(),
+            audio_filters: Vec::new(),
+            inputs: 0,
+            maps_all_streams: false,
+            overlay_input: None,
+            overlay: None,
+            output: None,
+        }
     }
 
     /// Adds input file with path validation and quoting
     pub fn input<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
         validate_safe_path(&path)?;
         self.command.arg("-i").arg(quote_path(path));
+        self.inputs += 1;
         Ok(self)
     }
 
+    /// Sets the display rotation of the next input without touching its pixels
+    /// Must be called before `input`; requires FFmpeg 6.0 or newer. When re-encoding,
+    /// autorotation is disabled so the rotation stays metadata-only
+    pub fn display_rotation(mut self, rotation: Rotation, reencode: bool) -> Self {
+        if reencode {
+            self.command.arg("-autorotate").arg("0");
+        }
+        // FFmpeg takes counter-clockwise degrees
+        self.command
+            .arg("-display_rotation")
+            .arg((-rotation.degrees()).to_string());
+        self
+    }
+
+    /// Decodes the next input on the GPU for a hardware encoder
+    /// Must be called before `input`; VA-API also needs its render device opened
+    pub fn hwaccel(mut self, accel: HwAccel) -> Self {
+        if let Some(decoder) = accel.decoder() {
+            self.command.arg("-hwaccel").arg(decoder);
+        }
+        if accel == HwAccel::Vaapi {
+            self.command.arg("-vaapi_device").arg(VAAPI_DEVICE);
+        }
+        self
+    }
+
+    /// Forces the demuxer used for the next input
+    /// Must be called before `input`; needed for raw streams and extensionless files
+    pub fn input_format(mut self, format: &str) -> Self {
+        self.command.arg("-f").arg(format);
+        self
+    }
+
+    /// Adds a reference input whose metadata and chapters replace the source's
+    /// Must directly follow `input`; maps every video, audio and subtitle stream from
+    /// input 0 (the source) and global metadata plus chapters from input 1 (the reference)
+    pub fn metadata_source<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        validate_safe_path(&path)?;
+        self.command
+            .arg("-i")
+            .arg(quote_path(path))
+            .arg("-map_metadata")
+            .arg("1")
+            .arg("-map_chapters")
+            .arg("1");
+        self.inputs += 1;
+        self.maps_all_streams = true;
+        Ok(self)
+    }
+
+    /// Adds an image input for `overlay`
+    /// Must follow `input` and `metadata_source`, since options after it apply to the output
+    pub fn overlay_input<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        validate_safe_path(&path)?;
+        self.command.arg("-i").arg(quote_path(path));
+        self.overlay_input = Some(self.inputs);
+        self.inputs += 1;
+        Ok(self)
+    }
+
+    /// Overlays the `overlay_input` image at `position` after the video filters added so far
+    /// The image goes through `image_filters` first; later video filters apply to the
+    /// composited frame. The video filters are then emitted as a -filter_complex
+    pub fn overlay(mut self, image_filters: &str, position: &str) -> Result<Self> {
+        let input = self.overlay_input.ok_or_else(|| {
+            CompressError::invalid_parameter("overlay", "no image input was added to overlay")
+        })?;
+        self.overlay = Some(Overlay {
+            input,
+            image_filters: image_filters.to_string(),
+            position: position.to_string(),
+            after_filters: self.video_filters.len(),
+        });
+        Ok(self)
+    }
+
+    /// Keeps or strips global metadata such as creation time and tags
+    /// MP4-family outputs need `use_metadata_tags` to keep tags they don't define
+    pub fn map_metadata(mut self, preserve: bool, mp4_tags: bool) -> Self {
+        if preserve {
+            self.command.arg("-map_metadata").arg("0");
+            if mp4_tags {
+                self.command.arg("-movflags").arg("use_metadata_tags");
+            }
+        } else {
+            self.command.arg("-map_metadata").arg("-1");
+        }
+        self
+    }
+
     /// Adds output file with path validation and quoting
     pub fn output<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
         validate_safe_path(&path)?;
-        self.command.arg(quote_path(path));
+        self.output = Some(quote_path(path));
         Ok(self)
     }
 
+    /// Sets a streamable output format for writing to pipes and FIFOs
+    /// MP4-family containers are fragmented since pipes can't be seeked
+    pub fn stream_output(mut self, container: Option<&str>) -> Self {
+        match container {
+            Some("mp4" | "m4v" | "mov") => {
+                self.command
+                    .arg("-f")
+                    .arg("mp4")
+                    .arg("-movflags")
+                    .arg("frag_keyframe+empty_moov");
+            }
+            Some("webm") => {
+                self.command.arg("-f").arg("webm");
+            }
+            _ => {
+                self.command.arg("-f").arg("matroska");
+            }
+        }
+        self
+    }
+
     /// Sets video codec
     pub fn video_codec(mut self, codec: VideoCodec) -> Self {
         self.command.arg("-c:v").arg(codec.to_string());
         self
     }
 
+    /// Sets the video encoder by FFmpeg name (e.g. a hardware encoder like h264_nvenc)
+    pub fn video_encoder(mut self, encoder: &str) -> Self {
+        self.command.arg("-c:v").arg(encoder);
+        self
+    }
+
     /// Sets audio codec
     pub fn audio_codec(mut self, codec: AudioCodec) -> Self {
         self.command.arg("-c:a").arg(codec.to_string());
@@ -54,21 +235,32 @@ impl FFmpegCommandBuilder {
         Ok(self)
     }
 
+    /// Sets constant quality for a hardware encoder, on the same 0-51 scale as CRF
+    /// Each backend names its quality option differently
+    pub fn hw_quality(mut self, accel: HwAccel, quality: u8) -> Result<Self> {
+        if quality > 51 {
+            return Err(CompressError::invalid_parameter("crf", quality.to_string()));
+        }
+        let value = quality.to_string();
+        match accel {
+            HwAccel::None => return self.crf(quality),
+            HwAccel::Nvenc => self.command.args(["-rc", "vbr", "-cq", &value]),
+            HwAccel::Qsv => self.command.args(["-global_quality", &value]),
+            HwAccel::Vaapi => self.command.args(["-rc_mode", "CQP", "-qp", &value]),
+        };
+        Ok(self)
+    }
+
     /// Sets target bitrate
     pub fn bitrate(mut self, bitrate: &str) -> Result<Self> {
-        // Basic validation of bitrate format
-        if !bitrate.chars().any(|c| c.is_ascii_digit()) {
-            return Err(CompressError::invalid_parameter("bitrate", bitrate));
-        }
+        let bitrate = normalize_bitrate("bitrate", bitrate)?;
         self.command.arg("-b:v").arg(bitrate);
         Ok(self)
     }
 
     /// Sets audio bitrate
     pub fn audio_bitrate(mut self, bitrate: &str) -> Result<Self> {
-        if !bitrate.chars().any(|c| c.is_ascii_digit()) {
-            return Err(CompressError::invalid_parameter("audio_bitrate", bitrate));
-        }
+        let bitrate = normalize_bitrate("audio_bitrate", bitrate)?;
         self.command.arg("-b:a").arg(bitrate);
         Ok(self)
     }
@@ -81,24 +273,61 @@ impl FFmpegCommandBuilder {
         self
     }
 
-    /// Sets resolution with validation
-    pub fn resolution(mut self, resolution: &str) -> Result<Self> {
-        let (width, height) = parse_resolution(resolution)?;
-        self.command
-            .arg("-vf")
-            .arg(format!("scale={}:{}", width, height));
-        Ok(self)
+    /// Tags the encoded stream with a field order
+    /// Pair with a setfield filter so the frames themselves carry the same flags
+    pub fn field_order(mut self, order: FieldOrder) -> Self {
+        self.command.arg("-field_order").arg(order.to_string());
+        self
+    }
+
+    /// Appends a filter to the video filter chain
+    /// FFmpeg only honors the last -vf, so filters are comma-joined into one at build time
+    pub fn video_filter(mut self, filter: &str) -> Self {
+        self.video_filters.push(filter.to_string());
+        self
+    }
+
+    /// Appends a filter to the audio filter chain
+    pub fn audio_filter(mut self, filter: &str) -> Self {
+        self.audio_filters.push(filter.to_string());
+        self
+    }
+
+    /// Limits the number of video frames written to the output
+    pub fn frames(mut self, count: u32) -> Self {
+        self.command.arg("-frames:v").arg(count.to_string());
+        self
     }
 
-    /// Sets frame rate
+    /// Sets frame rate with the fps filter, dropping or duplicating frames
+    /// Running it in the filter chain lets later filters skip frames that get dropped
     pub fn framerate(mut self, fps: f32) -> Result<Self> {
         if fps <= 0.0 || fps > 120.0 {
             return Err(CompressError::invalid_parameter("fps", fps.to_string()));
         }
-        self.command.arg("-r").arg(fps.to_string());
+        self.video_filters.push(format!("fps={}", fps));
         Ok(self)
     }
 
+    /// Rotates the pixels clockwise with transpose (or flips for 180 degrees)
+    pub fn rotate(mut self, rotation: Rotation) -> Self {
+        self.video_filters
+            .push(rotation.transpose_filter().to_string());
+        self
+    }
+
+    /// Scales the video; an unset axis keeps the aspect ratio
+    pub fn scale(mut self, resolution: Resolution) -> Self {
+        self.video_filters.push(resolution.scale_filter());
+        self
+    }
+
+    /// Forces constant frame rate output by duplicating/dropping frames
+    pub fn constant_frame_rate(mut self) -> Self {
+        self.command.arg("-vsync").arg("cfr");
+        self
+    }
+
     /// Sets start time for trimming
     pub fn start_time(mut self, time: &str) -> Result<Self> {
         let seconds = parse_time(time)?;
@@ -106,19 +335,111 @@ impl FFmpegCommandBuilder {
         Ok(self)
     }
 
-    /// Sets duration for trimming
-    pub fn duration(mut self, duration: &str) -> Result<Self> {
-        let seconds = parse_time(duration)?;
+    /// Sets the output duration in seconds for trimming
+    pub fn duration(mut self, seconds: f64) -> Result<Self> {
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Err(CompressError::invalid_parameter(
+                "duration",
+                format!("Duration must be greater than 0, got {}", seconds),
+            ));
+        }
         self.command.arg("-t").arg(seconds.to_string());
         Ok(self)
     }
 
+    /// Sets the output pixel format (e.g. yuv420p for 8-bit 4:2:0)
+    pub fn pixel_format(mut self, fmt: &str) -> Self {
+        self.command.arg("-pix_fmt").arg(fmt);
+        self
+    }
+
+    /// Sets the maximum number of frames between keyframes (GOP size)
+    pub fn keyframe_interval(mut self, frames: u32) -> Self {
+        self.command.arg("-g").arg(frames.to_string());
+        self
+    }
+
+    /// Sets the number of encoder threads; 0 leaves the choice to FFmpeg
+    pub fn threads(mut self, threads: usize) -> Self {
+        if threads > 0 {
+            self.command.arg("-threads").arg(threads.to_string());
+        }
+        self
+    }
+
+    /// Deinterlaces the video with yadif, one output frame per input frame
+    pub fn deinterlace(self) -> Self {
+        self.video_filter("yadif")
+    }
+
+    /// Sets the encoder tune for libx264 and libx265
+    /// Other codecs have no -tune and are left unchanged
+    pub fn tune(mut self, codec: &VideoCodec, tune: Tune) -> Self {
+        if matches!(codec, VideoCodec::H264 | VideoCodec::H265) {
+            self.command.arg("-tune").arg(tune.to_string());
+        }
+        self
+    }
+
+    /// Sets the scene-change threshold used to insert keyframes at hard cuts
+    /// Maps to -sc_threshold for libx264 and scenecut for libx265; 0 disables
+    /// scene detection. Other codecs have no equivalent and are left unchanged
+    pub fn scene_cut(mut self, codec: &VideoCodec, threshold: u8) -> Self {
+        match codec {
+            VideoCodec::H264 => {
+                self.command.arg("-sc_threshold").arg(threshold.to_string());
+            }
+            VideoCodec::H265 => {
+                self.command
+                    .arg("-x265-params")
+                    .arg(format!("scenecut={}", threshold));
+            }
+            VideoCodec::Vp9 | VideoCodec::Av1 | VideoCodec::Copy => {}
+        }
+        self
+    }
+
+    /// Configures the encode for byte-identical, reproducible output
+    /// Forces a single encoder thread, strips time-based metadata and enables
+    /// bitexact muxing; codecs with their own threading get it disabled as well
+    pub fn deterministic(mut self, codec: &VideoCodec) -> Self {
+        self = self.threads(1);
+        self.command
+            .arg("-map_metadata")
+            .arg("-1")
+            .arg("-fflags")
+            .arg("+bitexact")
+            .arg("-flags:v")
+            .arg("+bitexact")
+            .arg("-flags:a")
+            .arg("+bitexact");
+
+        match codec {
+            VideoCodec::H265 => {
+                self.command
+                    .arg("-x265-params")
+                    .arg("pools=none:frame-threads=1");
+            }
+            VideoCodec::Vp9 | VideoCodec::Av1 => {
+                self.command.arg("-row-mt").arg("0");
+            }
+            VideoCodec::H264 | VideoCodec::Copy => {}
+        }
+        self
+    }
+
     /// Disables audio track
     pub fn no_audio(mut self) -> Self {
         self.command.arg("-an");
         self
     }
 
+    /// Disables video output
+    pub fn no_video(mut self) -> Self {
+        self.command.arg("-vn");
+        self
+    }
+
     /// Enables progress reporting
     pub fn progress(mut self) -> Self {
         self.command.arg("-progress").arg("pipe:1");
@@ -132,13 +453,10 @@ impl FFmpegCommandBuilder {
     }
 
     /// Sets up for first pass of two-pass encoding
+    /// The analysis pass writes nothing, so it replaces the output with the null device
     pub fn first_pass(mut self) -> Self {
-        self.command
-            .arg("-pass")
-            .arg("1")
-            .arg("-f")
-            .arg("null")
-            .arg(NULL_DEVICE);
+        self.command.arg("-pass").arg("1").arg("-f").arg("null");
+        self.output = Some(NULL_DEVICE.to_string());
         self
     }
 
@@ -161,7 +479,41 @@ impl FFmpegCommandBuilder {
     }
 
     /// Builds the final command
-    pub fn build(self) -> Command {
+    /// Emits the stream maps, the accumulated video and audio filters and then the output path.
+    /// An overlay's filtergraph output replaces the source video, so audio and subtitles
+    /// are mapped from the source explicitly
+    pub fn build(mut self) -> Command {
+        if let Some(overlay) = &self.overlay {
+            self.command
+                .arg("-filter_complex")
+                .arg(overlay.filter_graph(&self.video_filters))
+                .arg("-map")
+                .arg("[video]")
+                .arg("-map")
+                .arg("0:a?");
+            if self.maps_all_streams {
+                self.command.arg("-map").arg("0:s?");
+            }
+        } else {
+            if self.maps_all_streams {
+                self.command
+                    .arg("-map")
+                    .arg("0:v")
+                    .arg("-map")
+                    .arg("0:a?")
+                    .arg("-map")
+                    .arg("0:s?");
+            }
+            if !self.video_filters.is_empty() {
+                self.command.arg("-vf").arg(self.video_filters.join(","));
+            }
+        }
+        if !self.audio_filters.is_empty() {
+            self.command.arg("-af").arg(self.audio_filters.join(","));
+        }
+        if let Some(output) = self.output {
+            self.command.arg(output);
+        }
         self.command
     }
 
@@ -172,6 +524,32 @@ impl FFmpegCommandBuilder {
     }
 }
 
+/// Validates a bitrate such as "500k" or "2.5M" and writes it the way FFmpeg reads it
+/// Uses the same grammar as `parse_bitrate`, so anything config validation accepts works here
+fn normalize_bitrate(parameter: &str, bitrate: &str) -> Result<String> {
+    split_bitrate(bitrate)
+        .map(|(number, suffix)| format!("{}{}", number, suffix))
+        .ok_or_else(|| CompressError::invalid_parameter(parameter, bitrate))
+}
+
+/// Escapes a path for use as a single-quoted filter option value
+/// FFmpeg treats backslashes, colons and quotes specially inside filtergraphs
+pub fn escape_filter_path(path: &Path) -> String {
+    escape_filter_value(&path.to_string_lossy().replace('\\', "/"))
+}
+
+/// Escapes free text for use inside a single-quoted filter option value (`'...'`)
+/// FFmpeg unescapes twice: the option parser takes `\\`, `\:` and `\'`, and the
+/// filtergraph parser reads quoted text literally, so an apostrophe has to close the
+/// quote, follow as `\'` and reopen it
+pub fn escape_filter_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(':', "\\:")
+        .replace('\'', "\\'")
+        .replace('\'', "'\\''")
+}
+
 impl Default for FFmpegCommandBuilder {
     fn default() -> Self {
         Self::new()
@@ -184,9 +562,14 @@ pub struct FFprobeCommandBuilder {
 }
 
 impl FFprobeCommandBuilder {
-    /// Creates a new FFprobe command builder
+    /// Creates a new FFprobe command builder for the configured FFprobe binary
     pub fn new() -> Self {
-        let mut command = Command::new("ffprobe");
+        Self::with_binary(ffprobe_binary())
+    }
+
+    /// Creates a new FFprobe command builder that runs a specific FFprobe binary
+    pub fn with_binary<P: AsRef<Path>>(binary: P) -> Self {
+        let mut command = Command::new(binary.as_ref());
         command.stdout(Stdio::piped()).stderr(Stdio::piped());
         Self { command }
     }
@@ -211,7 +594,6 @@ impl FFprobeCommandBuilder {
     }
 
     /// Gets video metadata
-    #[allow(dead_code)]
     pub fn metadata(mut self) -> Self {
         self.command
             .arg("-v")
@@ -235,11 +617,60 @@ impl Default for FFprobeCommandBuilder {
     }
 }
 
+/// Gets media duration in seconds using FFprobe
+/// Returns None if FFprobe cannot determine the duration
+pub fn probe_duration(input: &Path) -> Result<Option<f64>> {
+    let mut command = FFprobeCommandBuilder::new()
+        .input(input)?
+        .duration()
+        .build();
+
+    let output = command.output().map_err(|e| {
+        CompressError::ffmpeg_error(
+            format!("Failed to run FFprobe: {}", e),
+            Some(format!("{:?}", command)),
+        )
+    })?;
+
+    if !output.status.success() {
+        warn!("FFprobe failed to get duration, continuing without progress tracking");
+        return Ok(None);
+    }
+
+    let duration_str = String::from_utf8_lossy(&output.stdout);
+    let duration: f64 = duration_str.trim().parse().map_err(|e| {
+        CompressError::progress_error(format!("Failed to parse video duration: {}", e))
+    })?;
+
+    Ok(Some(duration))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cli::args::{AudioCodec, VideoCodec};
 
+    #[test]
+    fn test_normalize_bitrate() {
+        assert_eq!(normalize_bitrate("bitrate", "1M").unwrap(), "1M");
+        assert_eq!(normalize_bitrate("bitrate", "2.5m").unwrap(), "2.5M");
+        assert_eq!(normalize_bitrate("bitrate", "500k").unwrap(), "500k");
+        assert_eq!(normalize_bitrate("bitrate", "128K").unwrap(), "128k");
+        assert_eq!(normalize_bitrate("bitrate", "800000").unwrap(), "800000");
+        assert_eq!(normalize_bitrate("bitrate", "1g").unwrap(), "1G");
+        assert_eq!(normalize_bitrate("bitrate", " 500k ").unwrap(), "500k");
+
+        for bad in ["abc", "1M2", "", "1M garbage", "k", "0k", "-5k"] {
+            assert!(normalize_bitrate("bitrate", bad).is_err(), "{:?}", bad);
+        }
+
+        assert!(
+            FFmpegCommandBuilder::new()
+                .audio_bitrate("128 kbps")
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_ffmpeg_command_builder() {
         let cmd = FFmpegCommandBuilder::new()
@@ -264,6 +695,236 @@ mod tests {
         assert!(cmd_str.contains("23"));
     }
 
+    #[test]
+    fn test_custom_binary_path() {
+        let ffmpeg = FFmpegCommandBuilder::with_binary("/opt/ffmpeg-7/bin/ffmpeg").build();
+        assert_eq!(ffmpeg.get_program(), "/opt/ffmpeg-7/bin/ffmpeg");
+
+        let ffprobe = FFprobeCommandBuilder::with_binary("/opt/ffmpeg-7/bin/ffprobe").build();
+        assert_eq!(ffprobe.get_program(), "/opt/ffmpeg-7/bin/ffprobe");
+
+        assert_eq!(
+            FFmpegCommandBuilder::new().build().get_program(),
+            ffmpeg_binary().as_os_str()
+        );
+    }
+
+    #[test]
+    fn test_video_filters_share_one_vf() {
+        let cmd = FFmpegCommandBuilder::new()
+            .input("input.mp4")
+            .unwrap()
+            .framerate(30.0)
+            .unwrap()
+            .output("output.mp4")
+            .unwrap()
+            .scale(Resolution::new(320, 180))
+            .build();
+
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args.iter().filter(|arg| *arg == "-vf").count(), 1);
+        assert_eq!(
+            args[args.len() - 3..],
+            ["-vf", "fps=30,scale=320:180", "output.mp4"]
+        );
+    }
+
+    #[test]
+    fn test_deterministic_flags() {
+        let cmd = FFmpegCommandBuilder::new()
+            .deterministic(&VideoCodec::H265)
+            .build();
+
+        let cmd_str = format!("{:?}", cmd);
+        assert!(cmd_str.contains("\"-threads\" \"1\""));
+        assert!(cmd_str.contains("\"-map_metadata\" \"-1\""));
+        assert!(cmd_str.contains("+bitexact"));
+        assert!(cmd_str.contains("frame-threads=1"));
+    }
+
+    #[test]
+    fn test_scene_cut_per_codec() {
+        let x264 = format!(
+            "{:?}",
+            FFmpegCommandBuilder::new()
+                .scene_cut(&VideoCodec::H264, 30)
+                .build()
+        );
+        assert!(x264.contains("\"-sc_threshold\" \"30\""));
+
+        let x265 = format!(
+            "{:?}",
+            FFmpegCommandBuilder::new()
+                .scene_cut(&VideoCodec::H265, 0)
+                .build()
+        );
+        assert!(x265.contains("\"-x265-params\" \"scenecut=0\""));
+
+        let vp9 = format!(
+            "{:?}",
+            FFmpegCommandBuilder::new()
+                .scene_cut(&VideoCodec::Vp9, 30)
+                .build()
+        );
+        assert!(!vp9.contains("sc_threshold") && !vp9.contains("scenecut"));
+    }
+
+    /// Mimics one level of FFmpeg's av_get_token: quoted text is literal, `\x` is x
+    fn unescape_level(value: &str) -> String {
+        let mut out = String::new();
+        let mut chars = value.chars();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => quoted = !quoted,
+                '\\' if !quoted => out.extend(chars.next()),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_bitrates_accepted_by_validate_build() {
+        for value in [
+            "2M",
+            "2.5m",
+            " 128k",
+            "1G ",
+            "800000",
+            "1M garbage",
+            "1e3",
+            "fast",
+        ] {
+            let mut config = crate::core::Config::default();
+            config.video_presets.get_mut("fast").unwrap().bitrate = Some(value.to_string());
+            let validated = config.validate().is_empty();
+
+            let built = FFmpegCommandBuilder::new().bitrate(value).is_ok();
+            assert_eq!(validated, built, "{:?}", value);
+            assert_eq!(
+                validated,
+                FFmpegCommandBuilder::new().audio_bitrate(value).is_ok(),
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_escape_filter_value() {
+        assert_eq!(escape_filter_value("a:b"), "a\\:b");
+        assert_eq!(escape_filter_value("a\\b"), "a\\\\b");
+        assert_eq!(escape_filter_value("it's"), "it\\'\\''s");
+
+        // Both FFmpeg parsing levels recover the original text
+        for text in ["it's", "12:30", "C:\\logo's\\", "'quoted'", "plain"] {
+            let quoted = format!("'{}'", escape_filter_value(text));
+            assert_eq!(unescape_level(&unescape_level(&quoted)), text, "{}", quoted);
+        }
+    }
+
+    #[test]
+    fn test_escape_filter_path() {
+        assert_eq!(
+            escape_filter_path(Path::new("C:\\Fonts\\it's.ttf")),
+            "C\\:/Fonts/it\\'\\''s.ttf"
+        );
+
+        let quoted = format!(
+            "'{}'",
+            escape_filter_path(Path::new("/brand/o'neil: logo.png"))
+        );
+        assert_eq!(
+            unescape_level(&unescape_level(&quoted)),
+            "/brand/o'neil: logo.png"
+        );
+    }
+
+    #[test]
+    fn test_metadata_source_follows_inputs() {
+        let cmd = FFmpegCommandBuilder::new()
+            .input("input.mp4")
+            .unwrap()
+            .metadata_source("chapters.mkv")
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", cmd);
+        assert!(cmd_str.contains("\"-i\" \"input.mp4\" \"-i\" \"chapters.mkv\""));
+        // All source streams are kept, not just the first video and audio track
+        assert!(cmd_str.contains("\"-map\" \"0:v\" \"-map\" \"0:a?\" \"-map\" \"0:s?\""));
+        assert!(!cmd_str.contains("0:a:0"));
+        assert!(cmd_str.contains("\"-map_metadata\" \"1\""));
+        assert!(cmd_str.contains("\"-map_chapters\" \"1\""));
+    }
+
+    #[test]
+    fn test_overlay_uses_filter_complex() {
+        let cmd = FFmpegCommandBuilder::new()
+            .input("input.mp4")
+            .unwrap()
+            .metadata_source("chapters.mkv")
+            .unwrap()
+            .overlay_input("logo.png")
+            .unwrap()
+            .video_filter("scale=1280:720")
+            .overlay("format=rgba", "10:10")
+            .unwrap()
+            .video_filter("fade=t=in:d=1")
+            .build();
+
+        let cmd_str = format!("{:?}", cmd);
+        assert!(cmd_str.contains("\"-i\" \"chapters.mkv\""));
+        assert!(cmd_str.contains(
+            "\"-filter_complex\" \"[0:v]scale=1280:720[base];[2:v]format=rgba[overlay];[base][overlay]overlay=10:10,fade=t=in:d=1[video]\""
+        ));
+        // The filtergraph output replaces the source video stream
+        assert!(cmd_str.contains("\"-map\" \"[video]\" \"-map\" \"0:a?\" \"-map\" \"0:s?\""));
+        assert!(!cmd_str.contains("0:v\""));
+        assert!(!cmd_str.contains("-vf"));
+
+        // An overlay needs its image input
+        assert!(
+            FFmpegCommandBuilder::new()
+                .overlay("format=rgba", "10:10")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_display_rotation_precedes_input() {
+        let cmd = FFmpegCommandBuilder::new()
+            .display_rotation(Rotation::Cw90, false)
+            .input("input.mp4")
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", cmd);
+        assert!(cmd_str.contains("\"-display_rotation\" \"-90\" \"-i\" \"input.mp4\""));
+        assert!(!cmd_str.contains("-autorotate"));
+
+        let cmd = FFmpegCommandBuilder::new()
+            .display_rotation(Rotation::Cw270, true)
+            .build();
+        assert!(format!("{:?}", cmd).contains("\"-autorotate\" \"0\""));
+    }
+
+    #[test]
+    fn test_input_format_precedes_input() {
+        let cmd = FFmpegCommandBuilder::new()
+            .input_format("h264")
+            .input("capture")
+            .unwrap()
+            .build();
+
+        let cmd_str = format!("{:?}", cmd);
+        assert!(cmd_str.contains("\"-f\" \"h264\" \"-i\" \"capture\""));
+    }
+
     #[test]
     fn test_invalid_crf() {
         let result = FFmpegCommandBuilder::new().crf(52);
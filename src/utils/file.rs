@@ -22,7 +22,6 @@ pub fn generate_output_path(
     extension: Option<&str>,
 ) -> PathBuf {
     let input_stem = input.file_stem().unwrap_or_default();
-    let input_extension = input.extension().unwrap_or_default();
 
     let mut filename = input_stem.to_string_lossy().to_string();
 
@@ -30,10 +29,14 @@ pub fn generate_output_path(
         filename.push_str(suffix);
     }
 
-    let final_extension = extension.unwrap_or_else(|| input_extension.to_str().unwrap_or("out"));
+    // Normalize to lowercase so outputs don't inherit mixed-case extensions like ".JPG"
+    let final_extension = extension
+        .map(str::to_lowercase)
+        .or_else(|| get_extension_lowercase(input))
+        .unwrap_or_else(|| "out".to_string());
 
     filename.push('.');
-    filename.push_str(final_extension);
+    filename.push_str(&final_extension);
 
     let output_dir = output_dir.unwrap_or_else(|| input.parent().unwrap_or(Path::new(".")));
     output_dir.join(filename)
@@ -58,18 +61,65 @@ pub fn validate_input_file<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
+/// Checks whether an output exists and is at least as new as its input
+/// Used for make-like incremental runs; missing timestamps count as stale
+pub fn is_output_fresh<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    match (modified(input.as_ref()), modified(output.as_ref())) {
+        (Some(input_time), Some(output_time)) => output_time >= input_time,
+        _ => false,
+    }
+}
+
+/// Checks for the `_compressed` suffix default output names carry
+/// (`clip_compressed.jpg`, or `clip_compressed_medium.mp4` for video presets)
+pub fn is_compressed_output<P: AsRef<Path>>(path: P) -> bool {
+    path.as_ref()
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .is_some_and(|stem| stem.ends_with("_compressed") || stem.contains("_compressed_"))
+}
+
 /// Checks if output file would overwrite existing file without permission
 /// Returns error if file exists and overwrite flag is not set
 pub fn check_output_overwrite<P: AsRef<Path>>(path: P, overwrite: bool) -> Result<()> {
     let path = path.as_ref();
 
-    if path.exists() && !overwrite {
+    // Special files like FIFOs "exist" by design and are written to as streams
+    if path.exists() && !overwrite && !is_special_file(path) {
         return Err(CompressError::file_exists(path));
     }
 
     Ok(())
 }
 
+/// Copies the original file to `<name>.bak` next to it before compressing
+/// An existing backup is kept, since it holds the source of an earlier in-place run
+pub fn backup_original<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
+    let path = path.as_ref();
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+
+    if !backup.exists() {
+        std::fs::copy(path, &backup)?;
+    }
+
+    Ok(backup)
+}
+
+/// Checks if a path is an existing special file (FIFO, device or socket)
+/// Output to such paths is streamed, so overwrite and size checks don't apply
+pub fn is_special_file<P: AsRef<Path>>(path: P) -> bool {
+    std::fs::metadata(path)
+        .map(|metadata| {
+            let file_type = metadata.file_type();
+            !file_type.is_file() && !file_type.is_dir()
+        })
+        .unwrap_or(false)
+}
+
 /// Gets list of supported video file extensions
 /// Returns the canonical list from constants, with both cases for compatibility
 pub fn get_video_extensions() -> Vec<&'static str> {
@@ -111,6 +161,8 @@ pub fn get_image_extensions() -> Vec<&'static str> {
             "tiff" => "TIFF",
             "tga" => "TGA",
             "gif" => "GIF",
+            "heic" => "HEIC",
+            "heif" => "HEIF",
             _ => ext,
         })
         .collect();
@@ -223,13 +275,78 @@ mod tests {
         assert_eq!(get_extension_lowercase("no_extension"), None);
     }
 
+    #[test]
+    fn test_generate_output_path_lowercases_extension() {
+        let output =
+            generate_output_path(Path::new("photos/IMG.JPG"), None, Some("_compressed"), None);
+        assert_eq!(output, Path::new("photos").join("IMG_compressed.jpg"));
+
+        let output = generate_output_path(Path::new("clip.MP4"), None, None, Some("MKV"));
+        assert_eq!(output.file_name().unwrap(), "clip.mkv");
+    }
+
+    #[test]
+    fn test_backup_original_copies_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("clip.mp4");
+        std::fs::write(&input, b"original bytes").unwrap();
+
+        let backup = backup_original(&input).unwrap();
+        assert_eq!(backup, dir.path().join("clip.mp4.bak"));
+        assert_eq!(std::fs::read(&backup).unwrap(), b"original bytes");
+
+        // A second run must not replace the first backup
+        std::fs::write(&input, b"compressed").unwrap();
+        backup_original(&input).unwrap();
+        assert_eq!(std::fs::read(&backup).unwrap(), b"original bytes");
+    }
+
+    #[test]
+    fn test_is_special_file() {
+        assert!(!is_special_file("Cargo.toml"));
+        assert!(!is_special_file("src"));
+        assert!(!is_special_file("does/not/exist"));
+
+        #[cfg(unix)]
+        assert!(is_special_file("/dev/null"));
+    }
+
     #[test]
     fn test_file_type_detection() {
         assert!(is_video_file("test.mp4"));
         assert!(is_video_file("test.MP4"));
         assert!(is_image_file("test.jpg"));
         assert!(is_image_file("test.PNG"));
+        assert!(is_image_file("IMG_0001.HEIC"));
         assert!(!is_video_file("test.txt"));
         assert!(!is_image_file("test.txt"));
     }
+
+    #[test]
+    fn test_is_output_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.jpg");
+        let output = dir.path().join("input_compressed.jpg");
+        std::fs::write(&input, b"input").unwrap();
+
+        assert!(!is_output_fresh(&input, &output));
+
+        let now = std::time::SystemTime::now();
+        let set_modified = |path: &Path, time| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap()
+        };
+
+        std::fs::write(&output, b"output").unwrap();
+        set_modified(&input, now - std::time::Duration::from_secs(60));
+        set_modified(&output, now);
+        assert!(is_output_fresh(&input, &output));
+
+        set_modified(&input, now + std::time::Duration::from_secs(60));
+        assert!(!is_output_fresh(&input, &output));
+    }
 }
@@ -0,0 +1,108 @@
+//! User-defined shell hooks run around each compressed file
+
+use crate::core::{CompressError, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Values available for substitution in hook commands
+pub struct HookContext<'a> {
+    pub input: &'a Path,
+    pub output: Option<&'a Path>,
+    pub input_size: Option<u64>,
+    pub output_size: Option<u64>,
+}
+
+/// Expands placeholders in a hook command
+/// Supports `{input}`, `{output}`, `{input_size}` and `{output_size}` (bytes);
+/// paths are shell-quoted so names with spaces stay a single argument
+pub fn expand_hook(template: &str, context: &HookContext) -> String {
+    let size = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let output = context.output.map(shell_quote).unwrap_or_default();
+
+    template
+        .replace("{input}", &shell_quote(context.input))
+        .replace("{output}", &output)
+        .replace("{input_size}", &size(context.input_size))
+        .replace("{output_size}", &size(context.output_size))
+}
+
+/// Runs a hook command through the platform shell
+/// Returns an error if the command can't be started or exits with a non-zero status
+pub fn run_hook(name: &str, template: &str, context: &HookContext) -> Result<()> {
+    let command_line = expand_hook(template, context);
+
+    let status = shell_command(&command_line).status().map_err(|e| {
+        CompressError::process_failed(format!("{} '{}': {}", name, command_line, e))
+    })?;
+
+    if !status.success() {
+        return Err(CompressError::process_failed(format!(
+            "{} '{}' exited with {}",
+            name, command_line, status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Builds a command that runs a command line through the platform shell
+fn shell_command(command_line: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    }
+    #[cfg(not(windows))]
+    {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command
+    }
+}
+
+/// Quotes a path for safe use in a shell command line
+fn shell_quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_hook() {
+        let context = HookContext {
+            input: Path::new("in file.jpg"),
+            output: Some(Path::new("out.jpg")),
+            input_size: Some(2048),
+            output_size: Some(1024),
+        };
+
+        let expanded = expand_hook("echo {input} {output} {input_size} {output_size}", &context);
+
+        #[cfg(not(windows))]
+        assert_eq!(expanded, "echo 'in file.jpg' 'out.jpg' 2048 1024");
+        #[cfg(windows)]
+        assert_eq!(expanded, "echo \"in file.jpg\" \"out.jpg\" 2048 1024");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_exit_status() {
+        let context = HookContext {
+            input: Path::new("input.mp4"),
+            output: None,
+            input_size: None,
+            output_size: None,
+        };
+
+        assert!(run_hook("post-hook", "true", &context).is_ok());
+        assert!(run_hook("post-hook", "exit 3", &context).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Reading and removal of image metadata
+
+use exif::{In, Reader, Tag};
+use image::metadata::Orientation;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// JPEG APPn/COM markers dropped by stripping: EXIF/XMP (APP1, which also carries the
+/// embedded thumbnail), ICC profile (APP2), Photoshop/IPTC (APP13) and comments
+const JPEG_STRIPPED_MARKERS: &[u8] = &[0xE1, 0xE2, 0xED, 0xFE];
+
+/// JPEG ICC profile marker (APP2), kept when only private metadata is stripped
+const JPEG_ICC_MARKER: u8 = 0xE2;
+
+/// PNG ancillary chunks dropped by stripping: ICC profile, EXIF, text and timestamp
+const PNG_STRIPPED_CHUNKS: &[&[u8; 4]] = &[b"iCCP", b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+/// PNG ICC profile chunk, kept when only private metadata is stripped
+const PNG_ICC_CHUNK: &[u8; 4] = b"iCCP";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Removes EXIF, XMP, ICC profiles, embedded thumbnails and comments from an encoded image
+/// Handles JPEG and PNG; other formats and malformed data are returned unchanged
+pub fn strip_image_metadata(data: &[u8]) -> Vec<u8> {
+    strip_metadata(data, false)
+}
+
+/// Removes EXIF (including GPS and thumbnails), XMP, IPTC and comments from an encoded
+/// image, keeping the ICC profile so colors display as intended
+pub fn strip_private_metadata(data: &[u8]) -> Vec<u8> {
+    strip_metadata(data, true)
+}
+
+/// Reads the EXIF orientation tag of an image file
+/// Returns None if the file has no EXIF data or the tag is missing or invalid
+pub fn read_exif_orientation(path: &Path) -> Option<Orientation> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let exif = Reader::new().read_from_container(&mut reader).ok()?;
+    let value = exif
+        .get_field(Tag::Orientation, In::PRIMARY)?
+        .value
+        .get_uint(0)?;
+    Orientation::from_exif(u8::try_from(value).ok()?)
+}
+
+fn strip_metadata(data: &[u8], keep_icc: bool) -> Vec<u8> {
+    let stripped = if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(data, keep_icc)
+    } else if data.starts_with(PNG_SIGNATURE) {
+        strip_png(data, keep_icc)
+    } else {
+        None
+    };
+
+    stripped.unwrap_or_else(|| data.to_vec())
+}
+
+/// Copies JPEG segments up to the image data, skipping metadata segments
+fn strip_jpeg(data: &[u8], keep_icc: bool) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+
+    while pos < data.len() {
+        if data[pos] != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+
+        // Fill bytes and standalone markers carry no length
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+            output.extend_from_slice(&data[pos..pos + 2]);
+            pos += 2;
+            continue;
+        }
+
+        // Entropy-coded data follows the start of scan; keep the rest as-is
+        if marker == 0xDA || marker == 0xD9 {
+            output.extend_from_slice(&data[pos..]);
+            return Some(output);
+        }
+
+        let length = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            return None;
+        }
+
+        if !JPEG_STRIPPED_MARKERS.contains(&marker) || (keep_icc && marker == JPEG_ICC_MARKER) {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(output)
+}
+
+/// Copies PNG chunks, skipping metadata chunks
+fn strip_png(data: &[u8], keep_icc: bool) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+
+    while pos < data.len() {
+        let length = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = data.get(pos + 4..pos + 8)?;
+        // Length, type, data and CRC
+        let end = pos.checked_add(12 + length)?;
+        if end > data.len() {
+            return None;
+        }
+
+        let stripped = PNG_STRIPPED_CHUNKS
+            .iter()
+            .any(|t| t.as_slice() == chunk_type);
+        if !stripped || (keep_icc && chunk_type == PNG_ICC_CHUNK) {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use std::io::Cursor;
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, image::Rgb([200, 40, 40])));
+        let mut data = Vec::new();
+        img.write_to(&mut Cursor::new(&mut data), format).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_strip_jpeg_exif() {
+        let jpeg = encode(ImageFormat::Jpeg);
+
+        // Insert an EXIF segment right after SOI
+        let exif_payload = b"Exif\0\0thumbnail-and-tags";
+        let mut with_exif = jpeg[..2].to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&((exif_payload.len() + 2) as u16).to_be_bytes());
+        with_exif.extend_from_slice(exif_payload);
+        with_exif.extend_from_slice(&jpeg[2..]);
+
+        let stripped = strip_image_metadata(&with_exif);
+        assert!(!stripped.windows(4).any(|w| w == b"Exif"));
+        assert_eq!(stripped, jpeg);
+        assert!(image::load_from_memory(&stripped).is_ok());
+    }
+
+    #[test]
+    fn test_strip_private_metadata_keeps_icc() {
+        let jpeg = encode(ImageFormat::Jpeg);
+        let segment = |marker: u8, payload: &[u8]| {
+            let mut segment = vec![0xFF, marker];
+            segment.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+            segment.extend_from_slice(payload);
+            segment
+        };
+        let icc = segment(0xE2, b"ICC_PROFILE\0\x01\x01profile");
+
+        let mut with_metadata = jpeg[..2].to_vec();
+        with_metadata.extend(segment(0xE1, b"Exif\0\0gps-tags"));
+        with_metadata.extend(&icc);
+        with_metadata.extend(segment(0xFE, b"comment"));
+        with_metadata.extend_from_slice(&jpeg[2..]);
+
+        let mut expected = jpeg[..2].to_vec();
+        expected.extend(&icc);
+        expected.extend_from_slice(&jpeg[2..]);
+
+        assert_eq!(strip_private_metadata(&with_metadata), expected);
+        assert_eq!(strip_image_metadata(&with_metadata), jpeg);
+    }
+
+    #[test]
+    fn test_strip_png_text_chunk() {
+        let png = encode(ImageFormat::Png);
+
+        // Insert a tEXt chunk after IHDR (8 byte signature + 25 byte IHDR chunk)
+        let mut with_text = png[..33].to_vec();
+        with_text.extend_from_slice(&7u32.to_be_bytes());
+        with_text.extend_from_slice(b"tEXtComment");
+        with_text.extend_from_slice(&[0; 4]);
+        with_text.extend_from_slice(&png[33..]);
+
+        assert_eq!(strip_image_metadata(&with_text), png);
+    }
+
+    #[test]
+    fn test_unknown_data_unchanged() {
+        let data = b"RIFF....WEBP".to_vec();
+        assert_eq!(strip_image_metadata(&data), data);
+    }
+}
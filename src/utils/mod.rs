@@ -4,22 +4,46 @@
 //! - `system`: System-related utilities (dependency checking, etc.)
 //! - `file`: File operations and validation
 //! - `parser`: Parsing utilities for various input formats
+//! - `hooks`: User-defined shell hooks around compression
 //! - `math`: Mathematical calculations
+//! - `metadata`: Stripping metadata from encoded images
+//! - `probe`: Media inspection via FFprobe
 
 pub mod command;
 pub mod file;
+pub mod hooks;
 pub mod math;
+pub mod metadata;
 pub mod parser;
+pub mod probe;
 pub mod progress;
 pub mod system;
 
-pub use command::{FFmpegCommandBuilder, FFprobeCommandBuilder};
+pub use command::{
+    FFmpegCommandBuilder, FFprobeCommandBuilder, escape_filter_path, escape_filter_value,
+    probe_duration,
+};
 pub use file::{
-    check_output_overwrite, ensure_parent_dir, generate_output_path, get_extension_lowercase,
-    get_file_size, get_image_extensions, get_video_extensions, is_image_file, is_video_file,
+    backup_original, check_output_overwrite, ensure_parent_dir, generate_output_path,
+    get_extension_lowercase, get_file_size, get_image_extensions, get_video_extensions,
+    is_compressed_output, is_image_file, is_output_fresh, is_special_file, is_video_file,
     quote_path, validate_input_file, validate_safe_path,
 };
+pub use hooks::{HookContext, expand_hook, run_hook};
 pub use math::calculate_compression_ratio;
-pub use parser::{parse_resolution, parse_time};
-pub use progress::{FFmpegProgressParser, ProgressManager, monitor_ffmpeg_progress};
-pub use system::{check_command_available, check_ffmpeg};
+pub use metadata::{read_exif_orientation, strip_image_metadata, strip_private_metadata};
+pub use parser::{
+    Resolution, parse_bitrate, parse_crop, parse_resolution, parse_time, split_bitrate,
+};
+pub use probe::{MediaInfo, probe_media};
+pub use progress::{
+    FFmpegProgressParser, ProgressManager, ascii_output, configure_output_style,
+    monitor_ffmpeg_pass, monitor_ffmpeg_progress, new_multi_progress, quiet_output,
+    set_plain_output, set_quiet_output,
+};
+pub use system::{
+    check_command_available, check_ffmpeg, check_ffmpeg_demuxer, check_ffmpeg_encoder,
+    check_ffmpeg_filter, check_ffmpeg_min_version, codec_availability, configure_ffmpeg_paths,
+    ffmpeg_binary, ffmpeg_encoders, ffprobe_binary, find_default_font, parse_ffmpeg_version,
+    supported_codec_encoders,
+};
@@ -2,14 +2,45 @@
 
 use crate::core::error::{CompressError, Result};
 
+/// Target frame size for scaling
+/// A missing axis is derived from the source aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl Resolution {
+    /// Creates a resolution with both axes fixed
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width: Some(width),
+            height: Some(height),
+        }
+    }
+
+    /// Returns (width, height) when both axes are fixed
+    pub fn dimensions(self) -> Option<(u32, u32)> {
+        Some((self.width?, self.height?))
+    }
+
+    /// Builds the FFmpeg scale filter; -2 keeps the aspect ratio with an even size
+    pub fn scale_filter(self) -> String {
+        let axis = |value: Option<u32>| value.map_or("-2".to_string(), |v| v.to_string());
+        format!("scale={}:{}", axis(self.width), axis(self.height))
+    }
+}
+
 /// Parses resolution string into width and height values
-/// Supports formats like "1920x1080", "720p", "1080p", "4K"
-/// Returns tuple of (width, height) in pixels
-pub fn parse_resolution(resolution: &str) -> Result<(u32, u32)> {
-    if let Some(height_str) = resolution.strip_suffix('p') {
-        let height: u32 = height_str
-            .parse()
-            .map_err(|_| CompressError::invalid_parameter("resolution", resolution))?;
+/// Supports formats like "1920x1080", "720p", "1080p", "4K", "FHD"
+/// Either side of "x" may be left out ("x720", "1280x") to keep the aspect ratio
+pub fn parse_resolution(resolution: &str) -> Result<Resolution> {
+    let invalid = || CompressError::invalid_parameter("resolution", resolution);
+
+    if let Some((width, height)) = named_resolution(resolution) {
+        Ok(Resolution::new(width, height))
+    } else if let Some(height_str) = resolution.strip_suffix('p') {
+        let height: u32 = height_str.parse().map_err(|_| invalid())?;
 
         // Map common resolution heights to their standard widths
         let width = match height {
@@ -20,35 +51,74 @@ pub fn parse_resolution(resolution: &str) -> Result<(u32, u32)> {
             1080 => 1920, // Full HD
             1440 => 2560, // QHD
             2160 => 3840, // 4K UHD
-            _ => return Err(CompressError::invalid_parameter("resolution", resolution)),
+            _ => return Err(invalid()),
         };
 
-        Ok((width, height))
-    } else if resolution.contains('x') {
-        // Parse "WIDTHxHEIGHT" format
-        let parts: Vec<&str> = resolution.split('x').collect();
-        if parts.len() != 2 {
-            return Err(CompressError::invalid_parameter("resolution", resolution));
-        }
+        Ok(Resolution::new(width, height))
+    } else if let Some((width, height)) = resolution.split_once('x') {
+        // Parse "WIDTHxHEIGHT" format, where one side may be empty
+        let axis = |value: &str| -> Result<Option<u32>> {
+            if value.is_empty() {
+                Ok(None)
+            } else {
+                value.parse().map(Some).map_err(|_| invalid())
+            }
+        };
 
-        let width: u32 = parts[0]
-            .parse()
-            .map_err(|_| CompressError::invalid_parameter("resolution", resolution))?;
-        let height: u32 = parts[1]
-            .parse()
-            .map_err(|_| CompressError::invalid_parameter("resolution", resolution))?;
+        let resolution = Resolution {
+            width: axis(width)?,
+            height: axis(height)?,
+        };
+        if resolution.width.is_none() && resolution.height.is_none() {
+            return Err(invalid());
+        }
 
-        Ok((width, height))
+        Ok(resolution)
     } else {
-        Err(CompressError::invalid_parameter("resolution", resolution))
+        Err(invalid())
+    }
+}
+
+/// Looks up a named resolution such as "4K" or "hd", ignoring case
+fn named_resolution(name: &str) -> Option<(u32, u32)> {
+    let dimensions = match name.to_uppercase().as_str() {
+        "HD" => (1280, 720),
+        "FHD" => (1920, 1080),
+        "2K" => (2048, 1080), // DCI 2K
+        "QHD" => (2560, 1440),
+        "4K" | "UHD" => (3840, 2160),
+        "8K" => (7680, 4320),
+        _ => return None,
+    };
+    Some(dimensions)
+}
+
+/// Parses a video crop rectangle in FFmpeg's "W:H:X:Y" order
+/// Returns tuple of (width, height, x, y) in pixels; width and height must be non-zero
+pub fn parse_crop(crop: &str) -> Result<(u32, u32, u32, u32)> {
+    let values: Vec<u32> = crop
+        .split(':')
+        .map(|part| part.trim().parse())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| CompressError::invalid_parameter("crop", crop))?;
+
+    match values[..] {
+        [width, height, x, y] if width > 0 && height > 0 => Ok((width, height, x, y)),
+        _ => Err(CompressError::invalid_parameter(
+            "crop",
+            format!("{} (expected W:H:X:Y with non-zero width and height)", crop),
+        )),
     }
 }
 
 /// Parses time string into seconds as floating point
-/// Supports formats: "90" (seconds), "1:30" (MM:SS), "01:30:45" (HH:MM:SS)
+/// Supports formats: "90" (seconds), "1:30" (MM:SS), "01:30:45" (HH:MM:SS),
+/// and unit-suffixed durations like "1h2m3.5s"
 /// Used for video trimming start/end times
 pub fn parse_time(time_str: &str) -> Result<f64> {
-    if time_str.contains(':') {
+    if time_str.ends_with(['h', 'm', 's']) {
+        parse_compact_duration(time_str)
+    } else if time_str.contains(':') {
         let parts: Vec<&str> = time_str.split(':').collect();
         match parts.len() {
             2 => {
@@ -84,16 +154,142 @@ pub fn parse_time(time_str: &str) -> Result<f64> {
     }
 }
 
+/// Parses a duration written with h/m/s units, e.g. "1h30m" or "2m30.5s"
+/// Each unit may appear once, largest first
+fn parse_compact_duration(time_str: &str) -> Result<f64> {
+    let invalid = || CompressError::invalid_parameter("time", time_str);
+
+    let mut total = 0.0;
+    let mut rest = time_str;
+    let mut units = [('h', 3600.0), ('m', 60.0), ('s', 1.0)].iter();
+
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(invalid)?;
+        let value: f64 = rest[..end].parse().map_err(|_| invalid())?;
+        let unit = rest[end..].chars().next().ok_or_else(invalid)?;
+
+        // Skipping ahead enforces the h, m, s order and rejects repeats
+        let (_, scale) = units.find(|(name, _)| *name == unit).ok_or_else(invalid)?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(invalid());
+        }
+
+        total += value * scale;
+        rest = &rest[end + 1..];
+    }
+
+    Ok(total)
+}
+
+/// Parses bitrate string into bits per second
+/// Supports plain numbers and K/M/G suffixes, e.g. "800", "128k", "2.5M"
+pub fn parse_bitrate(bitrate: &str) -> Result<u64> {
+    let (number, suffix) = split_bitrate(bitrate)
+        .ok_or_else(|| CompressError::invalid_parameter("bitrate", bitrate))?;
+    let multiplier = match suffix {
+        "k" => 1_000.0,
+        "M" => 1_000_000.0,
+        "G" => 1_000_000_000.0,
+        _ => 1.0,
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| CompressError::invalid_parameter("bitrate", bitrate))?;
+
+    Ok((value * multiplier) as u64)
+}
+
+/// Splits a bitrate into its number and suffix, the one grammar every bitrate goes through
+/// Accepts `<number>[k|K|m|M|g|G]` with optional surrounding whitespace, where the number
+/// is positive and written with digits and an optional decimal point. Suffixes come back
+/// as FFmpeg reads them: "k", "M" and "G", since a lowercase "m" means milli to FFmpeg
+pub fn split_bitrate(bitrate: &str) -> Option<(&str, &'static str)> {
+    let trimmed = bitrate.trim();
+    let (number, suffix) = match trimmed.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&trimmed[..i], "k"),
+        Some((i, 'm' | 'M')) => (&trimmed[..i], "M"),
+        Some((i, 'g' | 'G')) => (&trimmed[..i], "G"),
+        _ => (trimmed, ""),
+    };
+
+    let well_formed = number.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && number.parse::<f64>().is_ok_and(|value| value > 0.0);
+    well_formed.then_some((number, suffix))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_resolution() {
-        assert_eq!(parse_resolution("1920x1080").unwrap(), (1920, 1080));
-        assert_eq!(parse_resolution("720p").unwrap(), (1280, 720));
-        assert_eq!(parse_resolution("1080p").unwrap(), (1920, 1080));
+        assert_eq!(
+            parse_resolution("1920x1080").unwrap(),
+            Resolution::new(1920, 1080)
+        );
+        assert_eq!(
+            parse_resolution("720p").unwrap(),
+            Resolution::new(1280, 720)
+        );
+        assert_eq!(
+            parse_resolution("1080p").unwrap(),
+            Resolution::new(1920, 1080)
+        );
         assert!(parse_resolution("invalid").is_err());
+        assert!(parse_resolution("1920x1080x2").is_err());
+    }
+
+    #[test]
+    fn test_parse_single_axis_resolution() {
+        let height_only = parse_resolution("x720").unwrap();
+        assert_eq!(height_only.width, None);
+        assert_eq!(height_only.height, Some(720));
+        assert_eq!(height_only.scale_filter(), "scale=-2:720");
+
+        let width_only = parse_resolution("1280x").unwrap();
+        assert_eq!(width_only.scale_filter(), "scale=1280:-2");
+        assert_eq!(width_only.dimensions(), None);
+
+        let exact = parse_resolution("1280x720").unwrap();
+        assert_eq!(exact.scale_filter(), "scale=1280:720");
+        assert_eq!(exact.dimensions(), Some((1280, 720)));
+
+        assert!(parse_resolution("x").is_err());
+    }
+
+    #[test]
+    fn test_parse_named_resolution() {
+        assert_eq!(parse_resolution("4K").unwrap(), Resolution::new(3840, 2160));
+        assert_eq!(parse_resolution("hd").unwrap(), Resolution::new(1280, 720));
+        assert_eq!(
+            parse_resolution("Fhd").unwrap(),
+            Resolution::new(1920, 1080)
+        );
+        assert_eq!(parse_resolution("8k").unwrap(), Resolution::new(7680, 4320));
+        assert!(parse_resolution("5K").is_err());
+        assert!(parse_resolution("SD").is_err());
+    }
+
+    #[test]
+    fn test_parse_bitrate() {
+        assert_eq!(parse_bitrate("800").unwrap(), 800);
+        assert_eq!(parse_bitrate("128k").unwrap(), 128_000);
+        assert_eq!(parse_bitrate("2.5M").unwrap(), 2_500_000);
+        assert_eq!(parse_bitrate(" 1G ").unwrap(), 1_000_000_000);
+        assert!(parse_bitrate("fast").is_err());
+        assert!(parse_bitrate("0k").is_err());
+        assert!(parse_bitrate("1M garbage").is_err());
+        assert!(parse_bitrate("1e3").is_err());
+    }
+
+    #[test]
+    fn test_parse_crop() {
+        assert_eq!(parse_crop("1280:720:0:140").unwrap(), (1280, 720, 0, 140));
+        assert!(parse_crop("1280:720").is_err());
+        assert!(parse_crop("0:720:0:0").is_err());
+        assert!(parse_crop("1280:720:-5:0").is_err());
     }
 
     #[test]
@@ -103,4 +299,17 @@ mod tests {
         assert_eq!(parse_time("01:01:30").unwrap(), 3690.0);
         assert!(parse_time("invalid").is_err());
     }
+
+    #[test]
+    fn test_parse_compact_time() {
+        assert_eq!(parse_time("1h").unwrap(), 3600.0);
+        assert_eq!(parse_time("30m").unwrap(), 1800.0);
+        assert_eq!(parse_time("90s").unwrap(), 90.0);
+        assert_eq!(parse_time("1h2m3s").unwrap(), 3723.0);
+        assert_eq!(parse_time("2m30.5s").unwrap(), 150.5);
+        assert!(parse_time("1x").is_err());
+        assert!(parse_time("1s2m").is_err());
+        assert!(parse_time("1h30").is_err());
+        assert!(parse_time("m").is_err());
+    }
 }
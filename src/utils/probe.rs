@@ -0,0 +1,168 @@
+//! Media probing utilities built on FFprobe
+
+use crate::core::{CompressError, Result};
+use crate::utils::FFprobeCommandBuilder;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Basic properties of a media file as reported by FFprobe
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MediaInfo {
+    pub format_name: Option<String>,
+    pub duration: Option<f64>,
+    pub bit_rate: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub real_frame_rate: Option<f64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub field_order: Option<String>,
+    pub stream_count: usize,
+}
+
+impl MediaInfo {
+    /// Checks whether the video stream has a variable frame rate
+    /// FFprobe reports differing average and base (`r_frame_rate`) rates for VFR streams
+    pub fn is_variable_frame_rate(&self) -> bool {
+        match (self.frame_rate, self.real_frame_rate) {
+            (Some(avg), Some(real)) => (avg - real).abs() / real > 0.01,
+            _ => false,
+        }
+    }
+}
+
+/// Probes a media file with FFprobe and extracts its basic properties
+pub fn probe_media(input: &Path) -> Result<MediaInfo> {
+    let mut command = FFprobeCommandBuilder::new()
+        .metadata()
+        .input(input)?
+        .build();
+
+    let output = command.output().map_err(|e| {
+        CompressError::ffmpeg_error(
+            format!("Failed to run FFprobe: {}", e),
+            Some(format!("{:?}", command)),
+        )
+    })?;
+
+    if !output.status.success() {
+        return Err(CompressError::ffmpeg_error(
+            format!("FFprobe could not read {}", input.display()),
+            Some(format!("{:?}", command)),
+        ));
+    }
+
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    Ok(parse_media_info(&json))
+}
+
+/// Extracts media properties from FFprobe's JSON output
+fn parse_media_info(json: &Value) -> MediaInfo {
+    let format = &json["format"];
+    let streams = json["streams"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+
+    let video = streams.iter().find(|s| s["codec_type"] == "video");
+    let audio = streams.iter().find(|s| s["codec_type"] == "audio");
+
+    MediaInfo {
+        format_name: format["format_long_name"]
+            .as_str()
+            .or_else(|| format["format_name"].as_str())
+            .map(String::from),
+        duration: parse_number(&format["duration"]),
+        bit_rate: parse_number(&format["bit_rate"]).map(|b| b as u64),
+        width: video.and_then(|v| v["width"].as_u64()).map(|w| w as u32),
+        height: video.and_then(|v| v["height"].as_u64()).map(|h| h as u32),
+        frame_rate: video.and_then(|v| v["avg_frame_rate"].as_str().and_then(parse_rational)),
+        real_frame_rate: video.and_then(|v| v["r_frame_rate"].as_str().and_then(parse_rational)),
+        video_codec: video
+            .and_then(|v| v["codec_name"].as_str())
+            .map(String::from),
+        audio_codec: audio
+            .and_then(|a| a["codec_name"].as_str())
+            .map(String::from),
+        field_order: video
+            .and_then(|v| v["field_order"].as_str())
+            .filter(|order| *order != "unknown")
+            .map(String::from),
+        stream_count: streams.len(),
+    }
+}
+
+/// Parses a number that FFprobe may report as either a string or a number
+fn parse_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_f64(),
+        _ => None,
+    }
+}
+
+/// Parses an FFprobe rational like "30000/1001" into a float
+/// Returns None for undefined rates such as "0/0"
+pub fn parse_rational(value: &str) -> Option<f64> {
+    let (num, den) = value.split_once('/')?;
+    let num: f64 = num.parse().ok()?;
+    let den: f64 = den.parse().ok()?;
+    if den == 0.0 || num == 0.0 {
+        return None;
+    }
+    Some(num / den)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_media_info() {
+        let json: Value = serde_json::from_str(
+            r#"{
+                "streams": [
+                    {"codec_type": "video", "codec_name": "h264", "width": 1920, "height": 1080, "avg_frame_rate": "30000/1001", "r_frame_rate": "30000/1001", "field_order": "tt"},
+                    {"codec_type": "audio", "codec_name": "aac"}
+                ],
+                "format": {"format_name": "mov,mp4,m4a,3gp,3g2,mj2", "format_long_name": "QuickTime / MOV", "duration": "62.500000", "bit_rate": "4000000"}
+            }"#,
+        )
+        .unwrap();
+
+        let info = parse_media_info(&json);
+        assert_eq!(info.duration, Some(62.5));
+        assert_eq!(info.bit_rate, Some(4_000_000));
+        assert_eq!((info.width, info.height), (Some(1920), Some(1080)));
+        assert!((info.frame_rate.unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(info.video_codec.as_deref(), Some("h264"));
+        assert_eq!(info.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(info.field_order.as_deref(), Some("tt"));
+        assert_eq!(info.format_name.as_deref(), Some("QuickTime / MOV"));
+        assert_eq!(info.stream_count, 2);
+        assert!(!info.is_variable_frame_rate());
+    }
+
+    #[test]
+    fn test_variable_frame_rate_detection() {
+        let info = MediaInfo {
+            frame_rate: Some(23.7),
+            real_frame_rate: Some(60.0),
+            ..Default::default()
+        };
+        assert!(info.is_variable_frame_rate());
+
+        let info = MediaInfo {
+            frame_rate: Some(29.97),
+            real_frame_rate: Some(29.97),
+            ..Default::default()
+        };
+        assert!(!info.is_variable_frame_rate());
+    }
+
+    #[test]
+    fn test_parse_rational() {
+        assert_eq!(parse_rational("25/1"), Some(25.0));
+        assert_eq!(parse_rational("0/0"), None);
+        assert_eq!(parse_rational("invalid"), None);
+    }
+}
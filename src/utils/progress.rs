@@ -1,34 +1,195 @@
 //! Progress tracking utilities for compression operations
 
+use crate::cli::args::ProgressTheme;
 use crate::core::{
     CompressError, FFMPEG_PROGRESS_TIME_PATTERN, PROGRESS_UPDATE_INTERVAL_MS, Result,
 };
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::VecDeque;
 use std::io::{BufRead, BufReader};
 use std::process::Child;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
 use std::time::Duration;
 
+/// Number of trailing FFmpeg stderr lines kept for error reporting
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Stderr fragments that mean FFmpeg couldn't set up the requested encoder
+const ENCODER_FAILURE_PATTERNS: &[&str] = &[
+    "unknown encoder",
+    "encoder not found",
+    "error while opening encoder",
+    "could not open encoder",
+    "error initializing output stream",
+];
+
+/// Spinner frames used when output is restricted to ASCII
+const ASCII_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\", "|"];
+
+/// Template for file-count bars
+const FILE_PROGRESS_TEMPLATE: &str =
+    "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files processed";
+
+/// Template for duration-based compression bars
+const COMPRESSION_PROGRESS_TEMPLATE: &str =
+    "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {percent}% {msg}";
+
+/// Output style chosen at startup; Unicode with built-in templates until configured
+static OUTPUT_STYLE: OnceLock<OutputStyle> = OnceLock::new();
+
+/// Whether only errors should be printed; set from `--quiet`
+static QUIET_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Whether output goes somewhere that can't redraw bars, or `--no-color` was given
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+struct OutputStyle {
+    ascii: bool,
+    template: Option<String>,
+}
+
+/// Sets the progress bar and status symbol style for the rest of the run
+/// A custom template replaces the built-in bar templates and is validated up front
+pub fn configure_output_style(theme: ProgressTheme, template: Option<String>) -> Result<()> {
+    if let Some(template) = &template {
+        ProgressStyle::with_template(template)
+            .map_err(|e| CompressError::invalid_parameter("progress_template", e.to_string()))?;
+    }
+
+    let ascii = match theme {
+        ProgressTheme::Auto => !locale_is_utf8(),
+        ProgressTheme::Unicode => false,
+        ProgressTheme::Ascii => true,
+    };
+    let _ = OUTPUT_STYLE.set(OutputStyle { ascii, template });
+    Ok(())
+}
+
+/// Checks whether terminal output should avoid non-ASCII characters
+pub fn ascii_output() -> bool {
+    OUTPUT_STYLE.get().is_some_and(|style| style.ascii)
+}
+
+/// Enables or disables quiet mode for the rest of the run
+pub fn set_quiet_output(quiet: bool) {
+    QUIET_OUTPUT.store(quiet, Ordering::Relaxed);
+}
+
+/// Checks whether status messages and progress bars should be suppressed
+pub fn quiet_output() -> bool {
+    QUIET_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Enables or disables plain output (no progress bars) for the rest of the run
+pub fn set_plain_output(plain: bool) {
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+}
+
+/// Checks whether progress bars should be left out of plain output
+pub fn plain_output() -> bool {
+    PLAIN_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Checks whether progress bars should be hidden, by quiet mode or plain output
+fn bars_hidden() -> bool {
+    quiet_output() || plain_output()
+}
+
+/// Hides a progress bar in quiet mode or plain output; its position is still tracked
+fn hide_when_quiet(pb: &ProgressBar) {
+    if bars_hidden() {
+        pb.set_draw_target(ProgressDrawTarget::hidden());
+    }
+}
+
+/// Creates a display that draws several bars at once, hidden like single bars
+/// Used for parallel batch jobs: one bar per running file plus the overall count
+pub fn new_multi_progress() -> MultiProgress {
+    if bars_hidden() {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    } else {
+        MultiProgress::new()
+    }
+}
+
+/// Checks the locale for UTF-8 support; the first non-empty variable wins, as with setlocale
+fn locale_is_utf8() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty());
+
+    is_utf8_locale(locale.as_deref())
+}
+
+/// Checks a locale name such as "en_US.UTF-8" for a UTF-8 codeset
+fn is_utf8_locale(locale: Option<&str>) -> bool {
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_uppercase();
+            locale.contains("UTF-8") || locale.contains("UTF8")
+        }
+        // Windows terminals don't set a POSIX locale
+        None => cfg!(windows),
+    }
+}
+
+/// Builds a bar style from the configured template, or `default_template` if none is set
+fn bar_style(default_template: &str) -> ProgressStyle {
+    let template = OUTPUT_STYLE
+        .get()
+        .and_then(|style| style.template.as_deref())
+        .unwrap_or(default_template);
+
+    // Custom templates are validated in configure_output_style
+    let style = ProgressStyle::default_bar()
+        .template(template)
+        .unwrap()
+        .progress_chars("#>-");
+    if ascii_output() {
+        style.tick_strings(ASCII_SPINNER_FRAMES)
+    } else {
+        style
+    }
+}
+
+/// Builds the spinner style used when the total is unknown
+fn spinner_style() -> ProgressStyle {
+    let style = ProgressStyle::default_spinner()
+        .template("{spinner:.green} {msg}")
+        .unwrap();
+    if ascii_output() {
+        style.tick_strings(ASCII_SPINNER_FRAMES)
+    } else {
+        style
+    }
+}
+
 /// Manages progress tracking for compression operations
 pub struct ProgressManager {
     progress_bar: ProgressBar,
     total_duration: Option<f64>,
+    pass_offset: f64,
+    pass_scale: f64,
+    pass_label: Option<String>,
 }
 
 impl ProgressManager {
     /// Creates a new progress manager for file operations
     pub fn new_file_progress(total_files: usize) -> Self {
         let pb = ProgressBar::new(total_files as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files processed")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
+        pb.set_style(bar_style(FILE_PROGRESS_TEMPLATE));
+        hide_when_quiet(&pb);
         pb.enable_steady_tick(Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS));
 
         Self {
             progress_bar: pb,
             total_duration: None,
+            pass_offset: 0.0,
+            pass_scale: 1.0,
+            pass_label: None,
         }
     }
 
@@ -36,31 +197,32 @@ impl ProgressManager {
     pub fn new_compression_progress(duration: Option<f64>) -> Self {
         let pb = if let Some(duration) = duration {
             let pb = ProgressBar::new((duration * 1000.0) as u64); // Convert to milliseconds
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {percent}% {msg}")
-                    .unwrap()
-                    .progress_chars("#>-"),
-            );
+            pb.set_style(bar_style(COMPRESSION_PROGRESS_TEMPLATE));
             pb
         } else {
             let pb = ProgressBar::new_spinner();
-            pb.set_style(
-                ProgressStyle::default_spinner()
-                    .template("{spinner:.green} {msg}")
-                    .unwrap(),
-            );
+            pb.set_style(spinner_style());
             pb
         };
 
+        hide_when_quiet(&pb);
         pb.enable_steady_tick(Duration::from_millis(PROGRESS_UPDATE_INTERVAL_MS));
 
         Self {
             progress_bar: pb,
             total_duration: duration,
+            pass_offset: 0.0,
+            pass_scale: 1.0,
+            pass_label: None,
         }
     }
 
+    /// Moves the bar into a multi-progress display so it draws alongside other bars
+    pub fn add_to(mut self, multi: &MultiProgress) -> Self {
+        self.progress_bar = multi.add(self.progress_bar);
+        self
+    }
+
     /// Sets the progress message
     pub fn set_message(&self, message: &str) {
         self.progress_bar.set_message(message.to_string());
@@ -77,13 +239,25 @@ impl ProgressManager {
         self.progress_bar.set_position(pos);
     }
 
+    /// Maps subsequent updates into a sub-range of the bar
+    /// Used for multi-pass encodes so all passes share one 0-100% bar
+    pub fn set_pass_range(&mut self, offset: f64, scale: f64, label: &str) {
+        self.pass_offset = offset;
+        self.pass_scale = scale;
+        self.pass_label = Some(label.to_string());
+        self.set_message(label);
+    }
+
     /// Updates progress based on FFmpeg time output
     pub fn update_from_time(&self, time_ms: f64) {
         if let Some(total) = self.total_duration {
-            let progress = (time_ms / 1000.0 / total * 100.0).min(100.0);
+            let fraction = (time_ms / 1000.0 / total).clamp(0.0, 1.0);
+            let overall = self.pass_offset + self.pass_scale * fraction;
             self.progress_bar
-                .set_position((time_ms / 1000.0 * 1000.0) as u64);
-            self.set_message(&format!("Compressing... {:.1}%", progress));
+                .set_position((overall * total * 1000.0) as u64);
+
+            let label = self.pass_label.as_deref().unwrap_or("Compressing...");
+            self.set_message(&format!("{} {:.1}%", label, overall * 100.0));
         }
     }
 
@@ -112,6 +286,14 @@ impl FFmpegProgressParser {
         }
     }
 
+    /// Draws the bar inside a multi-progress display when one is given
+    pub fn in_multi_progress(mut self, multi: Option<&MultiProgress>) -> Self {
+        if let Some(multi) = multi {
+            self.progress_manager = self.progress_manager.add_to(multi);
+        }
+        self
+    }
+
     /// Parses a line of FFmpeg output and updates progress
     pub fn parse_line(&self, line: &str) -> Result<()> {
         if let Some(time_str) = line.strip_prefix(FFMPEG_PROGRESS_TIME_PATTERN) {
@@ -141,6 +323,14 @@ impl FFmpegProgressParser {
         self.progress_manager.set_message(message);
     }
 
+    /// Starts a new pass of a multi-pass encode (1-based)
+    /// Each pass fills an equal share of the same progress bar
+    pub fn start_pass(&mut self, pass: u32, total_passes: u32, message: &str) {
+        let scale = 1.0 / total_passes as f64;
+        let offset = (pass - 1) as f64 * scale;
+        self.progress_manager.set_pass_range(offset, scale, message);
+    }
+
     /// Finishes the progress tracking
     pub fn finish(self) {
         self.progress_manager.finish_and_clear();
@@ -154,7 +344,30 @@ impl FFmpegProgressParser {
 }
 
 /// Monitors FFmpeg process output and updates progress
-pub async fn monitor_ffmpeg_progress(mut child: Child, parser: FFmpegProgressParser) -> Result<()> {
+/// Finishes the progress bar once the process completes
+pub async fn monitor_ffmpeg_progress(child: Child, parser: FFmpegProgressParser) -> Result<()> {
+    monitor_ffmpeg_pass(child, &parser).await?;
+    parser.finish();
+    Ok(())
+}
+
+/// Monitors one FFmpeg process without finishing the progress bar
+/// Used for multi-pass encodes where the bar spans several processes
+pub async fn monitor_ffmpeg_pass(mut child: Child, parser: &FFmpegProgressParser) -> Result<()> {
+    // Drain stderr on its own thread so FFmpeg never blocks on a full pipe
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        thread::spawn(move || {
+            let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+            for line in BufReader::new(stderr).lines().map_while(|l| l.ok()) {
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            Vec::from(tail)
+        })
+    });
+
     if let Some(stdout) = child.stdout.take() {
         let reader = BufReader::new(stdout);
 
@@ -168,18 +381,55 @@ pub async fn monitor_ffmpeg_progress(mut child: Child, parser: FFmpegProgressPar
         CompressError::ffmpeg_error(format!("Failed to wait for FFmpeg process: {}", e), None)
     })?;
 
+    let stderr_tail = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
     if !status.success() {
-        return Err(CompressError::ffmpeg_error("FFmpeg process failed", None));
+        return Err(classify_ffmpeg_failure(&stderr_tail));
     }
 
-    parser.finish();
     Ok(())
 }
 
+/// Turns the tail of a failed FFmpeg run's stderr into an error
+/// Encoder setup failures become codec errors so callers can retry with another codec
+pub fn classify_ffmpeg_failure(stderr_tail: &[String]) -> CompressError {
+    let encoder_failure = stderr_tail.iter().find(|line| {
+        let line = line.to_lowercase();
+        ENCODER_FAILURE_PATTERNS
+            .iter()
+            .any(|pattern| line.contains(pattern))
+    });
+
+    if let Some(line) = encoder_failure {
+        return CompressError::codec_error(line.trim());
+    }
+
+    match stderr_tail
+        .iter()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+    {
+        Some(line) => {
+            CompressError::ffmpeg_error(format!("FFmpeg process failed: {}", line.trim()), None)
+        }
+        None => CompressError::ffmpeg_error("FFmpeg process failed", None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_utf8_locale_detection() {
+        assert!(is_utf8_locale(Some("en_US.UTF-8")));
+        assert!(is_utf8_locale(Some("C.utf8")));
+        assert!(!is_utf8_locale(Some("C")));
+        assert!(!is_utf8_locale(Some("POSIX")));
+    }
+
     #[test]
     fn test_progress_parser() {
         let parser = FFmpegProgressParser::new(Some(100.0));
@@ -197,6 +447,55 @@ mod tests {
         assert!(parser.parse_line("frame=100").is_ok());
     }
 
+    #[test]
+    fn test_pass_range_progress() {
+        let mut manager = ProgressManager::new_compression_progress(Some(100.0));
+
+        manager.set_pass_range(0.0, 0.5, "Pass 1/2");
+        manager.update_from_time(100_000.0);
+        assert_eq!(manager.progress_bar.position(), 50_000);
+
+        manager.set_pass_range(0.5, 0.5, "Pass 2/2");
+        manager.update_from_time(50_000.0);
+        assert_eq!(manager.progress_bar.position(), 75_000);
+    }
+
+    #[test]
+    fn test_classify_ffmpeg_failure() {
+        let stderr = vec![
+            "[libaom-av1 @ 0x55] Unknown encoder 'libaom-av1'".to_string(),
+            "Conversion failed!".to_string(),
+        ];
+        assert!(matches!(
+            classify_ffmpeg_failure(&stderr),
+            CompressError::CodecError { .. }
+        ));
+
+        let stderr = vec!["input.mp4: No such file or directory".to_string()];
+        match classify_ffmpeg_failure(&stderr) {
+            CompressError::FFmpegError { message, .. } => {
+                assert!(message.contains("No such file or directory"))
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_bars_join_multi_progress() {
+        let multi = MultiProgress::with_draw_target(ProgressDrawTarget::hidden());
+        let overall = ProgressManager::new_file_progress(4).add_to(&multi);
+        let worker = FFmpegProgressParser::new(Some(10.0)).in_multi_progress(Some(&multi));
+
+        overall.inc(1);
+        worker.parse_line("out_time_ms=5000000").unwrap();
+
+        // Both bars now draw through the multi-progress target
+        assert!(overall.progress_bar.is_hidden());
+        assert!(worker.progress_manager.progress_bar.is_hidden());
+        assert_eq!(overall.progress_bar.position(), 1);
+        assert_eq!(worker.progress_manager.progress_bar.position(), 5000);
+    }
+
     #[test]
     fn test_progress_manager_creation() {
         let _file_progress = ProgressManager::new_file_progress(10);
@@ -1,28 +1,309 @@
 //! System utilities for checking dependencies and system information
 
+use crate::cli::args::{AudioCodec, VideoCodec};
 use crate::core::error::{CompressError, Result};
+use crate::core::{DEFAULT_FONT_PATHS, FFMPEG_PATH_ENV, FFPROBE_PATH_ENV};
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
 use std::process::Command;
+use std::sync::OnceLock;
 
-/// Checks if a command is available in the system PATH
+/// FFmpeg and FFprobe binaries from the config file, set once at startup
+static CONFIGURED_BINARIES: OnceLock<ConfiguredBinaries> = OnceLock::new();
+
+/// Encoders of the FFmpeg build, listed once per run; None if FFmpeg couldn't be run
+static FFMPEG_ENCODERS: OnceLock<Option<Vec<String>>> = OnceLock::new();
+
+struct ConfiguredBinaries {
+    ffmpeg: Option<PathBuf>,
+    ffprobe: Option<PathBuf>,
+}
+
+/// Sets the FFmpeg and FFprobe binaries from the config for the rest of the run
+pub fn configure_ffmpeg_paths(ffmpeg: Option<PathBuf>, ffprobe: Option<PathBuf>) {
+    let _ = CONFIGURED_BINARIES.set(ConfiguredBinaries { ffmpeg, ffprobe });
+}
+
+/// The FFmpeg binary to run: $COMPRESSCLI_FFMPEG, then config ffmpeg_path, then the PATH
+pub fn ffmpeg_binary() -> PathBuf {
+    resolve_binary(
+        std::env::var_os(FFMPEG_PATH_ENV),
+        CONFIGURED_BINARIES.get().and_then(|b| b.ffmpeg.as_ref()),
+        "ffmpeg",
+    )
+}
+
+/// The FFprobe binary to run: $COMPRESSCLI_FFPROBE, then config ffprobe_path, then the PATH
+pub fn ffprobe_binary() -> PathBuf {
+    resolve_binary(
+        std::env::var_os(FFPROBE_PATH_ENV),
+        CONFIGURED_BINARIES.get().and_then(|b| b.ffprobe.as_ref()),
+        "ffprobe",
+    )
+}
+
+/// Picks a binary from the environment override, the configured path or the default name
+fn resolve_binary(env: Option<OsString>, configured: Option<&PathBuf>, default: &str) -> PathBuf {
+    env.filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| configured.cloned())
+        .unwrap_or_else(|| PathBuf::from(default))
+}
+
+/// Checks if a command is available in the system PATH (or, for a path, is executable)
 /// This is used to verify that external dependencies like FFmpeg are installed
-pub fn check_command_available(command: &str) -> bool {
+pub fn check_command_available<S: AsRef<OsStr>>(command: S) -> bool {
     which::which(command).is_ok()
 }
 
 /// Checks if FFmpeg is available and gets version information
 /// Returns the first line of FFmpeg version output or an error if not found
 pub fn check_ffmpeg() -> Result<String> {
-    if !check_command_available("ffmpeg") {
-        return Err(CompressError::missing_dependency("ffmpeg"));
+    let ffmpeg = ffmpeg_binary();
+    if !check_command_available(&ffmpeg) {
+        return Err(CompressError::missing_dependency(
+            ffmpeg.display().to_string(),
+        ));
     }
 
-    let output = Command::new("ffmpeg")
+    let output = Command::new(&ffmpeg)
         .arg("-version")
         .output()
-        .map_err(|_| CompressError::missing_dependency("ffmpeg"))?;
+        .map_err(|_| CompressError::missing_dependency(ffmpeg.display().to_string()))?;
 
     let version_info = String::from_utf8_lossy(&output.stdout);
     let first_line = version_info.lines().next().unwrap_or("Unknown version");
 
     Ok(first_line.to_string())
 }
+
+/// Parses the (major, minor) release from the first line of `ffmpeg -version`
+/// Git and dated snapshot builds (e.g. "N-113321-g4c8a9b1") carry no release number
+pub fn parse_ffmpeg_version(version_line: &str) -> Option<(u32, u32)> {
+    let version = version_line
+        .split_whitespace()
+        .skip_while(|word| *word != "version")
+        .nth(1)?;
+    // Release tags from git checkouts are prefixed with "n"
+    let version = version.strip_prefix('n').unwrap_or(version);
+
+    let (major, rest) = version.split_once('.')?;
+    let minor: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Checks that the installed FFmpeg is at least `major.minor`
+/// Builds without a release number are assumed new enough, since they're usually
+/// recent snapshots
+pub fn check_ffmpeg_min_version(major: u32, minor: u32) -> Result<()> {
+    let version_line = check_ffmpeg()?;
+
+    match parse_ffmpeg_version(&version_line) {
+        Some(found) if found < (major, minor) => Err(CompressError::missing_dependency(format!(
+            "FFmpeg {}.{} or newer (found {}.{})",
+            major, minor, found.0, found.1
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Checks if the installed FFmpeg provides a specific filter
+/// Used to detect optional filters like `drawtext` that depend on build flags
+pub fn check_ffmpeg_filter(filter: &str) -> bool {
+    let Ok(output) = Command::new(ffmpeg_binary())
+        .arg("-hide_banner")
+        .arg("-filters")
+        .output()
+    else {
+        return false;
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(filter))
+}
+
+/// Checks if the installed FFmpeg can read a given input format (demuxer)
+/// Demuxers may be listed with aliases, e.g. "mov,mp4,m4a,3gp,3g2,mj2"
+pub fn check_ffmpeg_demuxer(format: &str) -> bool {
+    let Ok(output) = Command::new(ffmpeg_binary())
+        .arg("-hide_banner")
+        .arg("-demuxers")
+        .output()
+    else {
+        return false;
+    };
+
+    String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+        line.split_whitespace()
+            .nth(1)
+            .is_some_and(|names| names.split(',').any(|name| name == format))
+    })
+}
+
+/// Checks if the installed FFmpeg provides an encoder, e.g. "libx265"
+pub fn check_ffmpeg_encoder(encoder: &str) -> bool {
+    ffmpeg_encoders().is_some_and(|encoders| encoders.iter().any(|name| name == encoder))
+}
+
+/// The codecs compresscli encodes to, paired with the FFmpeg encoder each one uses
+pub fn supported_codec_encoders() -> Vec<(&'static str, String)> {
+    vec![
+        ("H264", VideoCodec::H264.to_string()),
+        ("H265", VideoCodec::H265.to_string()),
+        ("VP9", VideoCodec::Vp9.to_string()),
+        ("AV1", VideoCodec::Av1.to_string()),
+        ("AAC", AudioCodec::Aac.to_string()),
+        ("MP3", AudioCodec::Mp3.to_string()),
+        ("Opus", AudioCodec::Opus.to_string()),
+    ]
+}
+
+/// Checks which supported codecs have their encoder in `encoders`
+/// Returns (codec, encoder, available) for each codec
+pub fn codec_availability(encoders: &[String]) -> Vec<(&'static str, String, bool)> {
+    supported_codec_encoders()
+        .into_iter()
+        .map(|(codec, encoder)| {
+            let available = encoders.contains(&encoder);
+            (codec, encoder, available)
+        })
+        .collect()
+}
+
+/// Lists the encoders the installed FFmpeg provides, running `ffmpeg -encoders` only once
+/// Returns None if FFmpeg couldn't be run
+pub fn ffmpeg_encoders() -> Option<&'static [String]> {
+    FFMPEG_ENCODERS
+        .get_or_init(|| {
+            let output = Command::new(ffmpeg_binary())
+                .arg("-hide_banner")
+                .arg("-encoders")
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| parse_ffmpeg_encoders(&String::from_utf8_lossy(&output.stdout)))
+        })
+        .as_deref()
+}
+
+/// Parses encoder names from `ffmpeg -encoders` output
+/// Entries are capability flags followed by the name, below a legend ending in `------`
+pub fn parse_ffmpeg_encoders(output: &str) -> Vec<String> {
+    let lines: Vec<&str> = output.lines().collect();
+    let entries = match lines.iter().position(|line| line.trim() == "------") {
+        Some(legend_end) => &lines[legend_end + 1..],
+        None => &lines[..],
+    };
+
+    entries
+        .iter()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(String::from)
+        .collect()
+}
+
+/// Finds a usable default font file for text overlays
+/// Returns the first existing path from the platform-specific candidates
+pub fn find_default_font() -> Option<PathBuf> {
+    DEFAULT_FONT_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ffmpeg_version() {
+        let cases = [
+            (
+                "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers",
+                Some((6, 1)),
+            ),
+            (
+                "ffmpeg version 4.4.2-0ubuntu0.22.04.1 Copyright (c) 2000-2021 the FFmpeg developers",
+                Some((4, 4)),
+            ),
+            (
+                "ffmpeg version 7.1-full_build-www.gyan.dev Copyright (c) 2000-2024 the FFmpeg developers",
+                Some((7, 1)),
+            ),
+            (
+                "ffmpeg version n7.0.2 Copyright (c) 2000-2024 the FFmpeg developers",
+                Some((7, 0)),
+            ),
+            (
+                "ffmpeg version 5.1.6-0+deb12u1 Copyright (c) 2000-2024 the FFmpeg developers",
+                Some((5, 1)),
+            ),
+            (
+                "ffmpeg version N-113321-g4c8a9b1c2d-20240101 Copyright (c) 2000-2024 the FFmpeg developers",
+                None,
+            ),
+            (
+                "ffmpeg version 2024-03-14-git-a8b2c3d4e5-essentials_build-www.gyan.dev Copyright (c) 2000-2024",
+                None,
+            ),
+            ("Unknown version", None),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(parse_ffmpeg_version(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_codec_availability() {
+        let encoders: Vec<String> = ["libx264", "libvpx-vp9", "aac", "libopus", "mjpeg"]
+            .map(String::from)
+            .to_vec();
+
+        let availability = codec_availability(&encoders);
+        let find = |codec: &str| {
+            availability
+                .iter()
+                .find(|(name, _, _)| *name == codec)
+                .unwrap()
+        };
+
+        assert_eq!(availability.len(), 7);
+        assert_eq!(find("H264"), &("H264", "libx264".to_string(), true));
+        assert_eq!(find("H265"), &("H265", "libx265".to_string(), false));
+        assert!(find("VP9").2);
+        assert_eq!(find("AV1"), &("AV1", "libaom-av1".to_string(), false));
+        assert!(find("AAC").2);
+        assert_eq!(find("MP3"), &("MP3", "libmp3lame".to_string(), false));
+        assert!(find("Opus").2);
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_encoders() {
+        let output = "Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ V....D libvpx-vp9           libvpx VP9 (codec vp9)
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+        let encoders = parse_ffmpeg_encoders(output);
+        assert_eq!(encoders, ["libx264", "libvpx-vp9", "aac"]);
+        assert!(encoders.iter().any(|name| name == "libx264"));
+        assert!(!encoders.iter().any(|name| name == "libx265"));
+
+        let with_x265 = format!(
+            "{} V....D libx265              libx265 H.265 / HEVC\n",
+            output
+        );
+        assert!(
+            parse_ffmpeg_encoders(&with_x265)
+                .iter()
+                .any(|name| name == "libx265")
+        );
+    }
+}